@@ -0,0 +1,354 @@
+// Copyright 2025 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+use std::{
+    fs,
+    net::TcpListener,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use mock_device::{
+    mock_config::MockConfig,
+    mock_data::MockData,
+    mock_web_socket_server::{MockWebSocketServer, WsServerParameters},
+};
+use ripple_main::{
+    bootstrap::boot::boot,
+    state::{
+        bootstrap_state::BootstrapState, openrpc_state::set_firebolt_open_rpc,
+        platform_state::PlatformState,
+    },
+};
+use ripple_sdk::{
+    api::manifest::{
+        device_manifest::{AppLibraryEntry, DeviceManifest},
+        extn_manifest::ExtnManifest,
+    },
+    framework::RippleResponse,
+    tokio::{self, net::TcpStream, task::JoinHandle, time::sleep},
+};
+use ripple_tdk::harness::{errors::HarnessError, firebolt_client::FireboltClient};
+use serde_json::{json, Value};
+
+/// Time the gateway gets to start accepting connections.
+pub const BOOT_TIMEOUT: Duration = Duration::from_secs(10);
+
+static NEXT_GATEWAY: AtomicU64 = AtomicU64::new(0);
+
+/*
+Boots the Firebolt gateway in process. The manifests default to the examples without their
+extensions, so only the rules and the built in handlers answer. With mock data a mock device is
+started and the Thunder endpoint of every rules file points to it. Both websocket gateways listen
+on free ports so tests can run in parallel.
+*/
+pub struct TestGatewayBuilder {
+    device_manifest: DeviceManifest,
+    extn_manifest: ExtnManifest,
+    rules: Vec<Value>,
+    mock_data: Option<MockData>,
+    app_library: Vec<AppLibraryEntry>,
+}
+
+pub struct TestGateway {
+    platform_state: PlatformState,
+    internal_gateway: String,
+    mock_device: Option<Arc<MockWebSocketServer>>,
+    tasks: Vec<JoinHandle<()>>,
+    boot: JoinHandle<RippleResponse>,
+    dir: PathBuf,
+}
+
+impl Default for TestGatewayBuilder {
+    fn default() -> Self {
+        let (_, device_manifest) = DeviceManifest::load_from_content(
+            include_str!("../../../examples/manifest/device-manifest-example.json").to_owned(),
+        )
+        .expect("example device manifest");
+        let (_, mut extn_manifest) = ExtnManifest::load_from_content(
+            include_str!("../../../examples/manifest/extn-manifest-example.json").to_owned(),
+        )
+        .expect("example extn manifest");
+        extn_manifest.extns.clear();
+        extn_manifest.rules_path.clear();
+        Self {
+            device_manifest,
+            extn_manifest,
+            rules: Vec::new(),
+            mock_data: None,
+            app_library: Vec::new(),
+        }
+    }
+}
+
+impl TestGatewayBuilder {
+    pub fn device_manifest(mut self, manifest: DeviceManifest) -> Self {
+        self.device_manifest = manifest;
+        self
+    }
+
+    /// Extension manifest, the files in its `rules_path` are loaded along with the added rules.
+    pub fn extn_manifest(mut self, manifest: ExtnManifest) -> Self {
+        self.extn_manifest = manifest;
+        self
+    }
+
+    /// Adds a rules file given as its content, e.g. `{"endpoints": {..}, "rules": {..}}`.
+    pub fn rules(mut self, rules: Value) -> Self {
+        self.rules.push(rules);
+        self
+    }
+
+    /// Mock device data in the format of `mock-device.json`.
+    pub fn mock_data(mut self, mock_data: MockData) -> Self {
+        self.mock_data = Some(mock_data);
+        self
+    }
+
+    pub fn app_library(mut self, app_library: Vec<AppLibraryEntry>) -> Self {
+        self.app_library = app_library;
+        self
+    }
+
+    pub async fn start(self) -> Result<TestGateway, HarnessError> {
+        let dir = std::env::temp_dir().join(format!(
+            "ripple-tdk-{}-{}",
+            std::process::id(),
+            NEXT_GATEWAY.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir_all(&dir).map_err(|e| HarnessError::BootFailed(e.to_string()))?;
+        let mut device_manifest = self.device_manifest;
+        let mut extn_manifest = self.extn_manifest;
+        let mut tasks = Vec::new();
+
+        let mock_device = match self.mock_data {
+            Some(mock_data) => {
+                let server = start_mock_device(mock_data, &dir).await?;
+                tasks.push(tokio::spawn(server.clone().start_server()));
+                Some(server)
+            }
+            None => None,
+        };
+        let thunder_url = mock_device
+            .as_ref()
+            .map(|server| format!("ws://127.0.0.1:{}/jsonrpc", server.port()));
+        if let Some(url) = &thunder_url {
+            device_manifest.configuration.platform_parameters["gateway"] = json!(url);
+        }
+
+        let mut rules = Vec::new();
+        for path in &extn_manifest.rules_path {
+            let path = if path.starts_with('/') {
+                path.clone()
+            } else {
+                format!("{}{}", extn_manifest.default_path, path)
+            };
+            let content = fs::read_to_string(&path)
+                .map_err(|e| HarnessError::BootFailed(format!("{}: {}", path, e)))?;
+            rules.push(
+                serde_json::from_str::<Value>(&content)
+                    .map_err(|e| HarnessError::BootFailed(format!("{}: {}", path, e)))?,
+            );
+        }
+        rules.extend(self.rules);
+        extn_manifest.rules_path = Vec::new();
+        for (i, mut rules) in rules.into_iter().enumerate() {
+            if let (Some(url), Some(thunder)) =
+                (&thunder_url, rules.pointer_mut("/endpoints/thunder"))
+            {
+                thunder["url"] = json!(url);
+            }
+            let path = dir.join(format!("rules-{}.json", i));
+            fs::write(&path, rules.to_string())
+                .map_err(|e| HarnessError::BootFailed(e.to_string()))?;
+            extn_manifest
+                .rules_path
+                .push(path.to_string_lossy().into_owned());
+        }
+
+        let configuration = &mut device_manifest.configuration;
+        configuration.ws_configuration.gateway = free_address()?;
+        configuration.internal_ws_configuration.gateway = free_address()?;
+        configuration.internal_ws_configuration.enabled = true;
+        let internal_gateway = configuration.internal_ws_configuration.gateway.clone();
+
+        // the open rpc in /etc is not there on a build machine, use the one compiled in
+        set_firebolt_open_rpc(
+            include_str!("../../../openrpc_validator/src/test/firebolt-open-rpc.json").to_owned(),
+        );
+        let state =
+            BootstrapState::from_manifests(extn_manifest, device_manifest, self.app_library, None);
+        let platform_state = state.platform_state.clone();
+        let boot = tokio::spawn(boot(state));
+
+        let gateway = TestGateway {
+            platform_state,
+            internal_gateway,
+            mock_device,
+            tasks,
+            boot,
+            dir,
+        };
+        gateway.wait_for_boot().await?;
+        Ok(gateway)
+    }
+}
+
+impl TestGateway {
+    pub fn builder() -> TestGatewayBuilder {
+        TestGatewayBuilder::default()
+    }
+
+    /// Connects to the internal gateway as the given app.
+    pub async fn connect(&self, app_id: &str) -> Result<FireboltClient, HarnessError> {
+        FireboltClient::connect(&format!("ws://{}", self.internal_gateway), app_id).await
+    }
+
+    pub fn get_platform_state(&self) -> &PlatformState {
+        &self.platform_state
+    }
+
+    /// The mock device answering the Thunder calls, if the gateway was built with mock data.
+    pub fn get_mock_device(&self) -> Option<Arc<MockWebSocketServer>> {
+        self.mock_device.clone()
+    }
+
+    async fn wait_for_boot(&self) -> Result<(), HarnessError> {
+        let started = Instant::now();
+        while started.elapsed() < BOOT_TIMEOUT {
+            if self.boot.is_finished() {
+                return Err(HarnessError::BootFailed("boot exited".to_owned()));
+            }
+            if TcpStream::connect(&self.internal_gateway).await.is_ok() {
+                return Ok(());
+            }
+            sleep(Duration::from_millis(20)).await;
+        }
+        Err(HarnessError::Timeout)
+    }
+}
+
+impl Drop for TestGateway {
+    fn drop(&mut self) {
+        self.boot.abort();
+        for task in &self.tasks {
+            task.abort();
+        }
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+async fn start_mock_device(
+    mock_data: MockData,
+    dir: &std::path::Path,
+) -> Result<Arc<MockWebSocketServer>, HarnessError> {
+    let mut server_config = WsServerParameters::new();
+    server_config.port(0).path("/jsonrpc");
+    let config = MockConfig {
+        stats_file: dir.join("stats.json").to_string_lossy().into_owned(),
+        ..Default::default()
+    };
+    let server = MockWebSocketServer::new(mock_data, server_config, config)
+        .await
+        .map_err(|e| HarnessError::BootFailed(e.to_string()))?;
+    Ok(server.into_arc())
+}
+
+fn free_address() -> Result<String, HarnessError> {
+    let listener =
+        TcpListener::bind("127.0.0.1:0").map_err(|e| HarnessError::BootFailed(e.to_string()))?;
+    let address = listener
+        .local_addr()
+        .map_err(|e| HarnessError::BootFailed(e.to_string()))?;
+    Ok(address.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ripple_tdk::harness::{
+        assertions::{assert_method_not_found, assert_rpc_result},
+        firebolt_client::DEFAULT_TIMEOUT,
+    };
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_rules_answered_by_mock_device() {
+        let mock_data: MockData = serde_json::from_value(json!({
+            "org.rdk.System.1.getFriendlyName": [
+                {"params": {}, "match": "partial", "result": {"friendlyName": "Kitchen", "success": true}}
+            ]
+        }))
+        .unwrap();
+        let gateway = TestGateway::builder()
+            .rules(json!({
+                "endpoints": {"thunder": {"protocol": "thunder", "url": "ws://127.0.0.1:9998/jsonrpc"}},
+                "rules": {
+                    "device.name": {
+                        "alias": "org.rdk.System.1.getFriendlyName",
+                        "transform": {"response": ".result.friendlyName"}
+                    }
+                }
+            }))
+            .mock_data(mock_data)
+            .start()
+            .await
+            .unwrap();
+
+        let client = gateway.connect("test_app").await.unwrap();
+        let response = client.call("device.name", None).await.unwrap();
+        assert_rpc_result(&response, &json!("Kitchen"));
+        assert_method_not_found(&client.call("device.unknown", None).await.unwrap());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_event_from_mock_device() {
+        let mock_data: MockData = serde_json::from_value(json!({
+            "org.rdk.System.register": [
+                {
+                    "params": {"event": "onFriendlyNameChanged"},
+                    "match": "partial",
+                    "result": 0,
+                    "events": [{"delay": 0, "data": {"friendlyName": "Den"}}]
+                }
+            ]
+        }))
+        .unwrap();
+        let gateway = TestGateway::builder()
+            .rules(json!({
+                "endpoints": {"thunder": {"protocol": "thunder", "url": "ws://127.0.0.1:9998/jsonrpc"}},
+                "rules": {
+                    "device.onNameChanged": {
+                        "alias": "org.rdk.System.onFriendlyNameChanged",
+                        "transform": {"event": ".friendlyName"}
+                    }
+                }
+            }))
+            .mock_data(mock_data)
+            .start()
+            .await
+            .unwrap();
+
+        let client = gateway.connect("test_app").await.unwrap();
+        let mut subscription = client.subscribe("device.onNameChanged").await.unwrap();
+        let name: String = subscription.next_event_as(DEFAULT_TIMEOUT).await.unwrap();
+        assert_eq!(name, "Den");
+    }
+}
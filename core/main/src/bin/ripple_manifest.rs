@@ -0,0 +1,140 @@
+// Copyright 2025 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+/*
+Resolves the cascaded manifests for a country, platform or tag the same way Ripple does at boot
+and prints the merged device and extension manifests. Any manifest which cannot be loaded fails
+the run with the file, line and column of the error.
+*/
+
+use std::process::exit;
+
+use ripple_sdk::{
+    api::manifest::ripple_manifest_loader::{ManifestLoadError, RippleManifestLoader},
+    serde_json::{self, json, Value},
+};
+
+const USAGE: &str = "usage: ripple_manifest [--base-path <dir>] [--country <code>] \
+[--platform <type>] [--tag <tag>] [--provenance]";
+
+const DEFAULT_BASE_PATH: &str = "/etc/ripple/rdke";
+
+#[derive(Debug, Default)]
+struct Args {
+    base_path: String,
+    country: Option<String>,
+    platform: Option<String>,
+    tag: Option<String>,
+    provenance: bool,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut parsed = Args {
+        base_path: DEFAULT_BASE_PATH.to_owned(),
+        ..Default::default()
+    };
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value for {}", arg));
+        match arg.as_str() {
+            "--base-path" => parsed.base_path = value()?,
+            "--country" => parsed.country = Some(value()?),
+            "--platform" => parsed.platform = Some(value()?),
+            "--tag" => parsed.tag = Some(value()?),
+            "--provenance" => parsed.provenance = true,
+            other => return Err(format!("unknown argument {}", other)),
+        }
+    }
+    Ok(parsed)
+}
+
+fn resolve(args: &Args) -> Result<Value, ManifestLoadError> {
+    let loader = RippleManifestLoader::for_target(
+        args.base_path.clone(),
+        args.country.clone(),
+        args.platform.clone(),
+    )?
+    .get_config_loader()
+    .with_tag(args.tag.clone());
+    let device = loader.inspect_device_manifest()?;
+    let extn = loader.inspect_extn_manifest()?;
+
+    let mut output = json!({
+        "device_manifest": device.manifest,
+        "extn_manifest": extn.manifest,
+    });
+    if args.provenance {
+        output["provenance"] = json!({
+            "device_manifest": device.provenance,
+            "extn_manifest": extn.provenance,
+        });
+    } else {
+        output["sources"] = json!({
+            "device_manifest": device.sources,
+            "extn_manifest": extn.sources,
+        });
+    }
+    Ok(output)
+}
+
+fn main() {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}\n{}", e, USAGE);
+            exit(exitcode::USAGE);
+        }
+    };
+    match resolve(&args) {
+        Ok(output) => println!(
+            "{}",
+            serde_json::to_string_pretty(&output).unwrap_or_default()
+        ),
+        Err(e) => {
+            eprintln!("{}", e);
+            exit(exitcode::DATAERR);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Result<Args, String> {
+        parse_args(list.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let parsed = args(&["--country", "us", "--platform", "tv", "--provenance"]).unwrap();
+        assert_eq!(parsed.base_path, DEFAULT_BASE_PATH);
+        assert_eq!(parsed.country, Some("us".to_owned()));
+        assert_eq!(parsed.platform, Some("tv".to_owned()));
+        assert!(parsed.tag.is_none());
+        assert!(parsed.provenance);
+
+        assert!(args(&["--tag"]).is_err());
+        assert!(args(&["manifest.json"]).is_err());
+    }
+
+    #[test]
+    fn test_resolve_missing_config() {
+        let parsed = args(&["--base-path", "/nonexistent"]).unwrap();
+        let error = resolve(&parsed).unwrap_err();
+        assert_eq!(error.path, "/nonexistent/ripple.config.json");
+    }
+}
@@ -0,0 +1,286 @@
+// Copyright 2025 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+/*
+Offline linter for rules files. Loads the rules files the same way Ripple does at boot,
+reports every problem found and optionally runs sample payloads through a single rule.
+*/
+
+use std::{fs, process::exit};
+
+use ripple_main::broker::rules::rules_engine::{
+    CompiledFilter, Rule, RuleEngine, RuleLoadError, RuleTransformType, RuleType, RuleVariables,
+};
+use ripple_sdk::{api::manifest::extn_manifest::ExtnManifest, serde_json::Value};
+
+const USAGE: &str = "usage: ripple_rules [--default-path <dir>] <rules.json>... \
+[--rule <method> [--app-id <id>] [--request <json|@file>] [--response <json|@file>] [--event <json|@file>]]";
+
+const FUNCTION_PREFIX: &str = "$function.";
+
+#[derive(Debug, Default)]
+struct Args {
+    default_path: String,
+    files: Vec<String>,
+    rule: Option<String>,
+    app_id: String,
+    request: Option<Value>,
+    response: Option<Value>,
+    event: Option<Value>,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut parsed = Args {
+        app_id: "ripple_rules".to_owned(),
+        ..Default::default()
+    };
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value for {}", arg));
+        match arg.as_str() {
+            "--default-path" => parsed.default_path = value()?,
+            "--rule" => parsed.rule = Some(value()?),
+            "--app-id" => parsed.app_id = value()?,
+            "--request" => parsed.request = Some(read_json(&value()?)?),
+            "--response" => parsed.response = Some(read_json(&value()?)?),
+            "--event" => parsed.event = Some(read_json(&value()?)?),
+            flag if flag.starts_with("--") => return Err(format!("unknown option {}", flag)),
+            _ => parsed.files.push(arg),
+        }
+    }
+    if parsed.files.is_empty() {
+        return Err("no rules file given".to_owned());
+    }
+    if parsed.rule.is_none()
+        && (parsed.request.is_some() || parsed.response.is_some() || parsed.event.is_some())
+    {
+        return Err("--request, --response and --event need --rule".to_owned());
+    }
+    Ok(parsed)
+}
+
+/// Reads a json payload given inline or as @path to a file.
+fn read_json(arg: &str) -> Result<Value, String> {
+    let contents = match arg.strip_prefix('@') {
+        Some(path) => fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?,
+        None => arg.to_owned(),
+    };
+    ripple_sdk::serde_json::from_str(&contents).map_err(|e| format!("invalid json {}: {}", arg, e))
+}
+
+/// Returns the problems found in a single rule, functions are expected to be expanded already.
+fn lint_rule(name: &str, rule: &Rule, engine: &RuleEngine) -> Vec<String> {
+    let mut findings = Vec::new();
+    if let Some(endpoint) = &rule.endpoint {
+        if rule.rule_type() == RuleType::Endpoint && !engine.rules.endpoints.contains_key(endpoint)
+        {
+            findings.push(format!(
+                "rule {}: endpoint {} is not declared in endpoints",
+                name, endpoint
+            ));
+        }
+    }
+    let filters = [
+        (
+            "request",
+            rule.transform.get_filter_source(RuleTransformType::Request),
+        ),
+        (
+            "response",
+            rule.transform
+                .get_filter_source(RuleTransformType::Response),
+        ),
+        (
+            "event",
+            rule.transform
+                .get_filter_source(RuleTransformType::Event(false)),
+        ),
+        (
+            "rpcv2_event",
+            rule.transform
+                .get_filter_source(RuleTransformType::Event(true)),
+        ),
+        ("filter", rule.filter.clone()),
+    ];
+    for (label, source) in filters {
+        let Some(source) = source else {
+            continue;
+        };
+        if source.contains(FUNCTION_PREFIX) {
+            findings.push(format!(
+                "rule {}: {} calls a function which is not imported or has too few parameters",
+                name, label
+            ));
+        } else if let Err(e) = CompiledFilter::try_compile(&source) {
+            findings.push(format!("rule {}: {} does not compile: {}", name, label, e));
+        }
+    }
+    findings
+}
+
+fn lint(engine: &RuleEngine, errors: Vec<RuleLoadError>) -> Vec<String> {
+    // compile failures are reported in detail per rule below
+    let mut findings: Vec<String> = errors
+        .into_iter()
+        .filter(|e| !matches!(e, RuleLoadError::CompileFailed(_)))
+        .map(|e| e.to_string())
+        .collect();
+    let mut rules: Vec<(&String, &Rule)> = engine.rules.iter().collect();
+    rules.sort_by_key(|(name, _)| *name);
+    for (name, rule) in rules {
+        findings.extend(lint_rule(name, rule, engine));
+    }
+    findings
+}
+
+fn dry_run(engine: &RuleEngine, args: &Args, method: &str) -> Result<(), String> {
+    let rule = engine
+        .get_rule_by_method(method)
+        .ok_or(format!("no rule named {}", method))?;
+    let variables = RuleVariables::new(&args.app_id);
+    let inputs = [
+        ("request", RuleTransformType::Request, &args.request),
+        ("response", RuleTransformType::Response, &args.response),
+        ("event", RuleTransformType::Event(false), &args.event),
+    ];
+    for (label, typ, input) in inputs {
+        let Some(input) = input else {
+            continue;
+        };
+        let filter = rule
+            .get_compiled_transform(typ)
+            .ok_or(format!("rule {} has no {} transform", method, label))?
+            .map_err(|e| format!("rule {} {} does not compile: {}", method, label, e))?;
+        let output = filter
+            .run(input.clone(), &variables, method)
+            .map_err(|e| format!("rule {} {} produced no output: {}", method, label, e))?;
+        println!(
+            "{}: {}",
+            label,
+            ripple_sdk::serde_json::to_string_pretty(&output).unwrap_or_default()
+        );
+    }
+    Ok(())
+}
+
+fn main() {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}\n{}", e, USAGE);
+            exit(exitcode::USAGE);
+        }
+    };
+    let manifest = ExtnManifest {
+        default_path: args.default_path.clone(),
+        rules_path: args.files.clone(),
+        ..Default::default()
+    };
+    let (engine, errors) = RuleEngine::build_with_errors(&manifest);
+    let findings = lint(&engine, errors);
+    for finding in findings.iter() {
+        eprintln!("{}", finding);
+    }
+    println!(
+        "{} rules checked, {} problems found",
+        engine.rules.len(),
+        findings.len()
+    );
+
+    if let Some(method) = &args.rule {
+        if let Err(e) = dry_run(&engine, &args, method) {
+            eprintln!("{}", e);
+            exit(exitcode::DATAERR);
+        }
+    }
+    if !findings.is_empty() {
+        exit(exitcode::DATAERR);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ripple_main::broker::rules::rules_engine::RuleSet;
+    use ripple_sdk::serde_json::json;
+
+    fn args(list: &[&str]) -> Result<Args, String> {
+        parse_args(list.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let parsed = args(&[
+            "--default-path",
+            "/etc/ripple/",
+            "a.json",
+            "b.json",
+            "--rule",
+            "device.make",
+            "--response",
+            r#"{"result": {"make": "arris"}}"#,
+        ])
+        .unwrap();
+        assert_eq!(parsed.default_path, "/etc/ripple/");
+        assert_eq!(parsed.files, vec!["a.json", "b.json"]);
+        assert_eq!(parsed.rule, Some("device.make".to_owned()));
+        assert_eq!(parsed.response, Some(json!({"result": {"make": "arris"}})));
+
+        assert!(args(&[]).is_err());
+        assert!(args(&["a.json", "--rule"]).is_err());
+        assert!(args(&["a.json", "--request", "{}"]).is_err());
+        assert!(args(&["a.json", "--unknown"]).is_err());
+    }
+
+    #[test]
+    fn test_lint() {
+        let rule_set: RuleSet = ripple_sdk::serde_json::from_value(json!({
+            "endpoints": { "thunder": { "protocol": "thunder", "url": "ws://127.0.0.1:9998" } },
+            "rules": {
+                "device.make": {
+                    "alias": "org.rdk.System.getDeviceInfo",
+                    "transform": { "response": ".result.make" }
+                },
+                "device.model": {
+                    "alias": "org.rdk.System.getDeviceInfo",
+                    "endpoint": "missing",
+                    "transform": { "response": "$function.not_imported(.result)" },
+                    "filter": "if .success"
+                }
+            }
+        }))
+        .unwrap();
+        let mut engine = RuleEngine::default();
+        engine.add_rules(rule_set, "");
+
+        let findings = lint(&engine, vec![]);
+        assert_eq!(findings.len(), 3);
+        assert!(findings[0].contains("endpoint missing"));
+        assert!(findings[1].contains("response calls a function"));
+        assert!(findings[2].contains("filter does not compile"));
+
+        let parsed = args(&[
+            "rules.json",
+            "--rule",
+            "device.make",
+            "--response",
+            r#"{"result": {"make": "arris"}}"#,
+        ])
+        .unwrap();
+        assert!(dry_run(&engine, &parsed, "device.make").is_ok());
+        assert!(dry_run(&engine, &parsed, "device.unknown").is_err());
+    }
+}
@@ -17,10 +17,10 @@
 
 use crate::state::cap::cap_state::CapState;
 use crate::state::platform_state::PlatformState;
-use crate::tokio;
-use crate::tokio::sync::mpsc;
 use crate::{broker::broker_utils::BrokerUtils, state::bootstrap_state::BootstrapState};
 use jsonrpsee::core::RpcResult;
+use ripple_sdk::tokio;
+use ripple_sdk::tokio::sync::mpsc;
 
 use ripple_sdk::api::session::AccountSession;
 use ripple_sdk::{
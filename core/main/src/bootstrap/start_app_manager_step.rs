@@ -20,7 +20,10 @@ use ripple_sdk::{framework::bootstrap::Bootstep, tokio, utils::error::RippleErro
 
 use crate::processor::lifecycle_management_processor::LifecycleManagementProcessor;
 use crate::{
-    service::apps::delegated_launcher_handler::DelegatedLauncherHandler,
+    service::apps::{
+        delegated_launcher_handler::DelegatedLauncherHandler,
+        lifecycle2_manager::{is_lifecycle2_enabled, Lifecycle2Manager},
+    },
     state::bootstrap_state::BootstrapState,
 };
 
@@ -40,6 +43,15 @@ impl Bootstep<BootstrapState> for StartAppManagerStep {
             .add_request_processor(LifecycleManagementProcessor::new(
                 state.platform_state.get_client(),
             ));
+        if is_lifecycle2_enabled() {
+            let lifecycle2_manager = Lifecycle2Manager::new(state.platform_state.clone());
+            tokio::spawn(lifecycle2_manager.start());
+        }
+        /*
+        The launcher handler keeps running with Lifecycle 2.0, app sessions, launch requests,
+        content catalogs and app names are still only served through it. Gating it is a follow-up,
+        see docs/lifecycle2.md.
+        */
         let mut app_manager =
             DelegatedLauncherHandler::new(state.channels_state, state.platform_state);
         tokio::spawn(async move {
@@ -24,12 +24,13 @@ use ripple_sdk::{
             ApiMessage, ApiProtocol, CallContext, JsonRpcApiRequest, JsonRpcApiResponse,
             RpcRequest, RPC_V2,
         },
+        manifest::extn_manifest::ExtnManifest,
         observability::log_signal::LogSignal,
         session::AccountSession,
     },
     extn::extn_client_message::{ExtnEvent, ExtnMessage},
     framework::RippleResponse,
-    log::{debug, error, info, trace},
+    log::{debug, error, info, trace, warn},
     service::service_message::{
         Id as ServiceMessageId, JsonRpcMessage as ServiceJsonRpcMessage,
         JsonRpcSuccess as ServiceJsonRpcSuccess, ServiceMessage,
@@ -68,9 +69,10 @@ use super::{
     extn_broker::ExtnBroker,
     http_broker::HttpBroker,
     provider_broker_state::{ProvideBrokerState, ProviderResult},
+    response_cache::ResponseCache,
     rules::rules_engine::{
-        jq_compile, EventHandler, Rule, RuleEndpoint, RuleEndpointProtocol, RuleEngine,
-        RuleRetrievalError, RuleRetrieved, RuleType,
+        CompiledFilter, EventHandler, Rule, RuleEndpoint, RuleEndpointProtocol, RuleEngine,
+        RuleLoadError, RuleRetrievalError, RuleRetrieved, RuleType, RuleVariables,
     },
     service_broker::ServiceBroker,
     thunder_broker::ThunderBroker,
@@ -381,6 +383,7 @@ pub struct EndpointBrokerState {
     reconnect_tx: Sender<BrokerConnectRequest>,
     provider_broker_state: ProvideBrokerState,
     metrics_state: OpMetricState,
+    response_cache: ResponseCache,
 }
 
 #[derive(Debug)]
@@ -472,6 +475,7 @@ impl Default for EndpointBrokerState {
             reconnect_tx: mpsc::channel(2).0,
             provider_broker_state: ProvideBrokerState::default(),
             metrics_state: OpMetricState::default(),
+            response_cache: ResponseCache::default(),
         }
     }
 }
@@ -494,6 +498,7 @@ impl EndpointBrokerState {
             reconnect_tx,
             provider_broker_state: ProvideBrokerState::default(),
             metrics_state,
+            response_cache: ResponseCache::default(),
         };
         /*bobra: configuring this out for unit tests */
         #[cfg(not(test))]
@@ -511,6 +516,28 @@ impl EndpointBrokerState {
     pub fn has_rule(&self, rule: &str) -> bool {
         self.rule_engine.read().unwrap().has_rule(rule)
     }
+    /// Re-reads every rules file in the manifest and swaps the rule set in only if all of
+    /// it loads and compiles. In flight requests carry their own copy of the rule and finish
+    /// on the old rules. Endpoints are not rebuilt, changing them still needs a restart.
+    pub fn reload_rules(&self, extn_manifest: &ExtnManifest) -> Result<usize, Vec<RuleLoadError>> {
+        let engine = RuleEngine::try_build(extn_manifest).inspect_err(|errors| {
+            for e in errors.iter() {
+                error!("reload_rules: {}", e);
+            }
+        })?;
+        let count = engine.rules.len();
+        let mut current = self.rule_engine.write().unwrap();
+        let mut old_endpoints: Vec<&String> = current.rules.endpoints.keys().collect();
+        let mut new_endpoints: Vec<&String> = engine.rules.endpoints.keys().collect();
+        old_endpoints.sort();
+        new_endpoints.sort();
+        if old_endpoints != new_endpoints {
+            warn!("reload_rules: endpoint changes are applied on restart only");
+        }
+        *current = engine;
+        info!("reload_rules: loaded {} rules", count);
+        Ok(count)
+    }
     #[cfg(not(test))]
     fn reconnect_thread(&self, mut rx: Receiver<BrokerConnectRequest>, client: RippleClient) {
         use crate::firebolt::firebolt_gateway::FireboltGatewayCommand;
@@ -588,14 +615,15 @@ impl EndpointBrokerState {
 
             if let Some(filter) = rpc_request
                 .rule
-                .transform
-                .get_transform_data(super::rules::rules_engine::RuleTransformType::Request)
+                .get_compiled_transform(super::rules::rules_engine::RuleTransformType::Request)
             {
-                let transformed_request_res = jq_compile(
-                    last,
-                    &filter,
-                    format!("{}_request", rpc_request.rpc.ctx.method),
-                );
+                let transformed_request_res = filter.and_then(|f| {
+                    f.run(
+                        last,
+                        &RuleVariables::from(&rpc_request.rpc),
+                        &format!("{}_request", rpc_request.rpc.ctx.method),
+                    )
+                });
 
                 LogSignal::new(
                     "endpoint_broker".to_string(),
@@ -961,6 +989,31 @@ impl EndpointBrokerState {
         let rule: Rule = match self.get_broker_rule(&rpc_request)? {
             RuleRetrieved::ExactMatch(rule) | RuleRetrieved::WildcardMatch(rule) => rule,
         };
+        /*
+        cached responses are sent through the forwarder like endpoint responses, so the
+        response transform still runs for the calling app
+        */
+        let cached = ResponseCache::get_key(&rule, &rpc_request)
+            .and_then(|key| self.response_cache.get(&key));
+        if let Some(mut data) = cached {
+            let request = self.update_request(
+                &rpc_request,
+                &rule,
+                extn_message,
+                workflow_callback,
+                telemetry_response_listeners,
+            );
+            data.id = Some(request.rpc.ctx.call_id);
+            capture_stage(&self.metrics_state, &rpc_request, "cached_rule_response");
+            let broker_callback = self.callback.clone();
+            let output = BrokerOutput::new(data.clone());
+            tokio::spawn(async move {
+                if let Err(err) = broker_callback.sender.try_send(output) {
+                    error!("Error sending cached json rpc response to broker {:?}", err);
+                }
+            });
+            return Ok(RenderedRequest::JsonRpc(data));
+        }
         /*
          attempt to get the endpoint from the rule
         https://github.com/rdkcentral/Ripple/blob/ae3fcd78b055cf70022959bf827de9ed569762aa/core/main/src/broker/endpoint_broker.rs#L722
@@ -1243,14 +1296,15 @@ pub trait EndpointBroker {
 
             if let Some(filter) = rpc_request
                 .rule
-                .transform
-                .get_transform_data(super::rules::rules_engine::RuleTransformType::Request)
+                .get_compiled_transform(super::rules::rules_engine::RuleTransformType::Request)
             {
-                let transformed_request_res = jq_compile(
-                    last,
-                    &filter,
-                    format!("{}_request", rpc_request.rpc.ctx.method),
-                );
+                let transformed_request_res = filter.and_then(|f| {
+                    f.run(
+                        last,
+                        &RuleVariables::from(&rpc_request.rpc),
+                        &format!("{}_request", rpc_request.rpc.ctx.method),
+                    )
+                });
 
                 LogSignal::new(
                     "endpoint_broker".to_string(),
@@ -1353,6 +1407,23 @@ impl BrokerOutputForwarder {
                         let rpc_request = broker_request.rpc.clone();
                         let is_subscription = rpc_request.is_subscription();
 
+                        if is_event {
+                            platform_state
+                                .endpoint_state
+                                .response_cache
+                                .invalidate(&rpc_request.method, &broker_request.rule.alias);
+                        } else if response.error.is_none() && response.result.is_some() {
+                            if let Some(key) =
+                                ResponseCache::get_key(&broker_request.rule, &rpc_request)
+                            {
+                                platform_state.endpoint_state.response_cache.insert(
+                                    key,
+                                    &broker_request.rule,
+                                    &output_c.data,
+                                );
+                            }
+                        }
+
                         let apply_response_needed = if let Some(result) = response.result.clone() {
                             if is_event {
                                 LogSignal::new(
@@ -1553,12 +1624,19 @@ impl BrokerOutputForwarder {
             return true;
         }
 
-        if let Some(filter) = broker_request.rule.transform.get_transform_data(
+        if let Some(filter) = broker_request.rule.get_compiled_transform(
             super::rules::rules_engine::RuleTransformType::Event(
                 rpc_request.ctx.context.contains(&RPC_V2.into()),
             ),
         ) {
-            apply_rule_for_event(broker_request, &result, rpc_request, &filter, response);
+            apply_compiled_rule_for_event(
+                broker_request,
+                &result,
+                rpc_request,
+                filter,
+                &RuleVariables::from(rpc_request),
+                response,
+            );
         }
 
         if !apply_filter(broker_request, &result, rpc_request) {
@@ -1691,10 +1769,14 @@ impl BrokerOutputForwarder {
         if apply_response_using_main_req_needed {
             if let Some(filter) = broker_request
                 .rule
-                .transform
-                .get_transform_data(super::rules::rules_engine::RuleTransformType::Response)
+                .get_compiled_transform(super::rules::rules_engine::RuleTransformType::Response)
             {
-                apply_response(filter, rule_context_name, response);
+                apply_compiled_response(
+                    filter,
+                    &RuleVariables::from(&broker_request.rpc),
+                    rule_context_name,
+                    response,
+                );
             } else if response.result.is_none() && response.error.is_none() {
                 response.result = Some(Value::Null);
             }
@@ -1785,29 +1867,23 @@ impl BrokerOutputForwarder {
         )
         .await
         {
-            if let Ok(event_handler_response_string) =
-                serde_json::to_string(&event_handler_response)
-            {
-                if let Some(mut event_filter) = broker_request.rule.transform.get_transform_data(
-                    super::rules::rules_engine::RuleTransformType::Event(
-                        rpc_request.ctx.context.contains(&RPC_V2.into()),
-                    ),
-                ) {
-                    event_filter = event_filter
-                        .replace("$event_handler_response", &event_handler_response_string);
-
-                    apply_rule_for_event(
-                        &broker_request,
-                        &event_handler_response,
-                        &rpc_request,
-                        &event_filter,
-                        &mut response,
-                    );
-                } else {
-                    response.result = Some(event_handler_response);
-                }
+            if let Some(event_filter) = broker_request.rule.get_compiled_transform(
+                super::rules::rules_engine::RuleTransformType::Event(
+                    rpc_request.ctx.context.contains(&RPC_V2.into()),
+                ),
+            ) {
+                // the handler response is bound to $event_handler_response in the filter
+                let variables = RuleVariables::from(&rpc_request)
+                    .with_event_handler_response(event_handler_response.clone());
+                apply_compiled_rule_for_event(
+                    &broker_request,
+                    &event_handler_response,
+                    &rpc_request,
+                    event_filter,
+                    &variables,
+                    &mut response,
+                );
             } else {
-                error!("handle_event: Could not deserialize event handler response");
                 response.result = Some(event_handler_response);
             }
         }
@@ -1922,44 +1998,65 @@ pub fn apply_response(
     method: &str,
     response: &mut JsonRpcApiResponse,
 ) {
+    let reference = format!("{}_response", method);
+    apply_compiled_response(
+        CompiledFilter::compile(&result_response_filter, &reference),
+        &RuleVariables::default(),
+        method,
+        response,
+    );
+}
+
+pub fn apply_compiled_response(
+    filter: Result<CompiledFilter, RippleError>,
+    variables: &RuleVariables,
+    method: &str,
+    response: &mut JsonRpcApiResponse,
+) {
+    let filter = match filter {
+        Ok(filter) => filter,
+        Err(e) => {
+            response.error = Some(json!(e.to_string()));
+            error!("jq compile error: e={:?}, response={:?}", e, response);
+            return;
+        }
+    };
     match serde_json::to_value(response.clone()) {
-        Ok(input) => {
-            match jq_compile(
-                input,
-                &result_response_filter,
-                format!("{}_response", method),
-            ) {
-                Ok(jq_out) => {
-                    trace!(
-                        "jq rendered output {:?} original input {:?} for filter {}",
-                        jq_out,
-                        response,
-                        result_response_filter
-                    );
+        Ok(input) => match filter.run(input, variables, &format!("{}_response", method)) {
+            Ok(jq_out) => {
+                trace!(
+                    "jq rendered output {:?} original input {:?} for filter {}",
+                    jq_out,
+                    response,
+                    filter.get_source()
+                );
 
-                    if jq_out.is_object() && jq_out.get("error").is_some() {
-                        response.error = Some(jq_out.get("error").unwrap().clone());
-                        response.result = None;
-                    } else {
-                        response.result = Some(jq_out);
-                        response.error = None;
-                    }
-                    trace!("mutated response {:?}", response);
-                }
-                Err(e) => {
-                    response.error = Some(json!(e.to_string()));
-                    error!(
-                        "jq compile error: e={:?}, filter={}, response={:?}",
-                        e, result_response_filter, response
-                    );
+                if jq_out.is_object() && jq_out.get("error").is_some() {
+                    response.error = Some(jq_out.get("error").unwrap().clone());
+                    response.result = None;
+                } else {
+                    response.result = Some(jq_out);
+                    response.error = None;
                 }
+                trace!("mutated response {:?}", response);
             }
-        }
+            Err(e) => {
+                response.error = Some(json!(e.to_string()));
+                error!(
+                    "jq compile error: e={:?}, filter={}, response={:?}",
+                    e,
+                    filter.get_source(),
+                    response
+                );
+            }
+        },
         Err(e) => {
             response.error = Some(json!(e.to_string()));
             error!(
                 "json rpc response error: e={:?}, filter={}, response={:?}",
-                e, result_response_filter, response
+                e,
+                filter.get_source(),
+                response
             );
         }
     }
@@ -1972,11 +2069,32 @@ pub fn apply_rule_for_event(
     filter: &str,
     response: &mut JsonRpcApiResponse,
 ) {
-    if let Ok(r) = jq_compile(
-        result.clone(),
-        filter,
-        format!("{}_event", rpc_request.ctx.method),
-    ) {
+    let reference = format!("{}_event", rpc_request.ctx.method);
+    apply_compiled_rule_for_event(
+        broker_request,
+        result,
+        rpc_request,
+        CompiledFilter::compile(filter, &reference),
+        &RuleVariables::from(rpc_request),
+        response,
+    );
+}
+
+pub fn apply_compiled_rule_for_event(
+    broker_request: &BrokerRequest,
+    result: &Value,
+    rpc_request: &RpcRequest,
+    filter: Result<CompiledFilter, RippleError>,
+    variables: &RuleVariables,
+    response: &mut JsonRpcApiResponse,
+) {
+    if let Ok(r) = filter.and_then(|f| {
+        f.run(
+            result.clone(),
+            variables,
+            &format!("{}_event", rpc_request.ctx.method),
+        )
+    }) {
         LogSignal::new(
             "apply_rule_for_event".to_string(),
             "broker request found".to_string(),
@@ -1998,12 +2116,14 @@ pub fn apply_rule_for_event(
 }
 
 fn apply_filter(broker_request: &BrokerRequest, result: &Value, rpc_request: &RpcRequest) -> bool {
-    if let Some(filter) = broker_request.rule.filter.clone() {
-        if let Ok(r) = jq_compile(
-            result.clone(),
-            &filter,
-            format!("{}_event filter", rpc_request.ctx.method),
-        ) {
+    if let Some(filter) = broker_request.rule.get_compiled_filter() {
+        if let Ok(r) = filter.and_then(|f| {
+            f.run(
+                result.clone(),
+                &RuleVariables::from(rpc_request),
+                &format!("{}_event filter", rpc_request.ctx.method),
+            )
+        }) {
             if r.is_null() {
                 return false;
             } else {
@@ -2037,6 +2157,9 @@ mod endpoint_broker_tests {
                         filter: None,
                         event_handler: None,
                         sources: None,
+                        http: None,
+                        cache: None,
+                        compiled: None,
                     },
                     subscription_processed: None,
                     workflow_callback: None,
@@ -2090,7 +2213,7 @@ mod endpoint_broker_tests {
         use crate::broker::endpoint_broker::ATOMIC_ID;
         use crate::broker::rules::rules_engine::RuleEndpoint;
         use crate::broker::rules::rules_engine::RuleEndpointProtocol;
-        use ripple_sdk::api::session::AccountSession;
+        use ripple_sdk::api::{manifest::extn_manifest::ExtnManifest, session::AccountSession};
         use std::{collections::HashMap, sync::atomic::Ordering};
 
         fn reset_counter(value: u64) {
@@ -2243,6 +2366,43 @@ mod endpoint_broker_tests {
             assert!(endpoints.contains_key("http_endpoint"));
         }
 
+        #[tokio::test]
+        async fn test_reload_rules() {
+            let (tx, _) = channel(2);
+            let client = RippleClient::new(ChannelsState::new());
+            let state = EndpointBrokerState::new(
+                OpMetricState::default(),
+                tx,
+                RuleEngine::default(),
+                client,
+            );
+            let path = std::env::temp_dir()
+                .join(format!("ripple_reload_rules_{}.json", std::process::id()));
+            let manifest = ExtnManifest {
+                rules_path: vec![path.to_str().unwrap().to_owned()],
+                ..Default::default()
+            };
+
+            std::fs::write(
+                &path,
+                r#"{"endpoints": {}, "rules": {"device.make": {"alias": "org.rdk.System.getDeviceInfo"}}}"#,
+            )
+            .unwrap();
+            assert_eq!(state.reload_rules(&manifest), Ok(1));
+            assert!(state.has_rule("device.make"));
+
+            // a set with a bad filter is rejected and the previous rules stay active
+            std::fs::write(
+                &path,
+                r#"{"endpoints": {}, "rules": {"device.model": {"alias": "org.rdk.System.getDeviceInfo", "filter": "if .success"}}}"#,
+            )
+            .unwrap();
+            assert!(state.reload_rules(&manifest).is_err());
+            assert!(state.has_rule("device.make"));
+            assert!(!state.has_rule("device.model"));
+            let _ = std::fs::remove_file(&path);
+        }
+
         #[tokio::test]
         async fn test_build_endpoint_websocket() {
             let (tx, _) = channel(2);
@@ -2742,6 +2902,9 @@ mod endpoint_broker_tests {
                 filter: None,
                 event_handler: None,
                 sources: None,
+                http: None,
+                cache: None,
+                compiled: None,
             };
             state.update_request(&rpc_request, &rule, None, None, vec![]);
             apply_response(filter, &rpc_request.ctx.method, &mut output.data);
@@ -2787,6 +2950,9 @@ mod endpoint_broker_tests {
                 filter: None,
                 event_handler: None,
                 sources: None,
+                http: None,
+                cache: None,
+                compiled: None,
             };
             state.update_request(&rpc_request, &rule, None, None, vec![]);
             apply_response(filter, &rpc_request.ctx.method, &mut output.data);
@@ -2834,6 +3000,9 @@ mod endpoint_broker_tests {
                 filter: None,
                 event_handler: None,
                 sources: None,
+                http: None,
+                cache: None,
+                compiled: None,
             };
             state.update_request(&rpc_request, &rule, None, None, vec![]);
             apply_response(filter, &rpc_request.ctx.method, &mut output.data);
@@ -2880,6 +3049,9 @@ mod endpoint_broker_tests {
                 filter: None,
                 event_handler: None,
                 sources: None,
+                http: None,
+                cache: None,
+                compiled: None,
             };
             state.update_request(&rpc_request, &rule, None, None, vec![]);
             apply_response(filter, &rpc_request.ctx.method, &mut output.data);
@@ -2927,6 +3099,9 @@ mod endpoint_broker_tests {
                 filter: None,
                 event_handler: None,
                 sources: None,
+                http: None,
+                cache: None,
+                compiled: None,
             };
             state.update_request(&rpc_request, &rule, None, None, vec![]);
             apply_response(filter, &rpc_request.ctx.method, &mut output.data);
@@ -2965,6 +3140,9 @@ mod endpoint_broker_tests {
                 filter: None,
                 event_handler: None,
                 sources: None,
+                http: None,
+                cache: None,
+                compiled: None,
             };
             engine.add_rule(r);
 
@@ -3012,15 +3190,18 @@ mod endpoint_broker_tests {
             broker::{
                 endpoint_broker::{
                     BrokerRequest, BrokerSender, EndpointBrokerState, HandleBrokerageError,
+                    RenderedRequest,
                 },
-                rules::rules_engine::{Rule, RuleEngine, RuleSet},
+                response_cache::ResponseCache,
+                rules::rules_engine::{Rule, RuleCache, RuleEngine, RuleSet},
             },
             service::extn::ripple_client::RippleClient,
             state::{bootstrap_state::ChannelsState, ops_metrics_state::OpMetricState},
         };
         use ripple_sdk::{
-            api::gateway::rpc_gateway_api::RpcRequest,
+            api::gateway::rpc_gateway_api::{JsonRpcApiResponse, RpcRequest},
             extn::extn_client_message::ExtnMessage,
+            serde_json::json,
             tokio::{
                 self,
                 sync::mpsc::{self, channel},
@@ -3109,6 +3290,9 @@ mod endpoint_broker_tests {
                 filter: None,
                 event_handler: None,
                 sources: None,
+                http: None,
+                cache: None,
+                compiled: None,
             };
             engine.add_rule(rule);
             let mut under_test =
@@ -3125,6 +3309,56 @@ mod endpoint_broker_tests {
             assert!(result.is_ok(), "Expected Ok but got: {:?}", result);
         }
 
+        #[tokio::test]
+        async fn test_dispatch_brokerage_cached_rule() {
+            let (tx, mut rx) = channel(2);
+            let client = RippleClient::new(ChannelsState::new());
+            let mut engine = RuleEngine {
+                rules: RuleSet::default(),
+                functions: HashMap::default(),
+            };
+            let rule = Rule {
+                alias: "endpoint".to_owned(),
+                endpoint: Some("thunder".to_string()),
+                cache: Some(RuleCache {
+                    ttl_ms: 60_000,
+                    invalidate_on: vec![],
+                }),
+                ..Default::default()
+            };
+            engine.add_rule(rule.clone());
+            let mut under_test =
+                EndpointBrokerState::new(OpMetricState::default(), tx, engine, client);
+            let (endpoint_tx, mut endpoint_rx) = mpsc::channel::<BrokerRequest>(10);
+            under_test.add_endpoint(
+                "thunder".to_string(),
+                BrokerSender {
+                    sender: endpoint_tx,
+                },
+            );
+
+            let mut request = RpcRequest::mock();
+            request.method = "endpoint".to_string();
+            let key = ResponseCache::get_key(&rule, &request).unwrap();
+            under_test.response_cache.insert(
+                key,
+                &rule,
+                &JsonRpcApiResponse::new(Some(1), None).with_result(Some(json!("cached"))),
+            );
+
+            let result =
+                under_test.handle_brokerage_workflow(request, None, None, vec![], None, vec![]);
+            assert!(
+                matches!(result, Ok(RenderedRequest::JsonRpc(ref data)) if data.result == Some(json!("cached"))),
+                "Expected cached response but got: {:?}",
+                result
+            );
+            let output = rx.recv().await.unwrap();
+            assert_eq!(output.data.result, Some(json!("cached")));
+            assert!(under_test.get_request(output.data.id.unwrap()).is_ok());
+            assert!(endpoint_rx.try_recv().is_err());
+        }
+
         #[tokio::test]
         async fn test_dispatch_brokerage_rule_not_found() {
             let (tx, _) = channel(2);
@@ -3162,6 +3396,9 @@ mod endpoint_broker_tests {
                 filter: None,
                 event_handler: None,
                 sources: None,
+                http: None,
+                cache: None,
+                compiled: None,
             };
             engine.add_rule(rule);
             let under_test = EndpointBrokerState::new(OpMetricState::default(), tx, engine, client);
@@ -3214,6 +3451,9 @@ mod endpoint_broker_tests {
                     filter: None,
                     event_handler: None,
                     sources: None,
+                    http: None,
+                    cache: None,
+                    compiled: None,
                 };
 
                 let broker_request = state.update_request(&rpc_request, &rule, None, None, vec![]);
@@ -3243,6 +3483,9 @@ mod endpoint_broker_tests {
                     filter: None,
                     event_handler: None,
                     sources: None,
+                    http: None,
+                    cache: None,
+                    compiled: None,
                 };
                 let extn_message = Some(ExtnMessage::default());
 
@@ -3274,6 +3517,9 @@ mod endpoint_broker_tests {
                     filter: None,
                     event_handler: None,
                     sources: None,
+                    http: None,
+                    cache: None,
+                    compiled: None,
                 };
                 let workflow_callback = Some(BrokerCallback::default());
 
@@ -3311,6 +3557,9 @@ mod endpoint_broker_tests {
                     filter: None,
                     event_handler: None,
                     sources: None,
+                    http: None,
+                    cache: None,
+                    compiled: None,
                 };
                 let telemetry_response_listeners = vec![channel(2).0];
 
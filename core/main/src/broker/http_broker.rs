@@ -17,13 +17,32 @@
 
 use std::vec;
 
-use hyper::{client::HttpConnector, Body, Client, Method, Request, Response, Uri};
+use std::{fs::File, io::BufReader};
+
+use hyper::{
+    client::{connect::Connect, HttpConnector},
+    header::{HeaderName, HeaderValue, AUTHORIZATION, CONTENT_TYPE},
+    Body, Client, Method, Request, Response, StatusCode, Uri,
+};
+use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
 use ripple_sdk::{
-    api::{gateway::rpc_gateway_api::JsonRpcApiError, observability::log_signal::LogSignal},
-    log::{debug, error},
-    tokio::{self, sync::mpsc},
+    api::{
+        firebolt::fb_capabilities::{
+            CAPABILITY_NOT_AVAILABLE, CAPABILITY_NOT_PERMITTED, CAPABILITY_NOT_SUPPORTED,
+            JSON_RPC_STANDARD_ERROR_INVALID_PARAMS,
+        },
+        gateway::rpc_gateway_api::JsonRpcApiError,
+        observability::log_signal::LogSignal,
+    },
+    log::{debug, error, warn},
+    tokio::{
+        self,
+        sync::mpsc,
+        time::{sleep, timeout, Duration},
+    },
     utils::error::RippleError,
 };
+use rustls::{ClientConfig, OwnedTrustAnchor, RootCertStore};
 use serde_json::Value;
 
 use super::endpoint_broker::{
@@ -32,7 +51,7 @@ use super::endpoint_broker::{
 };
 
 use crate::{
-    broker::rules::rules_engine::{jq_compile, RuleTransformType},
+    broker::rules::rules_engine::{HttpMethod, RuleEndpoint, RuleTransformType, RuleVariables},
     state::platform_state::PlatformState,
 };
 
@@ -43,64 +62,276 @@ pub struct HttpBroker {
     cleaner: BrokerCleaner,
 }
 
-async fn send_http_request(
-    client: &Client<HttpConnector>,
-    uri: &Uri,
-    broker_request: BrokerRequest,
-) -> Result<Response<Body>, RippleError> {
-    let mut method = Method::GET;
-    let mut body = Body::empty();
+/// JSON-RPC error code for http failures which have no closer Firebolt equivalent
+const HTTP_BROKER_ERROR: i32 = -32000;
+const DEFAULT_HTTP_TIMEOUT_MS: u64 = 10000;
+const HTTP_RETRY_BACKOFF_MS: u64 = 100;
+/// Upper bound for the retries configured on a rule endpoint
+const HTTP_MAX_RETRIES: u32 = 5;
+/// Upper bound for the exponential backoff between two attempts
+const HTTP_MAX_RETRY_BACKOFF_MS: u64 = 5000;
+/// Idle keep-alive connections are reused for this long
+const HTTP_POOL_IDLE_TIMEOUT_SECS: u64 = 90;
+
+impl From<HttpMethod> for Method {
+    fn from(method: HttpMethod) -> Self {
+        match method {
+            HttpMethod::Get => Method::GET,
+            HttpMethod::Post => Method::POST,
+            HttpMethod::Put => Method::PUT,
+            HttpMethod::Patch => Method::PATCH,
+            HttpMethod::Delete => Method::DELETE,
+        }
+    }
+}
+
+/*
+percent encodes everything except the unreserved characters of RFC 3986 so that a
+request param can be used as a path segment or a query value
+*/
+fn encode_url_param(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Replaces "{name}" placeholders in the url template with the matching request params.
+fn render_url_template(template: &str, params: &Value) -> Result<String, RippleError> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        let name = &rest[start + 1..start + end];
+        let value = match params.get(name) {
+            Some(Value::String(value)) => value.clone(),
+            Some(Value::Null) | None => {
+                return Err(RippleError::BrokerError(format!(
+                    "missing request param {} for url {}",
+                    name, template
+                )))
+            }
+            Some(value) => value.to_string(),
+        };
+        rendered.push_str(&rest[..start]);
+        rendered.push_str(&encode_url_param(&value));
+        rest = &rest[start + end + 1..];
+    }
+    rendered.push_str(rest);
+    Ok(rendered)
+}
 
-    // A rule with a request transform defined indicates that the request is a POST, where
-    // the request transform is the body of the request. Otherwise, it is a GET request.
+fn status_to_error_code(status: StatusCode) -> i32 {
+    match status {
+        StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => {
+            JSON_RPC_STANDARD_ERROR_INVALID_PARAMS
+        }
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => CAPABILITY_NOT_PERMITTED,
+        StatusCode::NOT_IMPLEMENTED => CAPABILITY_NOT_SUPPORTED,
+        status if status.is_server_error() => CAPABILITY_NOT_AVAILABLE,
+        _ => HTTP_BROKER_ERROR,
+    }
+}
 
-    if let Some(request_transform) = broker_request
+fn build_http_request(
+    uri: &Uri,
+    endpoint: &RuleEndpoint,
+    token: Option<&String>,
+    broker_request: &BrokerRequest,
+) -> Result<Request<Body>, RippleError> {
+    let http_rule = broker_request.rule.http.clone().unwrap_or_default();
+    let request_transform = broker_request
         .rule
-        .transform
-        .get_transform_data(RuleTransformType::Request)
-    {
-        method = Method::POST;
-
-        let transform_params =
-            match serde_json::from_str::<Vec<Value>>(&broker_request.rpc.params_json) {
-                Ok(mut params) => params.pop().unwrap_or(Value::Null),
-                Err(e) => {
-                    error!(
-                        "send_http_request: Error in http broker parsing request params: e={:?}",
-                        e
-                    );
-                    Value::Null
-                }
-            };
+        .get_compiled_transform(RuleTransformType::Request);
+
+    // Without an explicit method, a rule with a request transform defined indicates that the
+    // request is a POST, where the request transform is the body of the request. Otherwise,
+    // it is a GET request.
+    let method: Method = http_rule
+        .method
+        .unwrap_or(if request_transform.is_some() {
+            HttpMethod::Post
+        } else {
+            HttpMethod::Get
+        })
+        .into();
+
+    let params = match serde_json::from_str::<Vec<Value>>(&broker_request.rpc.params_json) {
+        Ok(mut params) => params.pop().unwrap_or(Value::Null),
+        Err(e) => {
+            error!(
+                "send_http_request: Error in http broker parsing request params: e={:?}",
+                e
+            );
+            Value::Null
+        }
+    };
 
-        let body_val = jq_compile(
-            transform_params,
-            &request_transform,
-            format!("{}_http_post", broker_request.rpc.ctx.method),
+    let mut body = Body::empty();
+    let has_body = request_transform.is_some();
+    if let Some(request_transform) = request_transform {
+        let body_val = request_transform?.run(
+            params.clone(),
+            &RuleVariables::from(&broker_request.rpc),
+            &format!("{}_http_{}", broker_request.rpc.ctx.method, method),
         )?;
 
         body = Body::from(body_val.to_string());
     }
 
-    let uri: Uri = format!("{}{}", uri, broker_request.rule.alias)
+    let path = render_url_template(&broker_request.rule.alias, &params)?;
+    let uri: Uri = format!("{}{}", uri, path)
         .parse()
         .map_err(|e: InvalidUri| RippleError::BrokerError(e.to_string()))?;
 
     debug!("http_broker sending {} request={}", method, uri,);
 
-    let http_request = Request::builder()
-        .uri(uri)
-        .method(method)
+    let mut builder = Request::builder().uri(uri).method(method);
+    if has_body {
+        builder = builder.header(CONTENT_TYPE, "application/json");
+    }
+    // rule headers win over endpoint headers
+    let mut headers = endpoint.headers.clone();
+    headers.extend(http_rule.headers);
+    if let Some(request_headers) = builder.headers_mut() {
+        for (name, value) in headers {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|e| RippleError::BrokerError(e.to_string()))?;
+            let value = HeaderValue::from_str(&value)
+                .map_err(|e| RippleError::BrokerError(e.to_string()))?;
+            request_headers.insert(name, value);
+        }
+    }
+    if http_rule.auth.unwrap_or(endpoint.auth) {
+        let token = token.ok_or(RippleError::BrokerError(
+            "no account session token for authenticated http request".to_owned(),
+        ))?;
+        builder = builder.header(AUTHORIZATION, format!("Bearer {}", token));
+    }
+
+    builder
         .body(body)
-        .map_err(|e| RippleError::BrokerError(e.to_string()))?;
+        .map_err(|e| RippleError::BrokerError(e.to_string()))
+}
 
-    match client.request(http_request).await {
-        Ok(v) => Ok(v),
-        Err(e) => {
-            error!("Error in server");
-            Err(RippleError::BrokerError(e.to_string()))
+fn is_idempotent(method: &Method) -> bool {
+    matches!(*method, Method::GET | Method::PUT | Method::DELETE)
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+async fn send_http_request<C>(
+    client: &Client<C>,
+    uri: &Uri,
+    endpoint: &RuleEndpoint,
+    token: Option<String>,
+    broker_request: BrokerRequest,
+) -> Result<Response<Body>, RippleError>
+where
+    C: Connect + Clone + Send + Sync + 'static,
+{
+    let request_timeout =
+        Duration::from_millis(endpoint.timeout_ms.unwrap_or(DEFAULT_HTTP_TIMEOUT_MS));
+    let mut attempt = 0;
+    loop {
+        // the request is rebuilt for every attempt as the body can only be sent once
+        let http_request = build_http_request(uri, endpoint, token.as_ref(), &broker_request)?;
+        let retries = if is_idempotent(http_request.method()) {
+            endpoint.retries.min(HTTP_MAX_RETRIES)
+        } else {
+            0
+        };
+        let last_attempt = attempt >= retries;
+
+        /* the deadline covers reading the body as well, a service sending the headers and
+        stalling on the body must not block the broker */
+        let response = timeout(request_timeout, async {
+            let (parts, body) = client.request(http_request).await?.into_parts();
+            let body = hyper::body::to_bytes(body).await?;
+            Ok::<_, hyper::Error>(Response::from_parts(parts, Body::from(body)))
+        });
+        let error = match response.await {
+            Ok(Ok(response)) if last_attempt || !is_retryable_status(response.status()) => {
+                return Ok(response)
+            }
+            Ok(Ok(response)) => RippleError::BrokerError(response.status().to_string()),
+            Ok(Err(e)) => {
+                error!("Error in server");
+                RippleError::BrokerError(e.to_string())
+            }
+            Err(_) => RippleError::TimeoutError,
+        };
+        if last_attempt {
+            return Err(error);
+        }
+        let backoff = retry_backoff_ms(attempt);
+        warn!(
+            "http_broker retrying {} in {} ms after {}",
+            broker_request.rpc.ctx.method, backoff, error
+        );
+        sleep(Duration::from_millis(backoff)).await;
+        attempt += 1;
+    }
+}
+
+fn retry_backoff_ms(attempt: u32) -> u64 {
+    2u64.checked_pow(attempt)
+        .map_or(u64::MAX, |factor| {
+            HTTP_RETRY_BACKOFF_MS.saturating_mul(factor)
+        })
+        .min(HTTP_MAX_RETRY_BACKOFF_MS)
+}
+
+/// Https client trusting the CA bundle from the device manifest, or the bundled web pki roots.
+fn build_client(
+    ca_bundle_path: Option<String>,
+) -> Result<Client<HttpsConnector<HttpConnector>>, RippleError> {
+    let mut roots = RootCertStore::empty();
+    match ca_bundle_path {
+        Some(path) => {
+            let file = File::open(&path)
+                .map_err(|e| RippleError::BrokerError(format!("{}: {}", path, e)))?;
+            let certs = rustls_pemfile::certs(&mut BufReader::new(file))
+                .map_err(|e| RippleError::BrokerError(format!("{}: {}", path, e)))?;
+            let (added, ignored) = roots.add_parsable_certificates(&certs);
+            debug!(
+                "http_broker loaded {} certificates from {}, {} ignored",
+                added, path, ignored
+            );
         }
+        None => roots.add_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.iter().map(|ta| {
+            OwnedTrustAnchor::from_subject_spki_name_constraints(
+                ta.subject,
+                ta.spki,
+                ta.name_constraints,
+            )
+        })),
     }
+    let tls_config = ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    let connector = HttpsConnectorBuilder::new()
+        .with_tls_config(tls_config)
+        .https_or_http()
+        .enable_http1()
+        .build();
+    Ok(Client::builder()
+        .pool_idle_timeout(Duration::from_secs(HTTP_POOL_IDLE_TIMEOUT_SECS))
+        .build(connector))
 }
 
 async fn send_broker_response(
@@ -135,24 +366,68 @@ async fn body_to_bytes(body: Body) -> Vec<u8> {
     }
 }
 
+impl HttpBroker {
+    /// Answers every request of a broker which could not start with a broker error, so the
+    /// callers are not left waiting on a closed channel.
+    fn reject_requests(
+        mut tr: mpsc::Receiver<BrokerRequest>,
+        callback: BrokerCallback,
+        reason: String,
+    ) {
+        tokio::spawn(async move {
+            while let Some(request) = tr.recv().await {
+                Self::send_broker_failure_response(
+                    &callback,
+                    JsonRpcApiError::default()
+                        .with_id(request.rpc.ctx.call_id)
+                        .with_code(HTTP_BROKER_ERROR)
+                        .with_message(reason.clone())
+                        .into(),
+                );
+            }
+        });
+    }
+}
+
 impl EndpointBroker for HttpBroker {
     fn get_broker(
-        _ps: Option<PlatformState>,
+        ps: Option<PlatformState>,
         request: BrokerConnectRequest,
         callback: BrokerCallback,
         _broker_state: &mut EndpointBrokerState,
     ) -> Self {
         let endpoint = request.endpoint.clone();
+        // the session token is looked up per request as it is refreshed while ripple runs
+        let session_state = ps.as_ref().map(|ps| ps.session_state.clone());
+        let connect_session = request.session.clone();
         let (tx, mut tr) = mpsc::channel(BROKER_CHANNEL_BUFFER_SIZE);
         let broker = BrokerSender { sender: tx };
-        let client = Client::new();
+        let ca_bundle_path = ps
+            .as_ref()
+            .and_then(|ps| ps.get_device_manifest().get_ca_bundle_path());
+        let client = match build_client(ca_bundle_path) {
+            Ok(client) => client,
+            Err(e) => {
+                error!("invalid CA bundle, cannot start http broker. error={}", e);
+                Self::reject_requests(tr, callback, format!("http broker unavailable: {}", e));
+                return Self {
+                    sender: broker,
+                    cleaner: BrokerCleaner { cleaner: None },
+                };
+            }
+        };
 
         let _ =  endpoint.get_url().parse().map_err(|e| error!("broker url {:?} in endpoint is invalid, cannot start http broker. error={}",endpoint,e) ).map(|uri| tokio::spawn(async move {
             while let Some(mut request) = tr.recv().await {
                 LogSignal::new("http_broker".to_string(), format!("received request - start processing request={:?}", request), request.rpc.ctx.clone())
                     .with_diagnostic_context_item("rule_alias", request.rule.alias.as_str()).emit_debug();
 
-                match send_http_request(&client, &uri, request.clone())
+                let token = session_state
+                    .as_ref()
+                    .and_then(|state| state.get_account_session())
+                    .or(connect_session.clone())
+                    .map(|session| session.token);
+                match send_http_request(&client, &uri, &endpoint, token, request.clone())
                     .await
                 {
                     Ok(response) => {
@@ -166,7 +441,18 @@ impl EndpointBroker for HttpBroker {
                         let (parts, body) = response.into_parts();
                         let body = body_to_bytes(body).await;
 
-                        if !body.is_empty() {
+                        if !parts.status.is_success() {
+                            let msg = format!("http error {} returned from http service at {}: {}",
+                                parts.status, uri, String::from_utf8_lossy(&body));
+                            LogSignal::new("http_broker".to_string(), "Prepare request failed".to_string(), request.rpc.ctx.clone())
+                                .with_diagnostic_context_item("error", &msg)
+                                .emit_error();
+                            Self::send_broker_failure_response(&callback,
+                                JsonRpcApiError::default()
+                                .with_id(request.rpc.ctx.call_id)
+                                .with_code(status_to_error_code(parts.status))
+                                .with_message(msg).into());
+                        } else if !body.is_empty() {
                             if let Ok(json_str) = serde_json::from_slice::<serde_json::Value>(&body).map(|v| vec![v])
                             .and_then(|v| serde_json::to_string(&v))
                             {
@@ -185,13 +471,6 @@ impl EndpointBroker for HttpBroker {
                         } else {
                             send_broker_response(&callback, &request, &body).await.ok();
                         }
-
-                        if !parts.status.is_success() {
-                            LogSignal::new("http_broker".to_string(), "Prepare request failed".to_string(), request.rpc.ctx.clone())
-                            .with_diagnostic_context_item("error", &format!("http error {} returned from http service in http broker {:?}",
-                                parts.status, body))
-                            .emit_error();
-                        }
                     }
                     Err(err) => {
                         let msg = format!("An error message from calling the downstream http service={} in http broker {:?}", uri, err);
@@ -225,15 +504,23 @@ mod tests {
     use serde_json::{json, Value};
     use std::time::Duration;
 
-    use crate::broker::{
-        endpoint_broker::BrokerOutput,
-        rules::rules_engine::{Rule, RuleEndpoint, RuleEndpointProtocol},
+    use crate::{
+        broker::{
+            endpoint_broker::BrokerOutput,
+            rules::rules_engine::{HttpRule, Rule, RuleEndpointProtocol, RuleTransform},
+        },
+        service::extn::ripple_client::RippleClient,
+        state::bootstrap_state::ChannelsState,
     };
+    use std::collections::HashMap;
 
     use super::*;
 
     use ripple_sdk::{
-        api::gateway::rpc_gateway_api::{JsonRpcApiResponse, RpcRequest},
+        api::{
+            gateway::rpc_gateway_api::{JsonRpcApiResponse, RpcRequest},
+            manifest::{device_manifest::DeviceManifest, extn_manifest::ExtnManifest},
+        },
         tokio::{runtime::Runtime, task::JoinHandle, time::timeout},
         Mockable,
     };
@@ -285,6 +572,7 @@ mod tests {
             url: base_uri.to_string(),
             protocol: RuleEndpointProtocol::Http,
             jsonrpc: false,
+            ..Default::default()
         };
 
         let (tx, _) = mpsc::channel(BROKER_CHANNEL_BUFFER_SIZE);
@@ -504,8 +792,14 @@ mod tests {
             let uri_clone = base_uri.clone();
             let broker_request_clone = broker_request.clone();
             let handle: JoinHandle<Result<Value, String>> = tokio::spawn(async move {
-                let response_result =
-                    send_http_request(&client_clone, &uri_clone, broker_request).await;
+                let response_result = send_http_request(
+                    &client_clone,
+                    &uri_clone,
+                    &RuleEndpoint::default(),
+                    None,
+                    broker_request,
+                )
+                .await;
                 match response_result {
                     Ok(response) => {
                         if response.status() != StatusCode::OK {
@@ -572,7 +866,14 @@ mod tests {
         };
 
         let base_uri: Uri = "http://localhost:1234/".parse().unwrap();
-        let result = send_http_request(&client, &base_uri, broker_request).await;
+        let result = send_http_request(
+            &client,
+            &base_uri,
+            &RuleEndpoint::default(),
+            None,
+            broker_request,
+        )
+        .await;
         assert!(matches!(result, Err(RippleError::BrokerError(_))));
     }
 
@@ -602,9 +903,15 @@ mod tests {
         };
 
         let client = Client::new();
-        let response = send_http_request(&client, &base_uri, broker_request)
-            .await
-            .unwrap();
+        let response = send_http_request(
+            &client,
+            &base_uri,
+            &RuleEndpoint::default(),
+            None,
+            broker_request,
+        )
+        .await
+        .unwrap();
         assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
 
         let body_bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
@@ -641,13 +948,308 @@ mod tests {
         };
 
         let client = Client::new();
-        let response = send_http_request(&client, &base_uri, broker_request)
-            .await
-            .unwrap();
+        let response = send_http_request(
+            &client,
+            &base_uri,
+            &RuleEndpoint::default(),
+            None,
+            broker_request,
+        )
+        .await
+        .unwrap();
         assert_eq!(response.status(), StatusCode::OK);
 
         let body_bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
         let body_json: Value = serde_json::from_slice(&body_bytes).unwrap();
         assert_eq!(body_json, json!({"ok": true}));
     }
+
+    #[test]
+    fn test_render_url_template() {
+        let params = json!({"id": "a b/c", "lang": "en", "count": 3});
+        assert_eq!(
+            render_url_template("devices/{id}?lang={lang}&count={count}", &params).unwrap(),
+            "devices/a%20b%2Fc?lang=en&count=3"
+        );
+        assert_eq!(
+            render_url_template("devices", &Value::Null).unwrap(),
+            "devices"
+        );
+        assert!(matches!(
+            render_url_template("devices/{missing}", &params),
+            Err(RippleError::BrokerError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_send_http_request_method_headers_and_auth() {
+        let mock_server = MockServer::start();
+        let mock = mock_server.mock(|when, then| {
+            when.method(PUT)
+                .path("/devices/box1")
+                .query_param("lang", "en")
+                .header("authorization", "Bearer some_token")
+                .header("x-client", "ripple")
+                .header("x-api-key", "key")
+                .header("content-type", "application/json")
+                .json_body(json!({"name": "tv"}));
+            then.status(200).json_body(json!({"ok": true}));
+        });
+        let base_uri = mock_server.base_url().parse::<Uri>().unwrap();
+
+        let endpoint = RuleEndpoint {
+            headers: HashMap::from([("x-client".to_owned(), "ripple".to_owned())]),
+            auth: true,
+            ..Default::default()
+        };
+        let mut rpc = RpcRequest::mock();
+        rpc.params_json = json!([{}, {"id": "box1", "lang": "en", "name": "tv"}]).to_string();
+        let broker_request = BrokerRequest {
+            rpc,
+            rule: Rule {
+                alias: "devices/{id}?lang={lang}".to_string(),
+                transform: RuleTransform {
+                    request: Some("{ name: .name }".to_string()),
+                    ..Default::default()
+                },
+                http: Some(HttpRule {
+                    method: Some(HttpMethod::Put),
+                    headers: HashMap::from([("x-api-key".to_owned(), "key".to_owned())]),
+                    auth: None,
+                }),
+                ..Default::default()
+            },
+            subscription_processed: None,
+            workflow_callback: None,
+            telemetry_response_listeners: vec![],
+        };
+
+        let client = Client::new();
+        let response = send_http_request(
+            &client,
+            &base_uri,
+            &endpoint,
+            Some("some_token".to_owned()),
+            broker_request.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        mock.assert();
+
+        // authenticated endpoints are not called without a session token
+        let result = send_http_request(&client, &base_uri, &endpoint, None, broker_request).await;
+        assert!(matches!(result, Err(RippleError::BrokerError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_http_broker_maps_error_status() {
+        let mock_server = MockServer::start();
+        mock_server.mock(|when, then| {
+            when.method(GET).path("/unauthorized");
+            then.status(401)
+                .json_body(json!({"message": "token expired"}));
+        });
+        let base_uri = mock_server.base_url().parse::<Uri>().unwrap();
+
+        let output = send_and_receive_broker_output(base_uri, "unauthorized")
+            .await
+            .unwrap()
+            .unwrap();
+        let error = output.data.error.unwrap();
+        assert_eq!(error["code"], json!(CAPABILITY_NOT_PERMITTED));
+        assert!(error["message"].as_str().unwrap().contains("token expired"));
+        assert!(output.data.result.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_send_http_request_retries_idempotent_requests() {
+        let mock_server = MockServer::start();
+        let get_mock = mock_server.mock(|when, then| {
+            when.method(GET).path("/flaky");
+            then.status(503);
+        });
+        let post_mock = mock_server.mock(|when, then| {
+            when.method(POST).path("/flaky");
+            then.status(503);
+        });
+        let base_uri = mock_server.base_url().parse::<Uri>().unwrap();
+        let endpoint = RuleEndpoint {
+            retries: 2,
+            ..Default::default()
+        };
+        let client = Client::new();
+
+        let response = send_http_request(
+            &client,
+            &base_uri,
+            &endpoint,
+            None,
+            get_mock_broker_request("flaky"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(get_mock.hits(), 3);
+
+        let mut broker_request = get_mock_broker_request("flaky");
+        broker_request.rule.http = Some(HttpRule {
+            method: Some(HttpMethod::Post),
+            ..Default::default()
+        });
+        let response = send_http_request(&client, &base_uri, &endpoint, None, broker_request)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(post_mock.hits(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_send_http_request_timeout() {
+        let mock_server = MockServer::start();
+        mock_server.mock(|when, then| {
+            when.method(GET).path("/slow");
+            then.status(200).delay(Duration::from_millis(500));
+        });
+        let base_uri = mock_server.base_url().parse::<Uri>().unwrap();
+        let endpoint = RuleEndpoint {
+            timeout_ms: Some(50),
+            ..Default::default()
+        };
+
+        let result = send_http_request(
+            &Client::new(),
+            &base_uri,
+            &endpoint,
+            None,
+            get_mock_broker_request("slow"),
+        )
+        .await;
+        assert!(matches!(result, Err(RippleError::TimeoutError)));
+    }
+
+    #[test]
+    fn test_retry_backoff_is_capped() {
+        assert_eq!(retry_backoff_ms(0), HTTP_RETRY_BACKOFF_MS);
+        assert_eq!(retry_backoff_ms(3), 800);
+        assert_eq!(retry_backoff_ms(10), HTTP_MAX_RETRY_BACKOFF_MS);
+        assert_eq!(retry_backoff_ms(u32::MAX), HTTP_MAX_RETRY_BACKOFF_MS);
+    }
+
+    #[tokio::test]
+    async fn test_send_http_request_clamps_retries() {
+        let mock_server = MockServer::start();
+        let mock = mock_server.mock(|when, then| {
+            when.method(GET).path("/down");
+            then.status(502);
+        });
+        let base_uri = mock_server.base_url().parse::<Uri>().unwrap();
+        let endpoint = RuleEndpoint {
+            retries: u32::MAX,
+            ..Default::default()
+        };
+
+        let response = send_http_request(
+            &Client::new(),
+            &base_uri,
+            &endpoint,
+            None,
+            get_mock_broker_request("down"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+        assert_eq!(mock.hits(), HTTP_MAX_RETRIES as usize + 1);
+    }
+
+    #[tokio::test]
+    async fn test_send_http_request_times_out_slow_body() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_uri = format!("http://{}", listener.local_addr().unwrap())
+            .parse::<Uri>()
+            .unwrap();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = stream.read(&mut buf).await;
+            // headers are sent right away, the promised body never follows
+            let _ = stream
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 10\r\n\r\n{")
+                .await;
+            sleep(Duration::from_secs(5)).await;
+        });
+        let endpoint = RuleEndpoint {
+            timeout_ms: Some(100),
+            ..Default::default()
+        };
+
+        let result = send_http_request(
+            &Client::new(),
+            &base_uri,
+            &endpoint,
+            None,
+            get_mock_broker_request("stalled"),
+        )
+        .await;
+        assert!(matches!(result, Err(RippleError::TimeoutError)));
+    }
+
+    #[tokio::test]
+    async fn test_http_broker_rejects_requests_without_client() {
+        let (_, mut manifest) = DeviceManifest::load_from_content(
+            include_str!("../../../../examples/manifest/device-manifest-example.json").to_string(),
+        )
+        .unwrap();
+        manifest.configuration.ca_bundle_path = Some("/ripple/does/not/exist.pem".to_owned());
+        let state = PlatformState::new(
+            ExtnManifest::default(),
+            manifest,
+            RippleClient::new(ChannelsState::new()),
+            vec![],
+            None,
+        );
+        let endpoint = RuleEndpoint {
+            url: "http://127.0.0.1:1234/".to_owned(),
+            protocol: RuleEndpointProtocol::Http,
+            ..Default::default()
+        };
+        let (tx, _) = mpsc::channel(BROKER_CHANNEL_BUFFER_SIZE);
+        let (btx, mut brx) = mpsc::channel::<BrokerOutput>(BROKER_CHANNEL_BUFFER_SIZE);
+        let request = BrokerConnectRequest::new("somekey".to_owned(), endpoint, tx);
+        let callback = BrokerCallback { sender: btx };
+        let mut broker_state = EndpointBrokerState::default();
+
+        let broker = HttpBroker::get_broker(Some(state), request, callback, &mut broker_state);
+        for call_id in [1, 2] {
+            let mut broker_request = get_mock_broker_request("test_rule");
+            broker_request.rpc.ctx.call_id = call_id;
+            broker
+                .get_sender()
+                .sender
+                .send(broker_request)
+                .await
+                .unwrap();
+            let output = timeout(Duration::from_secs(5), brx.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            let error = output.data.error.unwrap();
+            assert_eq!(error["code"], json!(HTTP_BROKER_ERROR));
+            assert!(error["message"]
+                .as_str()
+                .unwrap()
+                .contains("/ripple/does/not/exist.pem"));
+        }
+    }
+
+    #[test]
+    fn test_build_client() {
+        assert!(build_client(None).is_ok());
+        assert!(matches!(
+            build_client(Some("/ripple/does/not/exist.pem".to_owned())),
+            Err(RippleError::BrokerError(_))
+        ));
+    }
 }
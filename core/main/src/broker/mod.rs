@@ -20,6 +20,7 @@ pub mod event_management_utility;
 pub mod extn_broker;
 pub mod http_broker;
 pub mod provider_broker_state;
+pub mod response_cache;
 pub mod rules;
 pub mod service_broker;
 #[cfg(test)]
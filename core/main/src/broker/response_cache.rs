@@ -0,0 +1,189 @@
+// Copyright 2025 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+use ripple_sdk::api::gateway::rpc_gateway_api::{JsonRpcApiResponse, RpcRequest};
+
+use super::rules::rules_engine::{Rule, RuleTransformType};
+
+const CONTEXT_VARIABLE: &str = "$context";
+
+#[derive(Debug, Clone)]
+struct CachedResponse {
+    response: JsonRpcApiResponse,
+    expires: Instant,
+    invalidate_on: Vec<String>,
+}
+
+/*
+Responses are cached before the response transform is applied, so a cache hit goes through the
+forwarder like any other endpoint response and the transform still sees the calling app context.
+*/
+#[derive(Debug, Clone, Default)]
+pub struct ResponseCache {
+    entries: Arc<RwLock<HashMap<String, CachedResponse>>>,
+}
+
+impl ResponseCache {
+    /// Returns the key for requests whose rule is cacheable. Rules using `$context` in a
+    /// transform are cached per app.
+    pub fn get_key(rule: &Rule, rpc_request: &RpcRequest) -> Option<String> {
+        rule.cache.as_ref()?;
+        if rpc_request.is_subscription() {
+            return None;
+        }
+        let params = rpc_request
+            .get_params()
+            .map(|p| p.to_string())
+            .unwrap_or_default();
+        let per_app = [RuleTransformType::Request, RuleTransformType::Response]
+            .into_iter()
+            .filter_map(|typ| rule.transform.get_transform_data(typ))
+            .any(|source| source.contains(CONTEXT_VARIABLE));
+        let method = rpc_request.method.to_lowercase();
+        Some(if per_app {
+            format!("{}:{}:{}", rpc_request.ctx.app_id, method, params)
+        } else {
+            format!("{}:{}", method, params)
+        })
+    }
+
+    pub fn get(&self, key: &str) -> Option<JsonRpcApiResponse> {
+        let entry = { self.entries.read().unwrap().get(key).cloned() }?;
+        if entry.expires > Instant::now() {
+            return Some(entry.response);
+        }
+        self.entries.write().unwrap().remove(key);
+        None
+    }
+
+    /// Keeps the first fresh response, a response served from the cache does not extend its ttl.
+    pub fn insert(&self, key: String, rule: &Rule, response: &JsonRpcApiResponse) {
+        let Some(cache) = &rule.cache else {
+            return;
+        };
+        let now = Instant::now();
+        let mut entries = self.entries.write().unwrap();
+        entries.retain(|_, e| e.expires > now);
+        entries.entry(key).or_insert_with(|| CachedResponse {
+            response: response.clone(),
+            expires: now + Duration::from_millis(cache.ttl_ms),
+            invalidate_on: cache
+                .invalidate_on
+                .iter()
+                .map(|e| e.to_lowercase())
+                .collect(),
+        });
+    }
+
+    /// Drops the responses invalidated by an event, matched against the firebolt event
+    /// method and the alias of its rule.
+    pub fn invalidate(&self, method: &str, alias: &str) {
+        let method = method.to_lowercase();
+        let alias = alias.to_lowercase();
+        self.entries.write().unwrap().retain(|_, e| {
+            !e.invalidate_on
+                .iter()
+                .any(|event| *event == method || *event == alias)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::broker::rules::rules_engine::{RuleCache, RuleTransform};
+    use ripple_sdk::{serde_json::json, Mockable};
+
+    fn rule(ttl_ms: u64, response: Option<&str>) -> Rule {
+        Rule {
+            alias: "org.rdk.System.getDeviceInfo".to_owned(),
+            transform: RuleTransform {
+                response: response.map(|r| r.to_owned()),
+                ..Default::default()
+            },
+            cache: Some(RuleCache {
+                ttl_ms,
+                invalidate_on: vec!["device.onNameChanged".to_owned()],
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn request(method: &str, app_id: &str) -> RpcRequest {
+        let mut request = RpcRequest::mock();
+        request.method = method.to_owned();
+        request.ctx.app_id = app_id.to_owned();
+        request
+    }
+
+    #[test]
+    fn test_get_key() {
+        let cached = rule(1000, Some(".result.name"));
+        let key = ResponseCache::get_key(&cached, &request("device.name", "app1"));
+        assert!(key.is_some());
+        assert_eq!(
+            key,
+            ResponseCache::get_key(&cached, &request("Device.name", "app2"))
+        );
+
+        let mut with_params = request("device.name", "app1");
+        with_params.params_json = json!([{}, {"locale": "en-US"}]).to_string();
+        assert_ne!(key, ResponseCache::get_key(&cached, &with_params));
+
+        let per_app = rule(1000, Some("{app: $context.appId, name: .result.name}"));
+        assert_ne!(
+            ResponseCache::get_key(&per_app, &request("device.name", "app1")),
+            ResponseCache::get_key(&per_app, &request("device.name", "app2"))
+        );
+
+        let uncached = Rule::default();
+        assert!(ResponseCache::get_key(&uncached, &request("device.name", "app1")).is_none());
+    }
+
+    #[test]
+    fn test_cache_expiry_and_invalidation() {
+        let cache = ResponseCache::default();
+        let response = JsonRpcApiResponse::new(Some(1), None)
+            .with_result(Some(json!({"name": "Living Room"})));
+        let cached = rule(60_000, None);
+        cache.insert("device.name".to_owned(), &cached, &response);
+        assert_eq!(
+            cache.get("device.name").unwrap().result,
+            Some(json!({"name": "Living Room"}))
+        );
+
+        cache.invalidate(
+            "device.onHdcpChanged",
+            "org.rdk.HdcpProfile.onDisplayConnectionChanged",
+        );
+        assert!(cache.get("device.name").is_some());
+        cache.invalidate(
+            "Device.onNameChanged",
+            "org.rdk.System.onFriendlyNameChanged",
+        );
+        assert!(cache.get("device.name").is_none());
+
+        cache.insert("device.name".to_owned(), &rule(0, None), &response);
+        assert!(cache.get("device.name").is_none());
+    }
+}
@@ -14,7 +14,7 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 //
-use jaq_interpret::{Ctx, FilterT, ParseCtx, RcIter, Val};
+use jaq_interpret::{Ctx, Filter, FilterT, ParseCtx, RcIter, Val};
 use ripple_sdk::api::{
     gateway::rpc_gateway_api::RpcRequest, manifest::extn_manifest::ExtnManifest,
 };
@@ -22,13 +22,13 @@ use ripple_sdk::api::{
 use ripple_sdk::{
     chrono::Utc,
     log::{debug, error, info, trace, warn},
-    serde_json::Value,
+    serde_json::{json, Value},
     utils::error::RippleError,
 };
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-use std::sync::{Mutex, MutexGuard, Once};
+use std::sync::{Arc, Mutex, MutexGuard, Once, OnceLock};
 use std::{fs, path::Path};
 
 use super::rules_functions::{apply_functions, RulesFunction, RulesImport};
@@ -36,16 +36,28 @@ use super::rules_functions::{apply_functions, RulesFunction, RulesImport};
 static BASE_PARSE_CTX_INIT: Once = Once::new();
 static mut BASE_PARSE_CTX_PTR: Option<Mutex<ParseCtx>> = None;
 
+/*
+Global jq variables every filter is compiled against, in binding order.
+Values are supplied per call through RuleVariables.
+*/
+const RULE_VARIABLES: [&str; 2] = ["context", "event_handler_response"];
+const CONTEXT_APP_ID: &str = "$context.appId";
+
 #[derive(Debug, Deserialize, Default, Clone)]
 pub struct RuleSet {
     #[serde(default)]
     pub imports: Vec<String>,
     pub endpoints: HashMap<String, RuleEndpoint>,
-    pub rules: HashMap<String, Rule>,
+    /// Only changed through the mutators below, which keep the wildcard index in sync
+    rules: HashMap<String, Rule>,
+    /// Built on the first wildcard lookup and reset whenever rules are changed
+    #[serde(skip)]
+    wildcards: OnceLock<WildcardIndex>,
 }
 
 impl RuleSet {
     pub fn append(&mut self, rule_set: RuleSet) {
+        self.wildcards = OnceLock::new();
         for import in rule_set.imports {
             if !self.imports.contains(&import) {
                 self.imports.push(import);
@@ -66,6 +78,30 @@ impl RuleSet {
     pub fn get(&self, key: &str) -> Option<&Rule> {
         self.rules.get(key)
     }
+    pub fn insert(&mut self, key: String, rule: Rule) {
+        self.wildcards = OnceLock::new();
+        self.rules.insert(key, rule);
+    }
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&String, &mut Rule)> {
+        self.wildcards = OnceLock::new();
+        self.rules.iter_mut()
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Rule)> {
+        self.rules.iter()
+    }
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.rules.contains_key(key)
+    }
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+    fn wildcard_index(&self) -> &WildcardIndex {
+        self.wildcards
+            .get_or_init(|| WildcardIndex::build(&self.rules))
+    }
 }
 #[derive(Debug, Deserialize, Clone, Default)]
 pub struct RuleEndpoint {
@@ -73,6 +109,20 @@ pub struct RuleEndpoint {
     pub url: String,
     #[serde(default = "default_autostart")]
     pub jsonrpc: bool,
+    /// Headers added to every http request sent to this endpoint
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Sends the account session token as a bearer Authorization header
+    #[serde(default)]
+    pub auth: bool,
+    /// Timeout of each http request including its response body, the http broker default
+    /// applies when not set
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    /// Retries of idempotent http requests failing on connection, timeout or gateway errors,
+    /// at most 5 retries are made
+    #[serde(default)]
+    pub retries: u32,
 }
 
 impl RuleEndpoint {
@@ -112,6 +162,41 @@ pub struct JsonDataSource {
     pub params: Option<String>,
 }
 
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum HttpMethod {
+    #[default]
+    Get,
+    Post,
+    Put,
+    Patch,
+    Delete,
+}
+
+/// Request settings for rules served by an http endpoint.
+/// The rule alias is the url path, "{param}" placeholders are replaced with request params.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct HttpRule {
+    /// Defaults to POST when the rule has a request transform and GET otherwise
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub method: Option<HttpMethod>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+    /// Overrides the auth setting of the endpoint
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth: Option<bool>,
+}
+
+/// Keeps successful responses of a rule in memory, keyed by method and params.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct RuleCache {
+    pub ttl_ms: u64,
+    /// Events which drop the cached responses, either the firebolt event or the rule alias
+    /// of the event, the event only flows while some app listens to it
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub invalidate_on: Vec<String>,
+}
+
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct EventHandler {
     pub method: String,
@@ -133,6 +218,12 @@ pub struct Rule {
     pub endpoint: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sources: Option<Vec<JsonDataSource>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http: Option<HttpRule>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache: Option<RuleCache>,
+    #[serde(skip)]
+    pub compiled: Option<Arc<CompiledRule>>,
 }
 impl std::fmt::Display for Rule {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -191,6 +282,267 @@ impl Rule {
         }
         self
     }
+
+    /// Expands `$function.` calls and compiles every jq filter of the rule once, so that
+    /// requests, responses and events only have to run the cached filters.
+    /// Filters which fail to compile are cached as errors and reported in the result, so a
+    /// broken filter is not recompiled on every request.
+    pub fn compile(
+        &mut self,
+        name: &str,
+        functions: &HashMap<String, RulesFunction>,
+    ) -> Result<(), RippleError> {
+        let mut result = self
+            .transform
+            .apply_functions(functions)
+            .and(RuleTransform::expand_functions(&mut self.filter, functions));
+        if let Err(e) = &result {
+            error!("Error applying functions in rule {}: {:?}", name, e);
+        }
+
+        let mut compile = |source: Option<String>, suffix: &str| {
+            let compiled = CompiledFilter::compile(&source?, &format!("{}_{}", name, suffix));
+            if let Err(e) = &compiled {
+                result = Err(e.clone());
+            }
+            Some(compiled)
+        };
+        let compiled = CompiledRule {
+            request: compile(
+                self.transform.get_filter_source(RuleTransformType::Request),
+                "request",
+            ),
+            response: compile(
+                self.transform
+                    .get_filter_source(RuleTransformType::Response),
+                "response",
+            ),
+            event: compile(
+                self.transform
+                    .get_filter_source(RuleTransformType::Event(false)),
+                "event",
+            ),
+            rpcv2_event: compile(
+                self.transform
+                    .get_filter_source(RuleTransformType::Event(true)),
+                "rpcv2_event",
+            ),
+            filter: compile(self.filter.clone(), "event filter"),
+        };
+        self.compiled = Some(Arc::new(compiled));
+        result
+    }
+
+    /// Returns the compiled filter for the given transform type, if the rule has one.
+    /// Rules which did not go through [Rule::compile] are compiled on the fly.
+    pub fn get_compiled_transform(
+        &self,
+        typ: RuleTransformType,
+    ) -> Option<Result<CompiledFilter, RippleError>> {
+        let cached = self.compiled.as_ref().and_then(|c| match typ {
+            RuleTransformType::Request => c.request.clone(),
+            RuleTransformType::Response => c.response.clone(),
+            RuleTransformType::Event(false) => c.event.clone(),
+            RuleTransformType::Event(true) => c.rpcv2_event.clone(),
+        });
+        if cached.is_some() {
+            return cached;
+        }
+        self.transform
+            .get_filter_source(typ)
+            .map(|source| CompiledFilter::compile(&source, &self.alias))
+    }
+
+    /// Returns the compiled event filter of the rule, if the rule has one.
+    pub fn get_compiled_filter(&self) -> Option<Result<CompiledFilter, RippleError>> {
+        if let Some(filter) = self.compiled.as_ref().and_then(|c| c.filter.clone()) {
+            return Some(filter);
+        }
+        self.filter
+            .as_ref()
+            .map(|source| CompiledFilter::compile(source, &self.alias))
+    }
+}
+
+/// Filters of a [Rule] compiled at load time, shared by every request using the rule.
+/// Compile failures are kept as well, `None` means the rule has no such filter.
+#[derive(Debug, Clone, Default)]
+pub struct CompiledRule {
+    pub request: Option<Result<CompiledFilter, RippleError>>,
+    pub response: Option<Result<CompiledFilter, RippleError>>,
+    pub event: Option<Result<CompiledFilter, RippleError>>,
+    pub rpcv2_event: Option<Result<CompiledFilter, RippleError>>,
+    pub filter: Option<Result<CompiledFilter, RippleError>>,
+}
+
+/// Values bound to the global jq variables of a filter when it runs.
+#[derive(Debug, Clone, Default)]
+pub struct RuleVariables {
+    pub app_id: String,
+    pub event_handler_response: Option<Value>,
+}
+
+impl RuleVariables {
+    pub fn new(app_id: &str) -> Self {
+        Self {
+            app_id: app_id.to_owned(),
+            event_handler_response: None,
+        }
+    }
+
+    pub fn with_event_handler_response(mut self, response: Value) -> Self {
+        self.event_handler_response = Some(response);
+        self
+    }
+
+    fn get_values(&self) -> [Val; 2] {
+        [
+            Val::from(json!({ "appId": self.app_id })),
+            Val::from(self.event_handler_response.clone().unwrap_or(Value::Null)),
+        ]
+    }
+}
+
+impl From<&RpcRequest> for RuleVariables {
+    fn from(rpc_request: &RpcRequest) -> Self {
+        Self::new(&rpc_request.ctx.app_id)
+    }
+}
+
+/// A parsed and compiled jq filter which can be run any number of times without
+/// going through the shared parse context.
+#[derive(Clone)]
+pub struct CompiledFilter {
+    source: String,
+    filter: Arc<Filter>,
+}
+
+impl std::fmt::Debug for CompiledFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CompiledFilter {{ source: {} }}", self.source)
+    }
+}
+
+impl CompiledFilter {
+    pub fn compile(source: &str, reference: &str) -> Result<Self, RippleError> {
+        Self::try_compile(source).map_err(|e| {
+            error!("Error in rule {}: {}", reference, e);
+            RippleError::RuleError
+        })
+    }
+
+    /// Compiles the filter, returning the jq parse or compile errors as text.
+    pub fn try_compile(source: &str) -> Result<Self, String> {
+        let filter = bind_context_variables(source);
+        let (main, errs) = jaq_parse::parse(&filter, jaq_parse::main());
+        if !errs.is_empty() {
+            return Err(format!("{:?}", errs));
+        }
+        let Some(main) = main else {
+            return Err("empty filter".to_owned());
+        };
+        // Lock and use the shared ParseCtx
+        let mut defs = get_parse_ctx();
+        // compile the filter in the context of the given definitions
+        let compiled = defs.compile(main);
+        if !defs.errs.is_empty() {
+            let errors: Vec<String> = defs.errs.iter().map(|(err, _)| err.to_string()).collect();
+            defs.errs.clear(); // Clear errors before returning
+            return Err(errors.join(", "));
+        }
+        Ok(Self {
+            source: source.to_owned(),
+            filter: Arc::new(compiled),
+        })
+    }
+
+    pub fn get_source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn run(
+        &self,
+        input: Value,
+        variables: &RuleVariables,
+        reference: &str,
+    ) -> Result<Value, RippleError> {
+        info!(
+            "Jq rule {}  input {:?}, reference {}",
+            self.source, input, reference
+        );
+        let start = Utc::now().timestamp_millis();
+        let inputs = RcIter::new(core::iter::empty());
+        // iterator over the output values
+        let mut out = self
+            .filter
+            .run((Ctx::new(variables.get_values(), &inputs), Val::from(input)));
+        if let Some(Ok(v)) = out.next() {
+            info!(
+                "Ripple Gateway Rule Processing Time: {},{}",
+                reference,
+                Utc::now().timestamp_millis() - start
+            );
+            return Ok(Value::from(v));
+        }
+
+        Err(RippleError::ParseError)
+    }
+}
+
+/*
+Rules reference the calling app as "$context.appId", usually inside a string literal
+such as "{ namespace: \"$context.appId\" }". Inside strings the variable is turned into
+an interpolation so it can be bound at run time instead of being spliced into the filter text.
+*/
+fn bind_context_variables(filter: &str) -> String {
+    let mut output = String::with_capacity(filter.len());
+    let mut in_string = false;
+    let mut chars = filter.char_indices();
+    while let Some((index, character)) = chars.next() {
+        if in_string && filter[index..].starts_with(CONTEXT_APP_ID) {
+            output.push_str(&format!("\\({})", CONTEXT_APP_ID));
+            // skip the remainder of the variable name
+            for _ in 1..CONTEXT_APP_ID.len() {
+                chars.next();
+            }
+            continue;
+        }
+        output.push(character);
+        match character {
+            '\\' if in_string => {
+                if let Some((_, escaped)) = chars.next() {
+                    output.push(escaped);
+                }
+            }
+            '"' => in_string = !in_string,
+            _ => {}
+        }
+    }
+    output
+}
+
+/*
+Replaces the "$event" placeholder with the given filter, leaving longer
+variable names such as "$event_handler_response" untouched.
+*/
+fn inline_event_filter(input: &str, event: &str) -> String {
+    const EVENT_PLACEHOLDER: &str = "$event";
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(index) = rest.find(EVENT_PLACEHOLDER) {
+        let end = index + EVENT_PLACEHOLDER.len();
+        output.push_str(&rest[..index]);
+        let is_placeholder =
+            !rest[end..].starts_with(|c: char| c.is_ascii_alphanumeric() || c == '_');
+        if is_placeholder {
+            output.push_str(event);
+        } else {
+            output.push_str(EVENT_PLACEHOLDER);
+        }
+        rest = &rest[end..];
+    }
+    output.push_str(rest);
+    output
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
@@ -208,61 +560,41 @@ pub struct RuleTransform {
 }
 
 impl RuleTransform {
-    fn check_and_replace(&self, input: &str, rpc_request: &RpcRequest) -> String {
-        trace!(
-            "check_and_replace: input: {}, rpc_request: {:?}",
-            input,
-            rpc_request
-        );
-
-        let mut output = input.replace("$context.appId", &rpc_request.ctx.app_id);
-
-        if let Some(event) = &self.event {
-            output = output.replace("$event", event);
+    fn expand_functions(
+        transform: &mut Option<String>,
+        imports: &HashMap<String, RulesFunction>,
+    ) -> Result<(), RippleError> {
+        if let Some(value) = transform.as_ref() {
+            let _ = transform.insert(apply_functions(value, imports)?);
         }
-
-        output
+        Ok(())
     }
 
-    pub fn apply_functions(&mut self, imports: &HashMap<String, RulesFunction>) {
-        if let Some(transform) = self.request.take() {
-            if let Ok(transformed) = apply_functions(&transform, imports) {
-                let _ = self.request.insert(transformed);
-            }
-        }
-
-        if let Some(transform) = self.response.take() {
-            if let Ok(transformed) = apply_functions(&transform, imports) {
-                let _ = self.response.insert(transformed);
-            }
-        }
+    pub fn apply_functions(
+        &mut self,
+        imports: &HashMap<String, RulesFunction>,
+    ) -> Result<(), RippleError> {
+        // attempt every transform so that a single bad call does not hide the others
+        [
+            Self::expand_functions(&mut self.request, imports),
+            Self::expand_functions(&mut self.response, imports),
+            Self::expand_functions(&mut self.event, imports),
+            Self::expand_functions(&mut self.rpcv2_event, imports),
+        ]
+        .into_iter()
+        .collect()
     }
 
-    pub fn apply_variables(&mut self, rpc_request: &RpcRequest) -> &mut Self {
-        if let Some(value) = self.request.take() {
-            let _ = self
-                .request
-                .insert(self.check_and_replace(&value, rpc_request));
-        }
-
-        if let Some(value) = self.response.take() {
-            let _ = self
-                .response
-                .insert(self.check_and_replace(&value, rpc_request));
-        }
-
-        if let Some(value) = self.event.take() {
-            let _ = self
-                .event
-                .insert(self.check_and_replace(&value, rpc_request));
-        }
-
-        if let Some(value) = self.rpcv2_event.take() {
-            let _ = self
-                .rpcv2_event
-                .insert(self.check_and_replace(&value, rpc_request));
+    /// Returns the jq source for the given transform type with the "$event" placeholder
+    /// replaced by the event transform.
+    pub fn get_filter_source(&self, typ: RuleTransformType) -> Option<String> {
+        let source = self.get_transform_data(typ.clone())?;
+        match (&self.event, typ) {
+            (Some(event), typ) if !matches!(typ, RuleTransformType::Event(false)) => {
+                Some(inline_event_filter(&source, event))
+            }
+            _ => Some(source),
         }
-        self
     }
 
     pub fn get_transform_data(&self, typ: RuleTransformType) -> Option<String> {
@@ -280,18 +612,139 @@ impl RuleTransform {
     }
 }
 
+#[derive(Debug, Clone)]
 pub enum RuleTransformType {
     Request,
     Response,
     Event(bool),
 }
 
+/// Reasons a rules file set could not be loaded.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuleLoadError {
+    /// Rules or import file could not be read: (path, reason)
+    UnreadablePath(String, String),
+    /// Rules file is not a valid rule set: (path, reason)
+    InvalidRuleFile(String, String),
+    /// Imported functions file is not valid: (path, reason)
+    InvalidImport(String, String),
+    /// Rule whose jq filters failed to compile
+    CompileFailed(String),
+}
+
+impl std::fmt::Display for RuleLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RuleLoadError::UnreadablePath(path, e) => {
+                write!(f, "invalid rule path {}: {}", path, e)
+            }
+            RuleLoadError::InvalidRuleFile(path, e) => {
+                write!(f, "invalid rules file {}: {}", path, e)
+            }
+            RuleLoadError::InvalidImport(path, e) => {
+                write!(f, "invalid rules import {}: {}", path, e)
+            }
+            RuleLoadError::CompileFailed(name) => write!(f, "rule {} failed to compile", name),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct RuleEngine {
     pub rules: RuleSet,
     pub functions: HashMap<String, RulesFunction>,
 }
 
+/*
+A "*" segment in a rule name matches any single segment of the method name, a trailing "*"
+matches one or more segments. For example "device.*", "device.info.*" and "*.onchanged".
+*/
+#[derive(Debug, Clone)]
+struct WildcardPattern {
+    name: String,
+    segments: Vec<String>,
+    /// literal segments in front of the first "*"
+    prefix_len: usize,
+    literals: usize,
+}
+
+impl WildcardPattern {
+    fn parse(name: &str) -> Option<Self> {
+        let segments: Vec<String> = name.split('.').map(|s| s.to_owned()).collect();
+        let prefix_len = segments.iter().position(|s| s == "*")?;
+        Some(Self {
+            name: name.to_owned(),
+            literals: segments.iter().filter(|s| *s != "*").count(),
+            segments,
+            prefix_len,
+        })
+    }
+
+    fn is_trailing(&self) -> bool {
+        self.segments.last().is_some_and(|s| s == "*")
+    }
+
+    /// Longer literal prefix first, then more literal segments, then fixed length patterns.
+    fn specificity(&self) -> (usize, usize, bool) {
+        (self.prefix_len, self.literals, !self.is_trailing())
+    }
+
+    fn matches(&self, method: &[&str]) -> bool {
+        let length_matches = if self.is_trailing() {
+            method.len() >= self.segments.len()
+        } else {
+            method.len() == self.segments.len()
+        };
+        length_matches
+            && self
+                .segments
+                .iter()
+                .zip(method)
+                .all(|(pattern, segment)| pattern == "*" || pattern == segment)
+    }
+}
+
+/// Wildcard rules grouped by the literal prefix in front of their first "*", most specific first.
+#[derive(Debug, Clone, Default)]
+struct WildcardIndex {
+    by_prefix: HashMap<String, Vec<WildcardPattern>>,
+}
+
+impl WildcardIndex {
+    fn build(rules: &HashMap<String, Rule>) -> Self {
+        let mut by_prefix: HashMap<String, Vec<WildcardPattern>> = HashMap::new();
+        for pattern in rules.keys().filter_map(|name| WildcardPattern::parse(name)) {
+            let prefix = pattern.segments[..pattern.prefix_len].join(".");
+            by_prefix.entry(prefix).or_default().push(pattern);
+        }
+        for patterns in by_prefix.values_mut() {
+            patterns.sort_by_key(|p| std::cmp::Reverse(p.specificity()));
+        }
+        Self { by_prefix }
+    }
+
+    /// Returns the name of the most specific wildcard rule matching the method.
+    fn find(&self, method: &str) -> Result<&str, RuleRetrievalError> {
+        let segments: Vec<&str> = method.split('.').collect();
+        for prefix_len in (0..segments.len()).rev() {
+            let Some(patterns) = self.by_prefix.get(&segments[..prefix_len].join(".")) else {
+                continue;
+            };
+            let mut matching = patterns.iter().filter(|p| p.matches(&segments));
+            if let Some(best) = matching.next() {
+                if matching
+                    .next()
+                    .is_some_and(|next| next.specificity() == best.specificity())
+                {
+                    return Err(RuleRetrievalError::TooManyWildcardMatches);
+                }
+                return Ok(&best.name);
+            }
+        }
+        Err(RuleRetrievalError::RuleNotFoundAsWildcard)
+    }
+}
+
 impl RuleEngine {
     fn build_path(path: &str, default_path: &str) -> String {
         if path.starts_with('/') {
@@ -315,36 +768,62 @@ impl RuleEngine {
         let (_content, rule_set) = Self::load_from_content(contents)?;
         let mut rules_engine = RuleEngine::default();
         rules_engine.rules.append(rule_set);
-        Ok(rules_engine.clone())
+        rules_engine.compile_rules();
+        Ok(rules_engine)
     }
 
     pub fn build(extn_manifest: &ExtnManifest) -> Self {
+        let (engine, errors) = Self::build_with_errors(extn_manifest);
+        for e in errors {
+            warn!("{}", e);
+        }
+        engine
+    }
+
+    /// Builds the rules engine only if every rules file, import and jq filter loads cleanly.
+    pub fn try_build(extn_manifest: &ExtnManifest) -> Result<Self, Vec<RuleLoadError>> {
+        let (engine, errors) = Self::build_with_errors(extn_manifest);
+        if errors.is_empty() {
+            Ok(engine)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Builds the rules engine from everything that could be loaded, along with the errors
+    /// found on the way.
+    pub fn build_with_errors(extn_manifest: &ExtnManifest) -> (Self, Vec<RuleLoadError>) {
         trace!("building rules engine {:?}", extn_manifest.rules_path);
         let mut engine = RuleEngine::default();
+        let mut errors = Vec::new();
         for path in extn_manifest.rules_path.iter() {
             let path_for_rule = Self::build_path(path, &extn_manifest.default_path);
             debug!("loading rules file {}", path_for_rule);
-            if let Some(p) = Path::new(&path_for_rule).to_str() {
-                if let Ok(contents) = fs::read_to_string(p) {
-                    info!("Rules content {}", contents);
-                    info!("loading rules from path {}", path);
-                    info!("loading rule {}", path_for_rule);
-                    if let Ok((_, rule_set)) = Self::load_from_content(contents) {
-                        engine.add_rules(rule_set, &extn_manifest.default_path);
-                    } else {
-                        warn!("invalid rule found in path {}", path)
+            match fs::read_to_string(&path_for_rule) {
+                Ok(contents) => {
+                    info!("loading rules from path {}", path_for_rule);
+                    match serde_json::from_str::<RuleSet>(&contents) {
+                        Ok(rule_set) => errors
+                            .extend(engine.append_rules(rule_set, &extn_manifest.default_path)),
+                        Err(e) => errors
+                            .push(RuleLoadError::InvalidRuleFile(path_for_rule, e.to_string())),
                     }
-                } else {
-                    warn!("path for the rule is invalid {}", path)
                 }
-            } else {
-                warn!("invalid rule path {}", path)
+                Err(e) => errors.push(RuleLoadError::UnreadablePath(path_for_rule, e.to_string())),
             }
         }
-        engine
+        // compile once all imports are known, later imports may override functions
+        errors.extend(
+            engine
+                .compile_rules()
+                .into_iter()
+                .map(RuleLoadError::CompileFailed),
+        );
+        (engine, errors)
     }
 
-    fn load_imports(&mut self, imports: &Vec<String>, default_path: &str) {
+    fn load_imports(&mut self, imports: &Vec<String>, default_path: &str) -> Vec<RuleLoadError> {
+        let mut errors = Vec::new();
         for import in imports {
             let path_to_import = Self::build_path(import, default_path);
             match fs::read_to_string(&path_to_import) {
@@ -361,7 +840,9 @@ impl RuleEngine {
                             error!(
                                 "load_imports: Invalid import: path_to_import={}, e={:?}",
                                 path_to_import, e
-                            )
+                            );
+                            errors
+                                .push(RuleLoadError::InvalidImport(path_to_import, e.to_string()));
                         }
                     }
                 }
@@ -370,9 +851,11 @@ impl RuleEngine {
                         "load_imports: Invalid path: path_to_import={}, e={:?}",
                         path_to_import, e
                     );
+                    errors.push(RuleLoadError::UnreadablePath(path_to_import, e.to_string()));
                 }
             }
         }
+        errors
     }
 
     pub fn load_from_content(contents: String) -> Result<(String, RuleSet), RippleError> {
@@ -384,43 +867,49 @@ impl RuleEngine {
             }
         }
     }
-    pub fn add_rules(&mut self, rules: RuleSet, default_path: &str) {
+    fn append_rules(&mut self, rules: RuleSet, default_path: &str) -> Vec<RuleLoadError> {
         self.rules.append(rules.clone());
-        self.load_imports(&rules.imports, default_path);
+        self.load_imports(&rules.imports, default_path)
     }
-    pub fn add_rule(&mut self, rule: Rule) {
-        self.rules.rules.insert(rule.alias.clone(), rule);
+    pub fn add_rules(&mut self, rules: RuleSet, default_path: &str) {
+        for e in self.append_rules(rules, default_path) {
+            warn!("{}", e);
+        }
+        self.compile_rules();
     }
-
-    pub fn has_rule(&self, request: &str) -> bool {
-        self.rules.rules.contains_key(&request.to_lowercase())
-    }
-    fn wildcard_match(rule_name: &str, method: &str) -> bool {
-        rule_name.ends_with(".*") && method.starts_with(&rule_name[..rule_name.len() - 1])
-    }
-    fn find_wildcard_rule(
-        rules: &HashMap<String, Rule>,
-        method: &str,
-    ) -> Result<RuleRetrieved, RuleRetrievalError> {
-        let filtered_rules: Vec<&Rule> = rules
-            .iter()
-            .filter(|(rule_name, _)| Self::wildcard_match(rule_name, method))
-            .map(|(_, rule)| rule)
-            .collect();
-
-        match filtered_rules.len() {
-            1 => Ok(RuleRetrieved::WildcardMatch(filtered_rules[0].clone())),
-            0 => Err(RuleRetrievalError::RuleNotFoundAsWildcard),
-            _ => Err(RuleRetrievalError::TooManyWildcardMatches),
+    pub fn add_rule(&mut self, mut rule: Rule) {
+        let name = rule.alias.clone();
+        let _ = rule.compile(&name, &self.functions);
+        self.rules.insert(name, rule);
+    }
+    /// Compiles the filters of every loaded rule, returning the names of the rules
+    /// which could not be compiled.
+    pub fn compile_rules(&mut self) -> Vec<String> {
+        let start = Utc::now().timestamp_millis();
+        let mut failed = Vec::new();
+        for (name, rule) in self.rules.iter_mut() {
+            if rule.compile(name, &self.functions).is_err() {
+                failed.push(name.clone());
+            }
         }
+        info!(
+            "compiled {} rules in {} ms, {} failed",
+            self.rules.len(),
+            Utc::now().timestamp_millis() - start,
+            failed.len()
+        );
+        failed
     }
 
-    fn apply_functions(&self, rule: &mut Rule) {
-        rule.transform.apply_functions(&self.functions);
+    pub fn has_rule(&self, request: &str) -> bool {
+        self.rules.contains_key(&request.to_lowercase())
     }
-
-    fn apply_variables(&self, rule: &mut Rule, rpc_request: &RpcRequest) {
-        rule.transform.apply_variables(rpc_request);
+    fn find_wildcard_rule(&self, method: &str) -> Result<RuleRetrieved, RuleRetrievalError> {
+        let name = self.rules.wildcard_index().find(method)?;
+        self.rules
+            .get(name)
+            .map(|rule| RuleRetrieved::WildcardMatch(rule.clone()))
+            .ok_or(RuleRetrievalError::RuleNotFoundAsWildcard)
     }
 
     pub fn get_rule(&self, rpc_request: &RpcRequest) -> Result<RuleRetrieved, RuleRetrievalError> {
@@ -430,20 +919,19 @@ impl RuleEngine {
         match directly from method name
          */
 
-        if let Some(mut rule) = self.rules.get(&method).cloned() {
-            self.apply_functions(&mut rule);
-            self.apply_variables(&mut rule, rpc_request);
-            Ok(RuleRetrieved::ExactMatch(rule.to_owned()))
+        if let Some(rule) = self.rules.get(&method) {
+            Ok(RuleRetrieved::ExactMatch(rule.clone()))
         } else {
             /*
-             * match, for example api.v1.* as rule name and api.v1.get as method name
+             * match, for example api.v1.* as rule name and api.v1.get as method name,
+             * the most specific rule wins when several wildcards match
              */
-            Self::find_wildcard_rule(&self.rules.rules, &method)
+            self.find_wildcard_rule(&method)
         }
     }
 
     pub fn get_rule_by_method(&self, method: &str) -> Option<Rule> {
-        self.rules.rules.get(&method.to_lowercase()).cloned()
+        self.rules.get(&method.to_lowercase()).cloned()
     }
 }
 #[derive(Debug)]
@@ -506,7 +994,7 @@ pub enum RuleRetrievalError {
 // Initializes the base ParseCtx with core and std filters, only once.
 fn get_parse_ctx() -> MutexGuard<'static, ParseCtx> {
     BASE_PARSE_CTX_INIT.call_once(|| {
-        let mut ctx = ParseCtx::new(Vec::new());
+        let mut ctx = ParseCtx::new(RULE_VARIABLES.iter().map(|v| v.to_string()).collect());
         ctx.insert_natives(jaq_core::core());
         ctx.insert_defs(jaq_std::std());
         unsafe {
@@ -523,46 +1011,7 @@ fn get_parse_ctx() -> MutexGuard<'static, ParseCtx> {
 }
 
 pub fn jq_compile(input: Value, filter: &str, reference: String) -> Result<Value, RippleError> {
-    info!(
-        "Jq rule {}  input {:?}, reference {}",
-        filter, input, reference
-    );
-    let start = Utc::now().timestamp_millis();
-    // start out only from core filters,
-    // which do not include filters in the standard library
-    // such as `map`, `select` etc.
-
-    // Parse the filter
-    let (f, errs) = jaq_parse::parse(filter, jaq_parse::main());
-    if !errs.is_empty() {
-        error!("Error in rule {:?}: {:?}", reference, errs);
-        return Err(RippleError::RuleError);
-    }
-    // Lock and use the shared ParseCtx
-    let mut defs = get_parse_ctx();
-    // compile the filter in the context of the given definitions
-    let f = defs.compile(f.unwrap());
-    if !defs.errs.is_empty() {
-        error!("Error in rule {}", reference);
-        for (err, _) in &defs.errs {
-            error!("reference={} {}", reference, err);
-        }
-        defs.errs.clear(); // Clear errors before returning
-        return Err(RippleError::RuleError);
-    }
-    let inputs = RcIter::new(core::iter::empty());
-    // iterator over the output values
-    let mut out = f.run((Ctx::new([], &inputs), Val::from(input)));
-    if let Some(Ok(v)) = out.next() {
-        info!(
-            "Ripple Gateway Rule Processing Time: {},{}",
-            reference,
-            Utc::now().timestamp_millis() - start
-        );
-        return Ok(Value::from(v));
-    }
-
-    Err(RippleError::ParseError)
+    CompiledFilter::compile(filter, &reference)?.run(input, &RuleVariables::default(), &reference)
 }
 
 pub fn compose_json_values(values: Vec<Value>) -> Value {
@@ -738,7 +1187,7 @@ mod tests {
             alias: "wildcard_rule".to_string(),
             ..Default::default()
         };
-        rule_set.rules.insert("api.v1.*".to_string(), rule.clone());
+        rule_set.insert("api.v1.*".to_string(), rule.clone());
 
         let rule_engine = RuleEngine {
             rules: rule_set,
@@ -764,6 +1213,88 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_get_rule_most_specific_wildcard() {
+        let mut rule_engine = RuleEngine::default();
+        for name in [
+            "device.*",
+            "device.info.*",
+            "*.onchanged",
+            "device.*.onchanged",
+            "a.*.c",
+            "a.b.*",
+            "x.*.z",
+            "x.y.*.z",
+            "m.*.n",
+            "m.*.*",
+        ] {
+            rule_engine.add_rule(Rule {
+                alias: name.to_string(),
+                ..Default::default()
+            });
+        }
+        let alias = |method: &str| {
+            rule_engine
+                .get_rule(&RpcRequest {
+                    method: method.to_string(),
+                    ..Default::default()
+                })
+                .map(|rule| Rule::from(rule).alias)
+        };
+
+        assert_eq!(alias("device.make").unwrap(), "device.*");
+        assert_eq!(alias("device.info.make").unwrap(), "device.info.*");
+        assert_eq!(alias("device.info.make.more").unwrap(), "device.info.*");
+        assert_eq!(alias("Device.Info.Make").unwrap(), "device.info.*");
+        assert_eq!(
+            alias("device.name.onChanged").unwrap(),
+            "device.*.onchanged"
+        );
+        assert_eq!(alias("device.onChanged").unwrap(), "device.*");
+        assert_eq!(alias("audio.onChanged").unwrap(), "*.onchanged");
+        assert_eq!(alias("a.b.c").unwrap(), "a.b.*");
+        assert_eq!(alias("m.b.n").unwrap(), "m.*.n");
+        assert!(matches!(
+            alias("audio.volume"),
+            Err(RuleRetrievalError::RuleNotFoundAsWildcard)
+        ));
+        assert!(matches!(
+            alias("device"),
+            Err(RuleRetrievalError::RuleNotFoundAsWildcard)
+        ));
+
+        // rules added later are picked up by the index
+        rule_engine.add_rule(Rule {
+            alias: "audio.*".to_string(),
+            ..Default::default()
+        });
+        let rule = rule_engine.get_rule(&RpcRequest {
+            method: "audio.volume".to_string(),
+            ..Default::default()
+        });
+        assert_eq!(Rule::from(rule.unwrap()).alias, "audio.*");
+    }
+
+    #[test]
+    fn test_get_rule_ambiguous_wildcard() {
+        let mut rule_set = RuleSet::default();
+        rule_set.insert("a.*.c.*".to_string(), Rule::default());
+        rule_set.insert("a.*.*.d.*".to_string(), Rule::default());
+        rule_set.insert("a.*.e.*".to_string(), Rule::default());
+        let rule_engine = RuleEngine {
+            rules: rule_set,
+            functions: HashMap::default(),
+        };
+        let rpc_request = RpcRequest {
+            method: "a.b.c.d.e".to_string(),
+            ..Default::default()
+        };
+        assert!(matches!(
+            rule_engine.get_rule(&rpc_request),
+            Err(RuleRetrievalError::TooManyWildcardMatches)
+        ));
+    }
+
     #[test]
     fn test_get_rule_no_match() {
         let rule_set = RuleSet::default();
@@ -788,4 +1319,153 @@ mod tests {
             Err(RuleRetrievalError::RuleNotFoundAsWildcard)
         ));
     }
+
+    #[test]
+    fn test_context_app_id_bound_as_variable() {
+        let filter =
+            CompiledFilter::compile("{ namespace: \"$context.appId\", scope: .scope }", "test")
+                .unwrap();
+        let resp = filter.run(
+            json!({"scope": "device"}),
+            &RuleVariables::new("some.app"),
+            "test",
+        );
+        assert_eq!(
+            resp.unwrap(),
+            json!({"namespace": "some.app", "scope": "device"})
+        );
+        // the same compiled filter serves every app
+        let resp = filter.run(
+            json!({"scope": "device"}),
+            &RuleVariables::new("other.app"),
+            "test",
+        );
+        assert_eq!(
+            resp.unwrap(),
+            json!({"namespace": "other.app", "scope": "device"})
+        );
+    }
+
+    #[test]
+    fn test_event_handler_response_bound_as_variable() {
+        let filter = CompiledFilter::compile("{ value: $event_handler_response }", "test").unwrap();
+        let variables = RuleVariables::default().with_event_handler_response(json!(true));
+        let resp = filter.run(Value::Null, &variables, "test");
+        assert_eq!(resp.unwrap(), json!({"value": true}));
+    }
+
+    #[test]
+    fn test_inline_event_filter() {
+        assert_eq!(
+            inline_event_filter("{ \"countryCode\": $event }", ".value"),
+            "{ \"countryCode\": .value }"
+        );
+        assert_eq!(
+            inline_event_filter("$event_handler_response", ".value"),
+            "$event_handler_response"
+        );
+    }
+
+    #[test]
+    fn test_compile_rules() {
+        let mut engine = RuleEngine::default();
+        engine.functions.insert(
+            "is_success".to_string(),
+            RulesFunction {
+                params: Some(vec!["value".to_string()]),
+                body: "if .success then $value else null end".to_string(),
+            },
+        );
+        let mut rule_set = RuleSet::default();
+        let mut rule = Rule::default();
+        rule.with_alias("org.rdk.System.getDeviceInfo".to_string())
+            .with_rule_tranformt(RuleTransform {
+                response: Some("$function.is_success(.result.make)".to_string()),
+                event: Some(".value".to_string()),
+                rpcv2_event: Some("{ \"make\": $event }".to_string()),
+                ..Default::default()
+            });
+        rule_set.insert("device.make".to_string(), rule);
+        let mut broken = Rule::default();
+        broken.with_filter("if .success".to_string());
+        rule_set.insert("device.broken".to_string(), broken);
+        engine.rules.append(rule_set);
+
+        let failed = engine.compile_rules();
+        assert_eq!(failed, vec!["device.broken".to_string()]);
+
+        let rule = engine.get_rule_by_method("device.make").unwrap();
+        let compiled = rule.compiled.clone().unwrap();
+        assert!(compiled.request.is_none());
+        let response = rule
+            .get_compiled_transform(RuleTransformType::Response)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            response
+                .run(
+                    json!({"success": true, "result": {"make": "arris"}}),
+                    &RuleVariables::default(),
+                    "test"
+                )
+                .unwrap(),
+            json!("arris")
+        );
+        let rpcv2_event = rule
+            .get_compiled_transform(RuleTransformType::Event(true))
+            .unwrap()
+            .unwrap();
+        assert_eq!(rpcv2_event.get_source(), "{ \"make\": .value }");
+
+        let broken = engine.get_rule_by_method("device.broken").unwrap();
+        // the failure is cached instead of being recompiled on every lookup
+        assert!(matches!(
+            broken.compiled.as_ref().unwrap().filter,
+            Some(Err(RippleError::RuleError))
+        ));
+        assert!(broken.get_compiled_filter().unwrap().is_err());
+    }
+
+    #[test]
+    fn test_try_build() {
+        let dir = std::env::temp_dir().join(format!("ripple_rules_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let valid = dir.join("valid.json");
+        let broken = dir.join("broken.json");
+        fs::write(
+            &valid,
+            r#"{"endpoints": {}, "rules": {"device.make": {"alias": "org.rdk.System.getDeviceInfo", "transform": {"response": ".result.make"}}}}"#,
+        )
+        .unwrap();
+        fs::write(
+            &broken,
+            r#"{"endpoints": {}, "rules": {"device.model": {"alias": "org.rdk.System.getDeviceInfo", "filter": "if .success"}}}"#,
+        )
+        .unwrap();
+
+        let mut manifest = ExtnManifest {
+            rules_path: vec![valid.to_str().unwrap().to_owned()],
+            ..Default::default()
+        };
+        let engine = RuleEngine::try_build(&manifest).unwrap();
+        assert!(engine.has_rule("device.make"));
+
+        manifest
+            .rules_path
+            .push(broken.to_str().unwrap().to_owned());
+        manifest
+            .rules_path
+            .push("/ripple/does/not/exist.json".to_owned());
+        let errors = RuleEngine::try_build(&manifest).unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(errors[0], RuleLoadError::UnreadablePath(_, _)));
+        assert_eq!(
+            errors[1],
+            RuleLoadError::CompileFailed("device.model".to_owned())
+        );
+
+        // lenient build keeps whatever could be loaded
+        assert!(RuleEngine::build(&manifest).has_rule("device.make"));
+        let _ = fs::remove_dir_all(&dir);
+    }
 }
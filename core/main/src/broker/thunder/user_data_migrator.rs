@@ -40,7 +40,7 @@ use serde_json::{json, Value};
 
 use crate::broker::{
     endpoint_broker::{self, BrokerCallback, BrokerOutput, BrokerRequest, EndpointBrokerState},
-    rules::rules_engine::{Rule, RuleTransformType},
+    rules::rules_engine::{Rule, RuleTransformType, RuleVariables},
     thunder_broker::ThunderBroker,
 };
 
@@ -468,14 +468,11 @@ impl UserDataMigrator {
             "value": params_json
         });
 
-        if let Some(filter) = rule
-            .transform
-            .get_transform_data(RuleTransformType::Request)
-        {
-            return crate::broker::rules::rules_engine::jq_compile(
+        if let Some(filter) = rule.get_compiled_transform(RuleTransformType::Request) {
+            return filter?.run(
                 data,
-                &filter,
-                format!("{}_request", method),
+                &RuleVariables::default(),
+                &format!("{}_request", method),
             );
         }
         serde_json::to_value(&data).map_err(|e| {
@@ -748,10 +745,14 @@ impl UserDataMigrator {
 
         if let Some(filter) = request
             .rule
-            .transform
-            .get_transform_data(RuleTransformType::Response)
+            .get_compiled_transform(RuleTransformType::Response)
         {
-            endpoint_broker::apply_response(filter, &request.rule.alias, &mut response);
+            endpoint_broker::apply_compiled_response(
+                filter,
+                &RuleVariables::from(&request.rpc),
+                &request.rule.alias,
+                &mut response,
+            );
         }
 
         if let Some(result) = response.result {
@@ -20,7 +20,7 @@ use super::{
         BrokerSender, BrokerSubMap, EndpointBroker, EndpointBrokerState,
         BROKER_CHANNEL_BUFFER_SIZE,
     },
-    thunder::thunder_plugins_status_mgr::StatusManager,
+    thunder::thunder_plugins_status_mgr::{new_status_manager, StatusManager},
     thunder::user_data_migrator::UserDataMigrator,
 };
 use crate::state::platform_state::PlatformState;
@@ -90,7 +90,7 @@ impl ThunderBroker {
             sender,
             subscription_map,
             cleaner,
-            status_manager: StatusManager::new(),
+            status_manager: new_status_manager(),
             default_callback,
             data_migrator: None,
             custom_callback_list: Arc::new(Mutex::new(HashMap::new())),
@@ -529,7 +529,7 @@ impl ThunderBroker {
             Some(v) => v.clone(),
             None => {
                 self.status_manager
-                    .add_broker_request_to_pending_list(callsign.clone(), rpc_request.clone());
+                    .add_request_to_pending_list(callsign.clone(), rpc_request.clone());
                 // PluginState is not available with StateManager,  create an internal thunder request to activate the plugin
                 let request = self
                     .status_manager
@@ -550,14 +550,14 @@ impl ThunderBroker {
                 callsign
             );
             self.status_manager
-                .add_broker_request_to_pending_list(callsign.clone(), rpc_request.clone());
+                .add_request_to_pending_list(callsign.clone(), rpc_request.clone());
             return Err(RippleError::ServiceNotReady);
         }
 
         if !status.state.is_activated() {
             // add the broker request to pending list
             self.status_manager
-                .add_broker_request_to_pending_list(callsign.clone(), rpc_request.clone());
+                .add_request_to_pending_list(callsign.clone(), rpc_request.clone());
             // create an internal thunder request to activate the plugin
             let request = self
                 .status_manager
@@ -724,6 +724,7 @@ mod tests {
                 protocol: RuleEndpointProtocol::Thunder,
                 url: $server_handle.get_address(),
                 jsonrpc: true,
+                ..Default::default()
             };
             let (reconnect_tx, _rec_rx) = mpsc::channel(2);
 
@@ -814,6 +815,7 @@ mod tests {
             url: format!("ws://127.0.0.1:{}", port),
             protocol: crate::broker::rules::rules_engine::RuleEndpointProtocol::Websocket,
             jsonrpc: false,
+            ..Default::default()
         };
         let (tx, _) = mpsc::channel(1);
         let request = BrokerConnectRequest::new("somekey".to_owned(), endpoint, tx);
@@ -840,6 +842,9 @@ mod tests {
                 filter: event_filter,
                 event_handler,
                 sources: None,
+                http: None,
+                cache: None,
+                compiled: None,
             },
             subscription_processed: None,
             workflow_callback: None,
@@ -1067,6 +1072,9 @@ mod tests {
                     filter: None,
                     event_handler: None,
                     sources: None,
+                    http: None,
+                    cache: None,
+                    compiled: None,
                 },
                 subscription_processed: Some(false),
                 workflow_callback: None,
@@ -1124,6 +1132,9 @@ mod tests {
                     filter: None,
                     event_handler: None,
                     sources: None,
+                    http: None,
+                    cache: None,
+                    compiled: None,
                 },
                 subscription_processed: Some(true),
                 workflow_callback: None,
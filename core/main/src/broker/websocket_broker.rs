@@ -278,6 +278,7 @@ mod tests {
             url: format!("ws://127.0.0.1:{}", port),
             protocol: crate::broker::rules::rules_engine::RuleEndpointProtocol::Websocket,
             jsonrpc: false,
+            ..Default::default()
         };
         let (tx, _) = mpsc::channel(1);
         let request = BrokerConnectRequest::new("somekey".to_owned(), endpoint, tx);
@@ -304,6 +305,9 @@ mod tests {
                 filter: None,
                 event_handler: None,
                 sources: None,
+                http: None,
+                cache: None,
+                compiled: None,
             },
             workflow_callback: None,
             subscription_processed: None,
@@ -351,6 +355,9 @@ mod tests {
                 filter: None,
                 event_handler: None,
                 sources: None,
+                http: None,
+                cache: None,
+                compiled: None,
             },
             workflow_callback: None,
             subscription_processed: None,
@@ -382,6 +389,9 @@ mod tests {
                 filter: None,
                 event_handler: None,
                 sources: None,
+                http: None,
+                cache: None,
+                compiled: None,
             },
             workflow_callback: None,
             subscription_processed: None,
@@ -415,6 +425,7 @@ mod tests {
             url: format!("ws://127.0.0.1:{}", port),
             protocol: crate::broker::rules::rules_engine::RuleEndpointProtocol::Websocket,
             jsonrpc: false,
+            ..Default::default()
         };
 
         let request = BrokerRequest {
@@ -426,6 +437,9 @@ mod tests {
                 filter: None,
                 event_handler: None,
                 sources: None,
+                http: None,
+                cache: None,
+                compiled: None,
             },
             workflow_callback: None,
             subscription_processed: None,
@@ -505,6 +519,9 @@ mod tests {
                 filter: None,
                 event_handler: None,
                 sources: None,
+                http: None,
+                cache: None,
+                compiled: None,
             },
             workflow_callback: None,
             subscription_processed: None,
@@ -515,6 +532,7 @@ mod tests {
             url: format!("ws://127.0.0.1:{}", port),
             protocol: crate::broker::rules::rules_engine::RuleEndpointProtocol::Websocket,
             jsonrpc: false,
+            ..Default::default()
         };
         let _ = WSNotificationBroker::start(request, callback, endpoint.get_url().clone());
         assert!(rec.recv().await.is_none());
@@ -175,3 +175,61 @@ impl FireboltGatekeeper {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ripple_sdk::{
+        api::firebolt::{
+            fb_capabilities::{CapabilityRole, FireboltCap},
+            fb_openrpc::{FireboltOpenRpc, OpenRPCParser},
+        },
+        serde_json, tokio, Mockable,
+    };
+    use ripple_tdk::utils::test_utils::Mockable as _;
+
+    fn request(method: &str) -> RpcRequest {
+        let mut request = RpcRequest::mock();
+        request.method = method.to_owned();
+        request
+    }
+
+    #[tokio::test]
+    async fn test_gate_ripple_open_rpc_methods() {
+        let state = PlatformState::mock();
+        let methods = [
+            (
+                "lifecyclemanagement.setProvider",
+                "xrn:firebolt:capability:lifecycle:state",
+            ),
+            (
+                "capabilities.providers",
+                "xrn:firebolt:capability:capabilities:info",
+            ),
+        ];
+        for (method, _) in methods {
+            let result = FireboltGatekeeper::gate(state.clone(), request(method)).await;
+            assert_eq!(result.unwrap_err().reason, DenyReason::NotFound);
+        }
+
+        let ripple_open_rpc: OpenRPCParser =
+            serde_json::from_str(include_str!("../state/ripple-rpc.json")).unwrap();
+        state
+            .open_rpc_state
+            .add_open_rpc(FireboltOpenRpc::from(ripple_open_rpc));
+        for (method, cap) in methods {
+            // only apps which are permitted to manage the capability can call the method
+            let perms = vec![FireboltPermission {
+                cap: FireboltCap::Full(cap.to_owned()),
+                role: CapabilityRole::Manage,
+            }];
+            assert_eq!(
+                FireboltGatekeeper::get_resolved_caps_for_method(&state, method, true),
+                Some(perms.clone())
+            );
+            // the mock app is permitted
+            let result = FireboltGatekeeper::gate(state.clone(), request(method)).await;
+            assert_eq!(result.unwrap(), perms);
+        }
+    }
+}
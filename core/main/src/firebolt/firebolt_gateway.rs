@@ -19,7 +19,10 @@ use jsonrpsee::{core::server::rpc_module::Methods, types::TwoPointZero};
 use ripple_sdk::{
     api::{
         firebolt::{
-            fb_capabilities::JSON_RPC_STANDARD_ERROR_INVALID_PARAMS,
+            fb_capabilities::{
+                JSON_RPC_ERROR_RATE_LIMITED, JSON_RPC_STANDARD_ERROR_INVALID_PARAMS,
+            },
+            fb_metrics::SystemErrorParams,
             fb_openrpc::FireboltOpenRpcMethod,
         },
         gateway::{
@@ -49,7 +52,7 @@ use crate::{
     },
     state::{
         bootstrap_state::BootstrapState, openrpc_state::OpenRpcState,
-        platform_state::PlatformState, session_state::Session,
+        platform_state::PlatformState, rate_limit_state::RateLimitCheck, session_state::Session,
     },
     utils::router_utils::{capture_stage, get_rpc_header_with_status},
 };
@@ -321,6 +324,40 @@ impl FireboltGateway {
 
         tokio::spawn(async move {
             capture_stage(&platform_state.metrics, &request_c, "context_ready");
+            // Internal requests are not limited, only app requests are throttled
+            if !extn_request && !service_request {
+                if let RateLimitCheck::Throttled { first } = platform_state
+                    .rate_limit_state
+                    .check(&request_c.ctx.app_id, &request_c.method)
+                {
+                    if first {
+                        TelemetryBuilder::send_system_error(
+                            &platform_state,
+                            SystemErrorParams {
+                                error_name: "app_rate_limited".into(),
+                                component: "firebolt_gateway".into(),
+                                context: Some(format!(
+                                    "{} {}",
+                                    request_c.ctx.app_id, request_c.method
+                                )),
+                            },
+                        );
+                    }
+                    LogSignal::new(
+                        "firebolt_gateway".into(),
+                        "rate_limited".into(),
+                        request.clone(),
+                    )
+                    .emit_debug();
+                    let json_rpc_error = JsonRpcError {
+                        code: JSON_RPC_ERROR_RATE_LIMITED,
+                        message: "Rate limit exceeded".to_owned(),
+                        data: None,
+                    };
+                    send_json_rpc_error(&mut platform_state, &request, json_rpc_error).await;
+                    return;
+                }
+            }
             // Validate incoming request parameters.
             if let Err(error_string) = validate_request(open_rpc_state, &request_c, fail_open) {
                 let json_rpc_error = JsonRpcError {
@@ -16,14 +16,21 @@
 //
 
 use std::{
+    collections::HashMap,
     net::SocketAddr,
-    sync::{Arc, RwLock},
+    sync::{Arc, Mutex, RwLock},
+    time::Duration,
 };
 
-use super::firebolt_gateway::FireboltGatewayCommand;
+use super::{
+    firebolt_gateway::FireboltGatewayCommand,
+    rpc_batch::{BatchSlot, BatchedResponse, RpcBatches, BATCH_TIMEOUT_MS, MAX_BATCH_SIZE},
+};
 use crate::{
-    service::apps::delegated_launcher_handler::{AppManagerState, AppManagerState2_0},
-    service::ripple_service::service_controller_state::ServiceControllerState,
+    service::apps::delegated_launcher_handler::AppManagerState,
+    service::apps::lifecycle2_manager::{is_lifecycle2_enabled, AppManagerState2_0},
+    service::extn::ripple_client::RippleClient,
+    service::ripple_service::service_controller_state::{ServiceAuthError, ServiceControllerState},
     state::{
         cap::permitted_state::PermissionHandler, platform_state::PlatformState,
         session_state::Session,
@@ -33,7 +40,8 @@ use futures::SinkExt;
 use futures::StreamExt;
 use jsonrpsee::types::{error::INVALID_REQUEST_CODE, ErrorObject, ErrorResponse, Id};
 use ripple_sdk::{
-    api::manifest::extn_manifest::ExtnSymbol,
+    api::manifest::{device_manifest::WsConfiguration, extn_manifest::ExtnSymbol},
+    service::service_client::SERVICE_CREDENTIAL_HEADER,
     tokio_tungstenite::{
         tungstenite::{self, Message},
         WebSocketStream,
@@ -49,7 +57,8 @@ use ripple_sdk::{
     log::{error, info, trace},
     tokio::{
         net::{TcpListener, TcpStream},
-        sync::{mpsc, oneshot},
+        sync::{mpsc, oneshot, Semaphore},
+        time::timeout,
     },
     utils::channel_utils::oneshot_send_and_log,
     uuid::Uuid,
@@ -67,6 +76,60 @@ pub struct ClientIdentity {
     pub service_info: Option<ExtnSymbol>,
 }
 
+/// Connection details every request of a batch is parsed with.
+struct BatchRequest {
+    app_id: String,
+    session_id: String,
+    connection_id: String,
+    gateway_secure: bool,
+    context: Vec<String>,
+}
+
+/// Open app connections per app id, counted from the handshake until the socket closes.
+#[derive(Debug, Clone, Default)]
+struct AppConnections {
+    max_per_app: Option<usize>,
+    open: Arc<Mutex<HashMap<String, usize>>>,
+}
+
+impl AppConnections {
+    fn new(max_per_app: Option<usize>) -> Self {
+        AppConnections {
+            max_per_app,
+            ..Default::default()
+        }
+    }
+
+    fn try_open(&self, app_id: &str) -> bool {
+        let mut open = self.open.lock().unwrap();
+        let count = open.entry(app_id.to_owned()).or_default();
+        if matches!(self.max_per_app, Some(max) if *count >= max) {
+            return false;
+        }
+        *count += 1;
+        true
+    }
+
+    fn close(&self, app_id: &str) {
+        let mut open = self.open.lock().unwrap();
+        if let Some(count) = open.get_mut(app_id) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                open.remove(app_id);
+            }
+        }
+    }
+
+    /// Releases the slot taken by a handshake which did not complete.
+    fn release_handshake(&self, mut connect_rx: oneshot::Receiver<ClientIdentity>) {
+        if let Ok(identity) = connect_rx.try_recv() {
+            if identity.service_info.is_none() {
+                self.close(&identity.app_id);
+            }
+        }
+    }
+}
+
 struct ConnectionCallbackConfig {
     pub next: oneshot::Sender<ClientIdentity>,
     pub app_state: AppManagerState,
@@ -74,19 +137,10 @@ struct ConnectionCallbackConfig {
     pub app_lifecycle_2_enabled: bool,
     pub secure: bool,
     pub internal_app_id: Option<String>,
-    extns: Vec<ExtnSymbol>,
+    service_controller_state: ServiceControllerState,
+    connections: AppConnections,
 }
 
-impl ConnectionCallbackConfig {
-    fn get_extn(&self, id: &str) -> Option<ExtnSymbol> {
-        for extn in &self.extns {
-            if extn.id.eq(id) {
-                return Some(extn.clone());
-            }
-        }
-        None
-    }
-}
 pub struct ConnectionCallback(ConnectionCallbackConfig);
 
 /**
@@ -141,29 +195,44 @@ impl tungstenite::handshake::server::Callback for ConnectionCallback {
         if !cfg.secure {
             if let Ok(Some(extn_id)) = get_query(request, "service_handshake", false) {
                 info!("Service handshake for extn_id={}", extn_id);
-                let cid = if let Some(c) = cfg.get_extn(&extn_id) {
-                    // valid extn_id
-                    ClientIdentity {
-                        session_id: Uuid::new_v4().to_string(),
-                        app_id: extn_id.clone(),
-                        rpc_v2: true,
-                        service_info: Some(c),
-                    }
-                } else {
-                    // extn_id without any symbol in the manifest
-                    info!("Extn not found for extn_id={} in {:?}", extn_id, cfg.extns);
-                    // Accept the connection, the service will be registered later.
-                    let extn_symbol = ExtnSymbol {
-                        id: extn_id.clone(),
-                        ..Default::default()
-                    };
-                    ClientIdentity {
-                        session_id: Uuid::new_v4().to_string(),
-                        app_id: extn_id.clone(),
-                        rpc_v2: true,
-                        service_info: Some(extn_symbol),
+                let credential = request
+                    .headers()
+                    .get(SERVICE_CREDENTIAL_HEADER)
+                    .and_then(|v| v.to_str().ok());
+                let symbol = match cfg
+                    .service_controller_state
+                    .authenticate(&extn_id, credential)
+                {
+                    Ok(symbol) => symbol,
+                    Err(e) => {
+                        LogSignal::new(
+                            "firebolt_ws_connection".to_string(),
+                            "service handshake rejected".to_string(),
+                            ClientContext {
+                                session_id: String::default(),
+                                app_id: extn_id.clone(),
+                                gateway_secure: cfg.secure,
+                            },
+                        )
+                        .with_diagnostic_context_item("reason", &e.to_string())
+                        .emit_error();
+                        let status = match e {
+                            ServiceAuthError::Unlisted => 403,
+                            _ => 401,
+                        };
+                        let err = tungstenite::http::response::Builder::new()
+                            .status(status)
+                            .body(Some(e.to_string()))
+                            .unwrap();
+                        return Err(err);
                     }
                 };
+                let cid = ClientIdentity {
+                    session_id: Uuid::new_v4().to_string(),
+                    app_id: extn_id.clone(),
+                    rpc_v2: true,
+                    service_info: Some(symbol),
+                };
                 info!("New Service connection {:?}", extn_id);
                 oneshot_send_and_log(cfg.next, cid, "ResolveClientIdentity");
                 return Ok(response);
@@ -241,6 +310,25 @@ impl tungstenite::handshake::server::Callback for ConnectionCallback {
 
         info!("{:?} {} is_rpc_v2={}", query, app_id, rpc_v2);
 
+        if !cfg.connections.try_open(&app_id) {
+            LogSignal::new(
+                "firebolt_ws_connection".to_string(),
+                "connection rejected".to_string(),
+                ClientContext {
+                    session_id: session_id.clone(),
+                    app_id: app_id.clone(),
+                    gateway_secure: cfg.secure,
+                },
+            )
+            .with_diagnostic_context_item("reason", "too many connections for app")
+            .emit_error();
+            let err = tungstenite::http::response::Builder::new()
+                .status(429)
+                .body(Some(format!("Too many connections for {}", app_id)))
+                .unwrap();
+            return Err(err);
+        }
+
         let cid = ClientIdentity {
             session_id: session_id.clone(),
             app_id,
@@ -255,25 +343,44 @@ impl tungstenite::handshake::server::Callback for ConnectionCallback {
 
 impl FireboltWs {
     pub async fn start(
-        server_addr: &str,
+        config: WsConfiguration,
         state: PlatformState,
         secure: bool,
         internal_app_id: Option<String>,
     ) {
+        let server_addr = config.gateway.as_str();
         // Create the event loop and TCP listener we'll accept connections on.
         let try_socket = TcpListener::bind(&server_addr).await; //create the server on the address
         let listener = try_socket.unwrap_or_else(|_| panic!("Failed to bind {:?}", server_addr));
         info!("Listening on: {} secure={}", server_addr, secure);
         let state_for_connection = state.clone();
-        let extns = state.extn_manifest.get_all_extns();
+        let service_controller_state = state.service_controller_state.clone();
         let app_state = state.app_manager_state.clone();
         let app_state2_0 = state.lifecycle2_app_state.clone();
-        let app_lifecycle_2_enabled = std::env::var("RIPPLE_LIFECYCLE_2_ENABLED")
-            .ok()
-            .and_then(|s| s.parse::<bool>().ok())
-            .unwrap_or(false);
-        // Let's spawn the handling of each connection in a separate task.
+        let app_lifecycle_2_enabled = is_lifecycle2_enabled();
+        let handshakes = Arc::new(Semaphore::new(config.max_pending_handshakes));
+        let handshake_timeout = Duration::from_millis(config.handshake_timeout_ms);
+        let connections = AppConnections::new(config.max_connections_per_app);
+        /*
+        The upgrade happens in the connection task so a client stalling its handshake only
+        holds one of the pending handshake permits and not the accept loop.
+        */
         while let Ok((stream, client_addr)) = listener.accept().await {
+            let Ok(permit) = handshakes.clone().try_acquire_owned() else {
+                LogSignal::new(
+                    "firebolt_ws_connection".to_string(),
+                    "connection rejected".to_string(),
+                    ClientContext {
+                        session_id: String::default(),
+                        app_id: String::default(),
+                        gateway_secure: secure,
+                    },
+                )
+                .with_diagnostic_context_item("reason", "too many pending handshakes")
+                .with_diagnostic_context_item("client_addr", &client_addr.to_string())
+                .emit_error();
+                continue;
+            };
             let (connect_tx, connect_rx) = oneshot::channel::<ClientIdentity>();
             let cfg = ConnectionCallbackConfig {
                 next: connect_tx,
@@ -282,29 +389,55 @@ impl FireboltWs {
                 app_lifecycle_2_enabled,
                 secure,
                 internal_app_id: internal_app_id.clone(),
-                extns: extns.clone(),
+                service_controller_state: service_controller_state.clone(),
+                connections: connections.clone(),
             };
-            match ripple_sdk::tokio_tungstenite::accept_hdr_async(stream, ConnectionCallback(cfg))
-                .await
-            {
-                Err(e) => {
-                    error!("websocket connection error {:?}", e);
-                }
-                Ok(ws_stream) => {
-                    trace!("websocket connection success");
-                    let state_for_connection_c = state_for_connection.clone();
-                    tokio::spawn(async move {
+            let state_for_connection_c = state_for_connection.clone();
+            let connections_c = connections.clone();
+            tokio::spawn(async move {
+                let handshake = timeout(
+                    handshake_timeout,
+                    ripple_sdk::tokio_tungstenite::accept_hdr_async(
+                        stream,
+                        ConnectionCallback(cfg),
+                    ),
+                )
+                .await;
+                drop(permit);
+                match handshake {
+                    Ok(Ok(ws_stream)) => {
+                        trace!("websocket connection success");
                         FireboltWs::handle_connection(
                             client_addr,
                             ws_stream,
                             connect_rx,
                             state_for_connection_c.clone(),
                             secure,
+                            connections_c,
                         )
                         .await;
-                    });
+                    }
+                    Ok(Err(e)) => {
+                        error!("websocket connection error {:?}", e);
+                        connections_c.release_handshake(connect_rx);
+                    }
+                    Err(_) => {
+                        LogSignal::new(
+                            "firebolt_ws_connection".to_string(),
+                            "connection rejected".to_string(),
+                            ClientContext {
+                                session_id: String::default(),
+                                app_id: String::default(),
+                                gateway_secure: secure,
+                            },
+                        )
+                        .with_diagnostic_context_item("reason", "handshake timed out")
+                        .with_diagnostic_context_item("client_addr", &client_addr.to_string())
+                        .emit_error();
+                        connections_c.release_handshake(connect_rx);
+                    }
                 }
-            }
+            });
         }
     }
 
@@ -364,12 +497,26 @@ impl FireboltWs {
         let (mut sender, mut receiver) = ws_stream.split();
         let mut platform_state = state.clone();
         let context_clone = ctx.clone();
+        let batches = RpcBatches::default();
+        let batches_c = batches.clone();
 
         tokio::spawn(async move {
             while let Some(api_message) = resp_rx.recv().await {
-                let send_result = sender
-                    .send(Message::Text(api_message.jsonrpc_msg.clone()))
-                    .await;
+                let frame =
+                    match batches_c.collect(&api_message.request_id, &api_message.jsonrpc_msg) {
+                        BatchedResponse::NotBatched => api_message.jsonrpc_msg.clone(),
+                        BatchedResponse::Pending | BatchedResponse::Expired => {
+                            platform_state
+                                .metrics
+                                .update_api_stage(&api_message.request_id, "batched");
+                            platform_state
+                                .metrics
+                                .remove_api_stats(&api_message.request_id);
+                            continue;
+                        }
+                        BatchedResponse::Complete(frame) => frame,
+                    };
+                let send_result = sender.send(Message::Text(frame.clone())).await;
                 match send_result {
                     Ok(_) => {
                         platform_state
@@ -406,7 +553,7 @@ impl FireboltWs {
                         info!(
                             "Sent Firebolt response cid={} msg={}",
                             connection_id_c.clone(),
-                            api_message.jsonrpc_msg
+                            frame
                         );
                     }
                     Err(err) => error!("{:?}", err),
@@ -429,7 +576,40 @@ impl FireboltWs {
                         let req_id = Uuid::new_v4().to_string();
                         let req_text = String::from(msg.to_text().unwrap());
                         let context = { rpc_context.read().unwrap().clone() };
-                        if let Ok(request) = RpcRequest::parse(
+                        if let Some(batch) = RpcBatches::parse(&req_text) {
+                            if batch.is_empty() {
+                                return_invalid_format_error_message(req_id, &state, &connection_id)
+                                    .await;
+                                continue;
+                            }
+                            if batch.len() > MAX_BATCH_SIZE {
+                                error!(
+                                    "batch of {} requests rejected cid={}",
+                                    batch.len(),
+                                    connection_id
+                                );
+                                let msg = format!(
+                                    "batch too large, at most {} requests are allowed",
+                                    MAX_BATCH_SIZE
+                                );
+                                let api_msg = ApiMessage::new(
+                                    ApiProtocol::JsonRpc,
+                                    invalid_request_error(&msg),
+                                    req_id,
+                                );
+                                let _ = session.send_json_rpc(api_msg).await;
+                                continue;
+                            }
+                            let batch_request = BatchRequest {
+                                app_id: app_id_c.clone(),
+                                session_id: session_id_c.clone(),
+                                connection_id: connection_id.clone(),
+                                gateway_secure,
+                                context,
+                            };
+                            Self::handle_batch(batch, batch_request, &client, &session, &batches)
+                                .await;
+                        } else if let Ok(request) = RpcRequest::parse(
                             req_text.clone(),
                             app_id_c.clone(),
                             session_id_c.clone(),
@@ -460,6 +640,7 @@ impl FireboltWs {
                 }
             }
         }
+        batches.clear();
         debug!("SESSION DEBUG Unregistering {}", connection_id);
         let msg = FireboltGatewayCommand::UnregisterSession {
             session_id: identity.session_id.clone(),
@@ -470,12 +651,91 @@ impl FireboltWs {
         }
     }
 
+    /*
+    Requests of a batch are dispatched one by one like single requests, elements which are not
+    requests are answered in place and responses to provider calls need no answer.
+    */
+    async fn handle_batch(
+        batch: Vec<serde_json::Value>,
+        batch_request: BatchRequest,
+        client: &RippleClient,
+        session: &Session,
+        batches: &RpcBatches,
+    ) {
+        let mut slots = Vec::new();
+        let mut requests = Vec::new();
+        for element in batch {
+            let req_id = Uuid::new_v4().to_string();
+            let element = element.to_string();
+            if let Ok(request) = RpcRequest::parse(
+                element.clone(),
+                batch_request.app_id.clone(),
+                batch_request.session_id.clone(),
+                req_id.clone(),
+                Some(batch_request.connection_id.clone()),
+                batch_request.gateway_secure,
+                batch_request.context.clone(),
+            ) {
+                slots.push(BatchSlot::Request {
+                    request_id: req_id,
+                    call_id: request.ctx.call_id,
+                });
+                requests.push(request);
+            } else if let Some(response) = JsonRpcApiResponse::get_response(&element) {
+                let msg = FireboltGatewayCommand::HandleResponse { response };
+                if let Err(e) = client.send_gateway_command(msg) {
+                    error!("failed to send request {:?}", e);
+                }
+            } else {
+                error!("invalid batch element {}", element);
+                slots.push(BatchSlot::Response(invalid_format_error()));
+            }
+        }
+        if slots.is_empty() {
+            return;
+        }
+        info!(
+            "Received Firebolt batch of {} requests cid={}",
+            slots.len(),
+            batch_request.connection_id
+        );
+        // register the whole batch before any response can come back
+        let (batch_id, frame) = batches.add(slots);
+        if let Some(frame) = frame {
+            let api_msg = ApiMessage::new(ApiProtocol::JsonRpc, frame, Uuid::new_v4().to_string());
+            let _ = session.send_json_rpc(api_msg).await;
+            return;
+        }
+        for request in requests {
+            let req_id = request.ctx.request_id.clone();
+            let msg = FireboltGatewayCommand::HandleRpc { request };
+            if let Err(e) = client.send_gateway_command(msg) {
+                error!("failed to send request {:?}", e);
+                let api_msg = ApiMessage::new(ApiProtocol::JsonRpc, invalid_format_error(), req_id);
+                let _ = session.send_json_rpc(api_msg).await;
+            }
+        }
+        let batches = batches.clone();
+        let session = session.clone();
+        let connection_id = batch_request.connection_id;
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(BATCH_TIMEOUT_MS)).await;
+            if let Some(frame) = batches.expire(batch_id) {
+                error!("batch timed out cid={}", connection_id);
+                let api_msg =
+                    ApiMessage::new(ApiProtocol::JsonRpc, frame, Uuid::new_v4().to_string());
+                let _ = session.send_json_rpc(api_msg).await;
+            }
+        });
+    }
+
     async fn handle_connection(
         _client_addr: SocketAddr,
         ws_stream: WebSocketStream<TcpStream>,
         connect_rx: oneshot::Receiver<ClientIdentity>,
         state: PlatformState,
         gateway_secure: bool,
+        connections: AppConnections,
     ) {
         let identity = connect_rx.await.unwrap();
 
@@ -495,6 +755,7 @@ impl FireboltWs {
             .await;
         } else {
             // Handle app connection
+            let app_id = identity.app_id.clone();
             Self::handle_app_connection(
                 _client_addr,
                 ws_stream,
@@ -504,6 +765,7 @@ impl FireboltWs {
                 gateway_secure,
             )
             .await;
+            connections.close(&app_id);
         }
     }
 }
@@ -544,13 +806,50 @@ async fn return_invalid_format_error_message(
         .session_state
         .get_session_for_connection_id(connection_id)
     {
-        let err = ErrorResponse::owned(
-            ErrorObject::owned::<()>(INVALID_REQUEST_CODE, "invalid request".to_owned(), None),
-            Id::Null,
-        );
-
-        let msg = serde_json::to_string(&err).unwrap();
-        let api_msg = ApiMessage::new(ApiProtocol::JsonRpc, msg, req_id);
+        let api_msg = ApiMessage::new(ApiProtocol::JsonRpc, invalid_format_error(), req_id);
         let _ = session.send_json_rpc(api_msg).await;
     }
 }
+
+fn invalid_format_error() -> String {
+    invalid_request_error("invalid request")
+}
+
+fn invalid_request_error(message: &str) -> String {
+    let err = ErrorResponse::owned(
+        ErrorObject::owned::<()>(INVALID_REQUEST_CODE, message.to_owned(), None),
+        Id::Null,
+    );
+    serde_json::to_string(&err).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_app_connections_limit() {
+        let connections = AppConnections::new(Some(2));
+        assert!(connections.try_open("app1"));
+        assert!(connections.try_open("app1"));
+        assert!(!connections.try_open("app1"));
+        assert!(connections.try_open("app2"));
+        connections.close("app1");
+        assert!(connections.try_open("app1"));
+
+        let (tx, rx) = oneshot::channel::<ClientIdentity>();
+        let _ = tx.send(ClientIdentity {
+            session_id: "session".to_owned(),
+            app_id: "app2".to_owned(),
+            rpc_v2: false,
+            service_info: None,
+        });
+        connections.release_handshake(rx);
+        assert!(!connections.open.lock().unwrap().contains_key("app2"));
+
+        let unlimited = AppConnections::new(None);
+        for _ in 0..10 {
+            assert!(unlimited.try_open("app1"));
+        }
+    }
+}
@@ -17,7 +17,7 @@
 
 use crate::{
     firebolt::rpc::RippleRPCProvider,
-    service::user_grants::GrantState,
+    service::{apps::provider_broker::ProviderBroker, user_grants::GrantState},
     state::{
         cap::{cap_state::CapState, permitted_state::PermissionHandler},
         platform_state::PlatformState,
@@ -92,6 +92,8 @@ pub trait Capability {
         ctx: CallContext,
         grants: CapRequestRpcRequest,
     ) -> RpcResult<Vec<CapabilityInfo>>;
+    #[method(name = "capabilities.providers")]
+    async fn providers(&self, ctx: CallContext, cap: CapRPCRequest) -> RpcResult<Vec<String>>;
 }
 
 pub struct CapabilityImpl {
@@ -291,6 +293,13 @@ impl CapabilityServer for CapabilityImpl {
             )))
         }
     }
+
+    async fn providers(&self, _ctx: CallContext, cap: CapRPCRequest) -> RpcResult<Vec<String>> {
+        Ok(ProviderBroker::get_providers(
+            &self.state,
+            &cap.capability.as_str(),
+        ))
+    }
 }
 
 pub struct CapRPCProvider;
@@ -139,6 +139,9 @@ pub trait Internal {
         ctx: CallContext,
         request: SettingsRequestParam,
     ) -> RpcResult<()>;
+
+    #[method(name = "ripple.reloadRules")]
+    fn reload_rules(&self, ctx: CallContext) -> RpcResult<usize>;
 }
 
 #[derive(Debug, Clone, Default)]
@@ -377,6 +380,16 @@ impl InternalServer for InternalImpl {
         subscribe_to_settings(&self.state, request).await;
         Ok(())
     }
+
+    fn reload_rules(&self, _ctx: CallContext) -> RpcResult<usize> {
+        self.state
+            .endpoint_state
+            .reload_rules(&self.state.extn_manifest)
+            .map_err(|errors| {
+                let errors: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+                rpc_err(format!("Rules not reloaded: {}", errors.join(", ")))
+            })
+    }
 }
 
 pub struct InternalProvider;
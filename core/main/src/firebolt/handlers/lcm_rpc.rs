@@ -23,9 +23,10 @@ use ripple_sdk::{
             fb_capabilities::FireboltCap,
             fb_general::{ListenRequest, ListenerResponse},
             fb_lifecycle_management::{
-                AppSessionRequest, SessionResponse, SetStateRequest, LCM_EVENT_ON_REQUEST_CLOSE,
-                LCM_EVENT_ON_REQUEST_FINISHED, LCM_EVENT_ON_REQUEST_LAUNCH,
-                LCM_EVENT_ON_REQUEST_READY, LCM_EVENT_ON_SESSION_TRANSITION_CANCELED,
+                AppSessionRequest, SessionResponse, SetProviderRequest, SetStateRequest,
+                LCM_EVENT_ON_REQUEST_CLOSE, LCM_EVENT_ON_REQUEST_FINISHED,
+                LCM_EVENT_ON_REQUEST_LAUNCH, LCM_EVENT_ON_REQUEST_READY,
+                LCM_EVENT_ON_SESSION_TRANSITION_CANCELED,
                 LCM_EVENT_ON_SESSION_TRANSITION_COMPLETED,
             },
         },
@@ -93,6 +94,9 @@ pub trait LifecycleManagement {
         ctx: CallContext,
         request: ListenRequest,
     ) -> RpcResult<ListenerResponse>;
+
+    #[method(name = "lifecyclemanagement.setProvider")]
+    async fn set_provider(&self, ctx: CallContext, request: SetProviderRequest) -> RpcResult<()>;
 }
 
 #[derive(Debug)]
@@ -261,6 +265,11 @@ impl LifecycleManagementServer for LifecycleManagementImpl {
             event: LCM_EVENT_ON_SESSION_TRANSITION_CANCELED.to_string(),
         })
     }
+
+    async fn set_provider(&self, _ctx: CallContext, request: SetProviderRequest) -> RpcResult<()> {
+        ProviderBroker::set_chosen_provider(&self.state, request.capability, request.app_id);
+        Ok(())
+    }
 }
 
 pub struct LifecycleManagementProvider;
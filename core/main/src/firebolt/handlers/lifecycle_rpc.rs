@@ -41,7 +41,7 @@ use ripple_sdk::{
 use crate::broker::broker_utils::BrokerUtils;
 use crate::{
     firebolt::rpc::RippleRPCProvider,
-    service::apps::app_events::AppEvents,
+    service::apps::{app_events::AppEvents, lifecycle2_manager::Lifecycle2Manager},
     state::platform_state::PlatformState,
     utils::rpc_utils::{rpc_await_oneshot, rpc_err},
 };
@@ -207,6 +207,11 @@ impl LifecycleServer for LifecycleImpl {
     }
 
     async fn state(&self, ctx: CallContext) -> RpcResult<String> {
+        if ctx.is_rpc_v2() {
+            return Lifecycle2Manager::get_app_state(&self.platform_state, &ctx.app_id)
+                .map(|state| state.as_string().to_owned())
+                .ok_or_else(|| rpc_err("App is not loaded"));
+        }
         let (app_resp_tx, app_resp_rx) = oneshot::channel::<AppResponse>();
 
         let app_request = AppRequest::new(AppMethod::State(ctx.app_id), app_resp_tx);
@@ -42,4 +42,5 @@ pub mod firebolt_gatekeeper;
 pub mod firebolt_gateway;
 pub mod firebolt_ws;
 pub mod rpc;
+pub mod rpc_batch;
 pub mod rpc_router;
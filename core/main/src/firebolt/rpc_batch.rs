@@ -0,0 +1,299 @@
+// Copyright 2025 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
+
+use jsonrpsee::types::{ErrorObject, ErrorResponse, Id};
+use ripple_sdk::serde_json::{self, Value};
+
+/*
+JSON-RPC 2.0 batches on an app connection. Every request of a batch is handled on its own by the
+gateway, the responses are held back here and sent as a single array frame in request order once
+the last one is in. Requests still unanswered after BATCH_TIMEOUT_MS are answered with an error so
+a single slow request cannot hold back the whole batch forever.
+*/
+
+/// Most elements accepted in a single batch frame
+pub const MAX_BATCH_SIZE: usize = 100;
+/// Time a batch waits for its responses before the unanswered requests get an error
+pub const BATCH_TIMEOUT_MS: u64 = 30000;
+const BATCH_TIMEOUT_ERROR_CODE: i32 = -32000;
+
+/// Element of a batch in request order.
+#[derive(Debug)]
+pub enum BatchSlot {
+    /// Request dispatched to the gateway, waiting for its response
+    Request { request_id: String, call_id: u64 },
+    /// Response known right away, like the error of an invalid element
+    Response(String),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum BatchedResponse {
+    /// Not part of a batch, send as is
+    NotBatched,
+    /// Held until the rest of the batch is answered
+    Pending,
+    /// Last response of a batch, send the array frame
+    Complete(String),
+    /// Late response of a batch which was already answered on timeout, drop it
+    Expired,
+}
+
+#[derive(Debug, Default)]
+struct PendingBatch {
+    responses: Vec<Option<String>>,
+    remaining: usize,
+}
+
+impl PendingBatch {
+    fn frame(&mut self) -> String {
+        let responses: Vec<String> = self.responses.iter_mut().filter_map(|r| r.take()).collect();
+        format!("[{}]", responses.join(","))
+    }
+}
+
+#[derive(Debug, Default)]
+struct BatchState {
+    next_id: u64,
+    // request id to batch id, position in the batch and JSON-RPC id
+    requests: HashMap<String, (u64, usize, u64)>,
+    batches: HashMap<u64, PendingBatch>,
+    // requests answered on timeout whose response has not come back yet
+    expired: HashSet<String>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct RpcBatches {
+    state: Arc<Mutex<BatchState>>,
+}
+
+impl RpcBatches {
+    /// Splits a batch frame into its elements, None if the frame is not an array.
+    pub fn parse(text: &str) -> Option<Vec<Value>> {
+        if !text.trim_start().starts_with('[') {
+            return None;
+        }
+        serde_json::from_str::<Vec<Value>>(text).ok()
+    }
+
+    /// Registers the slots of a batch in request order and returns the id of the batch. The
+    /// frame is returned right away if no request is left to wait for.
+    pub fn add(&self, slots: Vec<BatchSlot>) -> (u64, Option<String>) {
+        let mut batch = PendingBatch::default();
+        let mut state = self.state.lock().unwrap();
+        let batch_id = state.next_id;
+        state.next_id += 1;
+        for (index, slot) in slots.into_iter().enumerate() {
+            match slot {
+                BatchSlot::Request {
+                    request_id,
+                    call_id,
+                } => {
+                    state
+                        .requests
+                        .insert(request_id, (batch_id, index, call_id));
+                    batch.remaining += 1;
+                    batch.responses.push(None);
+                }
+                BatchSlot::Response(response) => batch.responses.push(Some(response)),
+            }
+        }
+        if batch.remaining == 0 {
+            return (batch_id, Some(batch.frame()));
+        }
+        state.batches.insert(batch_id, batch);
+        (batch_id, None)
+    }
+
+    pub fn collect(&self, request_id: &str, message: &str) -> BatchedResponse {
+        let mut state = self.state.lock().unwrap();
+        let batched = state.requests.get(request_id).cloned();
+        if batched.is_none() && !state.expired.contains(request_id) {
+            return BatchedResponse::NotBatched;
+        }
+        // events of a subscription made in the batch share its request id
+        if serde_json::from_str::<Value>(message)
+            .map(|v| v.get("method").is_some())
+            .unwrap_or(false)
+        {
+            return BatchedResponse::NotBatched;
+        }
+        let Some((batch_id, index, _)) = batched else {
+            state.expired.remove(request_id);
+            return BatchedResponse::Expired;
+        };
+        state.requests.remove(request_id);
+        let Some(batch) = state.batches.get_mut(&batch_id) else {
+            return BatchedResponse::NotBatched;
+        };
+        batch.responses[index] = Some(message.to_owned());
+        batch.remaining -= 1;
+        if batch.remaining > 0 {
+            return BatchedResponse::Pending;
+        }
+        let frame = batch.frame();
+        state.batches.remove(&batch_id);
+        BatchedResponse::Complete(frame)
+    }
+
+    /// Answers the requests of the batch which are still pending with a timeout error and
+    /// returns the frame, None if the batch is already complete.
+    pub fn expire(&self, batch_id: u64) -> Option<String> {
+        let mut state = self.state.lock().unwrap();
+        let mut batch = state.batches.remove(&batch_id)?;
+        let pending: Vec<String> = state
+            .requests
+            .iter()
+            .filter(|(_, (id, _, _))| *id == batch_id)
+            .map(|(request_id, _)| request_id.clone())
+            .collect();
+        for request_id in pending {
+            if let Some((_, index, call_id)) = state.requests.remove(&request_id) {
+                batch.responses[index] = Some(timeout_error(call_id));
+                state.expired.insert(request_id);
+            }
+        }
+        Some(batch.frame())
+    }
+
+    /// Drops every pending batch, called when the connection closes.
+    pub fn clear(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.requests.clear();
+        state.batches.clear();
+        state.expired.clear();
+    }
+}
+
+fn timeout_error(call_id: u64) -> String {
+    let err = ErrorResponse::owned(
+        ErrorObject::owned::<()>(
+            BATCH_TIMEOUT_ERROR_CODE,
+            "request timed out".to_owned(),
+            None,
+        ),
+        Id::Number(call_id),
+    );
+    serde_json::to_string(&err).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ripple_sdk::serde_json::json;
+
+    #[test]
+    fn test_parse() {
+        let batch = RpcBatches::parse(
+            r#" [{"jsonrpc": "2.0", "id": 1, "method": "device.make"}, {"id": 2}]"#,
+        )
+        .unwrap();
+        assert_eq!(batch.len(), 2);
+        assert_eq!(batch[1], json!({"id": 2}));
+        assert!(RpcBatches::parse(r#"{"jsonrpc": "2.0", "id": 1}"#).is_none());
+        assert!(RpcBatches::parse("[{").is_none());
+    }
+
+    fn request(request_id: &str, call_id: u64) -> BatchSlot {
+        BatchSlot::Request {
+            request_id: request_id.to_owned(),
+            call_id,
+        }
+    }
+
+    #[test]
+    fn test_collect_in_request_order() {
+        let batches = RpcBatches::default();
+        let invalid = r#"{"jsonrpc":"2.0","id":null,"error":{"code":-32600}}"#.to_owned();
+        assert!(batches
+            .add(vec![
+                request("a", 1),
+                BatchSlot::Response(invalid.clone()),
+                request("c", 3),
+            ])
+            .1
+            .is_none());
+
+        assert_eq!(batches.collect("x", "{}"), BatchedResponse::NotBatched);
+        assert_eq!(
+            batches.collect("c", r#"{"jsonrpc":"2.0","id":3,"result":3}"#),
+            BatchedResponse::Pending
+        );
+        assert_eq!(
+            batches.collect("a", r#"{"jsonrpc":"2.0","method":"device.onNameChanged"}"#),
+            BatchedResponse::NotBatched
+        );
+        assert_eq!(
+            batches.collect("a", r#"{"jsonrpc":"2.0","id":1,"result":1}"#),
+            BatchedResponse::Complete(format!(
+                r#"[{{"jsonrpc":"2.0","id":1,"result":1}},{},{{"jsonrpc":"2.0","id":3,"result":3}}]"#,
+                invalid
+            ))
+        );
+        assert_eq!(batches.collect("a", "{}"), BatchedResponse::NotBatched);
+
+        assert_eq!(
+            batches.add(vec![BatchSlot::Response(invalid.clone())]).1,
+            Some(format!("[{}]", invalid))
+        );
+    }
+
+    #[test]
+    fn test_expire() {
+        let batches = RpcBatches::default();
+        let (batch_id, frame) = batches.add(vec![request("a", 1), request("b", 2)]);
+        assert!(frame.is_none());
+        assert_eq!(
+            batches.collect("a", r#"{"jsonrpc":"2.0","id":1,"result":1}"#),
+            BatchedResponse::Pending
+        );
+
+        let frame = batches.expire(batch_id).unwrap();
+        let frame: Vec<Value> = serde_json::from_str(&frame).unwrap();
+        assert_eq!(frame[0], json!({"jsonrpc": "2.0", "id": 1, "result": 1}));
+        assert_eq!(frame[1]["id"], json!(2));
+        assert_eq!(frame[1]["error"]["code"], json!(BATCH_TIMEOUT_ERROR_CODE));
+        assert!(batches.expire(batch_id).is_none());
+
+        // the late response is dropped once, events of the request still go through
+        assert_eq!(
+            batches.collect("b", r#"{"jsonrpc":"2.0","method":"device.onNameChanged"}"#),
+            BatchedResponse::NotBatched
+        );
+        assert_eq!(
+            batches.collect("b", r#"{"jsonrpc":"2.0","id":2,"result":2}"#),
+            BatchedResponse::Expired
+        );
+        assert_eq!(batches.collect("b", "{}"), BatchedResponse::NotBatched);
+    }
+
+    #[test]
+    fn test_clear() {
+        let batches = RpcBatches::default();
+        let (batch_id, _) = batches.add(vec![request("a", 1)]);
+        batches.clear();
+        assert!(batches.expire(batch_id).is_none());
+        assert_eq!(
+            batches.collect("a", r#"{"jsonrpc":"2.0","id":1,"result":1}"#),
+            BatchedResponse::NotBatched
+        );
+    }
+}
@@ -15,7 +15,6 @@
 // SPDX-License-Identifier: Apache-2.0
 //
 
-use crate::tokio::sync::mpsc::Sender;
 use futures::StreamExt;
 use jsonrpsee::{
     core::server::{
@@ -25,6 +24,7 @@ use jsonrpsee::{
     },
     types::{error::ErrorCode, Id, Params},
 };
+use ripple_sdk::tokio::sync::mpsc::Sender;
 use ripple_sdk::{
     api::{
         gateway::rpc_gateway_api::{ApiMessage, RpcRequest},
@@ -0,0 +1,29 @@
+// Copyright 2023 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+/*
+The gateway is built as a library so it can be booted in process, e.g. by the TDK test harness.
+The ripple binary only sets up logging and runs the boot sequence.
+*/
+pub mod bootstrap;
+pub mod broker;
+pub mod firebolt;
+pub mod processor;
+pub mod service;
+pub mod state;
+pub mod utils;
+include!(concat!(env!("OUT_DIR"), "/version.rs"));
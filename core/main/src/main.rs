@@ -15,21 +15,14 @@
 // SPDX-License-Identifier: Apache-2.0
 //
 
-use crate::bootstrap::boot::boot;
+use ripple_main::{
+    bootstrap::boot::boot, state::bootstrap_state::BootstrapState, SEMVER_LIGHTWEIGHT,
+};
 use ripple_sdk::{
     log::{error, info},
     tokio,
     utils::logger::init_and_configure_logger,
 };
-use state::bootstrap_state::BootstrapState;
-pub mod bootstrap;
-pub mod broker;
-pub mod firebolt;
-pub mod processor;
-pub mod service;
-pub mod state;
-pub mod utils;
-include!(concat!(env!("OUT_DIR"), "/version.rs"));
 
 #[tokio::main(worker_threads = 2)]
 async fn main() {
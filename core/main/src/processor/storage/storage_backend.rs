@@ -0,0 +1,385 @@
+// Copyright 2025 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+use std::{
+    collections::HashMap,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use ripple_sdk::{
+    api::{
+        device::device_peristence::{
+            DeleteStorageProperty, DevicePersistenceRequest, GetStorageProperty,
+            SetStorageProperty, StorageData,
+        },
+        manifest::device_manifest::StorageBackendType,
+    },
+    async_trait::async_trait,
+    extn::extn_client_message::ExtnResponse,
+    log::{error, info},
+    serde_json,
+    tokio::{self, sync::Mutex},
+    utils::error::RippleError,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::service::extn::ripple_client::RippleClient;
+
+const STORAGE_DIR_NAME: &str = "storage";
+
+/// Keeps the values read and written by the StorageManager.
+#[async_trait]
+pub trait StorageBackend: std::fmt::Debug + Send + Sync {
+    /// `ExtnResponse::StorageData` for a stored value, `ExtnResponse::None` when there is none
+    async fn get(&self, data: GetStorageProperty) -> Result<ExtnResponse, RippleError>;
+    async fn set(&self, data: SetStorageProperty) -> Result<ExtnResponse, RippleError>;
+    async fn delete(&self, data: DeleteStorageProperty) -> Result<ExtnResponse, RippleError>;
+}
+
+pub fn get_storage_backend(
+    backend_type: StorageBackendType,
+    client: RippleClient,
+    saved_dir: &str,
+) -> Arc<dyn StorageBackend> {
+    match backend_type {
+        StorageBackendType::Thunder => Arc::new(ThunderStorageBackend { client }),
+        StorageBackendType::File => {
+            let dir = Path::new(saved_dir).join(STORAGE_DIR_NAME);
+            info!("Storing values under {:?}", dir);
+            Arc::new(FileStorageBackend::new(dir))
+        }
+    }
+}
+
+/// Sends the requests to the device extension which keeps them in Thunder PersistentStore.
+#[derive(Debug)]
+pub struct ThunderStorageBackend {
+    client: RippleClient,
+}
+
+impl ThunderStorageBackend {
+    async fn request(
+        &self,
+        request: DevicePersistenceRequest,
+    ) -> Result<ExtnResponse, RippleError> {
+        let msg = self.client.send_extn_request(request).await?;
+        msg.payload.extract().ok_or(RippleError::ParseError)
+    }
+}
+
+#[async_trait]
+impl StorageBackend for ThunderStorageBackend {
+    async fn get(&self, data: GetStorageProperty) -> Result<ExtnResponse, RippleError> {
+        self.request(DevicePersistenceRequest::Get(data)).await
+    }
+
+    async fn set(&self, data: SetStorageProperty) -> Result<ExtnResponse, RippleError> {
+        // the response of a set was never looked at, only that the store answered
+        self.client
+            .send_extn_request(DevicePersistenceRequest::Set(data))
+            .await
+            .map(|msg| msg.payload.extract().unwrap_or(ExtnResponse::None(())))
+    }
+
+    async fn delete(&self, data: DeleteStorageProperty) -> Result<ExtnResponse, RippleError> {
+        self.request(DevicePersistenceRequest::Delete(data)).await
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct NamespaceFile {
+    #[serde(default)]
+    values: HashMap<String, StorageData>,
+    /// Values of a scope, like an app, keyed by the scope
+    #[serde(default)]
+    scopes: HashMap<String, HashMap<String, StorageData>>,
+}
+
+impl NamespaceFile {
+    fn values(&mut self, scope: Option<String>) -> &mut HashMap<String, StorageData> {
+        match scope {
+            Some(scope) => self.scopes.entry(scope).or_default(),
+            None => &mut self.values,
+        }
+    }
+}
+
+/*
+Keeps each namespace in its own JSON file, named after the percent-encoded namespace so distinct
+namespaces never share a file. A write goes to a temporary file which is then renamed over the
+namespace file, so a crash leaves either the old or the new values and never a partial file. The
+file access runs on the blocking pool and the lock serializes the read, modify and write of the
+files.
+*/
+#[derive(Debug)]
+pub struct FileStorageBackend {
+    dir: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl FileStorageBackend {
+    pub fn new(dir: PathBuf) -> Self {
+        FileStorageBackend {
+            dir,
+            lock: Mutex::new(()),
+        }
+    }
+
+    fn get_path(&self, namespace: &str) -> PathBuf {
+        let mut file_name = String::with_capacity(namespace.len());
+        for b in namespace.bytes() {
+            if b.is_ascii_alphanumeric() || b == b'-' || b == b'_' {
+                file_name.push(b as char);
+            } else {
+                file_name.push_str(&format!("%{:02X}", b));
+            }
+        }
+        self.dir.join(format!("{}.json", file_name))
+    }
+
+    fn read(path: &Path) -> Result<NamespaceFile, RippleError> {
+        match fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).map_err(|e| {
+                error!("Unable to parse {:?}: {:?}", path, e);
+                RippleError::ParseError
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(NamespaceFile::default()),
+            Err(e) => {
+                error!("Unable to read {:?}: {:?}", path, e);
+                Err(RippleError::ProcessorError)
+            }
+        }
+    }
+
+    fn write(dir: &Path, path: &Path, file: &NamespaceFile) -> Result<(), RippleError> {
+        let tmp_path = path.with_extension("json.tmp");
+        let contents = serde_json::to_vec(file).map_err(|_| RippleError::ParseError)?;
+        let result = fs::create_dir_all(dir)
+            .and_then(|_| fs::File::create(&tmp_path))
+            .and_then(|mut tmp| {
+                tmp.write_all(&contents)?;
+                tmp.sync_all()
+            })
+            .and_then(|_| fs::rename(&tmp_path, path));
+        result.map_err(|e| {
+            error!("Unable to write {:?}: {:?}", path, e);
+            RippleError::ProcessorError
+        })
+    }
+
+    async fn run_blocking<T, F>(f: F) -> Result<T, RippleError>
+    where
+        F: FnOnce() -> Result<T, RippleError> + Send + 'static,
+        T: Send + 'static,
+    {
+        tokio::task::spawn_blocking(f).await.map_err(|e| {
+            error!("File storage task failed: {:?}", e);
+            RippleError::ProcessorError
+        })?
+    }
+
+    async fn update<F>(&self, namespace: &str, f: F) -> Result<ExtnResponse, RippleError>
+    where
+        F: FnOnce(&mut NamespaceFile) + Send + 'static,
+    {
+        let dir = self.dir.clone();
+        let path = self.get_path(namespace);
+        let _guard = self.lock.lock().await;
+        Self::run_blocking(move || {
+            let mut file = Self::read(&path)?;
+            f(&mut file);
+            Self::write(&dir, &path, &file)
+        })
+        .await?;
+        Ok(ExtnResponse::Boolean(true))
+    }
+}
+
+#[async_trait]
+impl StorageBackend for FileStorageBackend {
+    async fn get(&self, data: GetStorageProperty) -> Result<ExtnResponse, RippleError> {
+        let path = self.get_path(&data.namespace);
+        let mut file = {
+            let _guard = self.lock.lock().await;
+            Self::run_blocking(move || Self::read(&path)).await?
+        };
+        Ok(match file.values(data.scope).remove(&data.key) {
+            Some(value) => ExtnResponse::StorageData(value),
+            None => ExtnResponse::None(()),
+        })
+    }
+
+    async fn set(&self, data: SetStorageProperty) -> Result<ExtnResponse, RippleError> {
+        let SetStorageProperty {
+            namespace,
+            key,
+            data,
+            scope,
+        } = data;
+        self.update(&namespace, |file| {
+            file.values(scope).insert(key, data);
+        })
+        .await
+    }
+
+    async fn delete(&self, data: DeleteStorageProperty) -> Result<ExtnResponse, RippleError> {
+        let namespace = data.namespace.clone();
+        self.update(&namespace, move |file| {
+            file.values(data.scope.clone()).remove(&data.key);
+            if let Some(scope) = &data.scope {
+                if file
+                    .scopes
+                    .get(scope)
+                    .map_or(false, |values| values.is_empty())
+                {
+                    file.scopes.remove(scope);
+                }
+            }
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ripple_sdk::{
+        serde_json::{json, Value},
+        tokio,
+    };
+
+    fn get_backend(name: &str) -> FileStorageBackend {
+        let dir =
+            std::env::temp_dir().join(format!("ripple-storage-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        FileStorageBackend::new(dir)
+    }
+
+    fn property(key: &str, scope: Option<&str>) -> GetStorageProperty {
+        GetStorageProperty {
+            namespace: "Privacy".to_owned(),
+            key: key.to_owned(),
+            scope: scope.map(str::to_owned),
+        }
+    }
+
+    async fn set(backend: &FileStorageBackend, key: &str, scope: Option<&str>, value: bool) {
+        backend
+            .set(SetStorageProperty {
+                namespace: "Privacy".to_owned(),
+                key: key.to_owned(),
+                data: StorageData::new(json!(value)),
+                scope: scope.map(str::to_owned),
+            })
+            .await
+            .unwrap();
+    }
+
+    async fn get(backend: &FileStorageBackend, key: &str, scope: Option<&str>) -> Option<Value> {
+        match backend.get(property(key, scope)).await.unwrap() {
+            ExtnResponse::StorageData(data) => Some(data.value),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_file_backend_values_persist() {
+        let backend = get_backend("persist");
+        assert_eq!(get(&backend, "allowWatchHistory", None).await, None);
+
+        set(&backend, "allowWatchHistory", None, true).await;
+        set(&backend, "allowWatchHistory", Some("app1"), false).await;
+
+        // a new backend on the same directory reads what the first one wrote
+        let reopened = FileStorageBackend::new(backend.dir.clone());
+        assert_eq!(
+            get(&reopened, "allowWatchHistory", None).await,
+            Some(json!(true))
+        );
+        assert_eq!(
+            get(&reopened, "allowWatchHistory", Some("app1")).await,
+            Some(json!(false))
+        );
+        assert!(!backend
+            .get_path("Privacy")
+            .with_extension("json.tmp")
+            .exists());
+
+        reopened
+            .delete(property("allowWatchHistory", Some("app1")))
+            .await
+            .unwrap();
+        assert_eq!(
+            get(&reopened, "allowWatchHistory", Some("app1")).await,
+            None
+        );
+        assert_eq!(
+            get(&reopened, "allowWatchHistory", None).await,
+            Some(json!(true))
+        );
+        let _ = fs::remove_dir_all(&backend.dir);
+    }
+
+    #[test]
+    fn test_file_backend_namespace_file_names() {
+        let backend = FileStorageBackend::new(PathBuf::from("/tmp/storage"));
+        assert_eq!(
+            backend.get_path("../DeviceName"),
+            PathBuf::from("/tmp/storage/%2E%2E%2FDeviceName.json")
+        );
+        assert_eq!(
+            backend.get_path("Privacy_1-a"),
+            PathBuf::from("/tmp/storage/Privacy_1-a.json")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_file_backend_namespaces_do_not_collide() {
+        let backend = get_backend("collide");
+        let namespaces = ["a/b", "a_b", "a?b", "a%2Fb"];
+        for (index, namespace) in namespaces.iter().enumerate() {
+            backend
+                .set(SetStorageProperty {
+                    namespace: namespace.to_string(),
+                    key: "key".to_owned(),
+                    data: StorageData::new(json!(index)),
+                    scope: None,
+                })
+                .await
+                .unwrap();
+        }
+        for (index, namespace) in namespaces.iter().enumerate() {
+            let value = backend
+                .get(GetStorageProperty {
+                    namespace: namespace.to_string(),
+                    key: "key".to_owned(),
+                    scope: None,
+                })
+                .await
+                .unwrap();
+            assert!(
+                matches!(value, ExtnResponse::StorageData(data) if data.value == json!(index)),
+                "{}",
+                namespace
+            );
+        }
+        let _ = fs::remove_dir_all(&backend.dir);
+    }
+}
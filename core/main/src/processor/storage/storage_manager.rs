@@ -19,8 +19,7 @@ use jsonrpsee::core::RpcResult;
 use ripple_sdk::{
     api::{
         device::device_peristence::{
-            DeleteStorageProperty, DevicePersistenceRequest, GetStorageProperty,
-            SetStorageProperty, StorageData,
+            DeleteStorageProperty, GetStorageProperty, SetStorageProperty, StorageData,
         },
         firebolt::fb_capabilities::CAPABILITY_NOT_AVAILABLE,
         storage_property::{StorageProperty, StoragePropertyData},
@@ -432,11 +431,7 @@ impl StorageManager {
             scope,
         };
 
-        match state
-            .get_client()
-            .send_extn_request(DevicePersistenceRequest::Set(ssp))
-            .await
-        {
+        match state.get_storage_backend().set(ssp).await {
             Ok(_) => {
                 StorageManager::notify(state, value.clone(), event_names, context).await;
                 Ok(StorageManagerResponse::Ok(()))
@@ -559,21 +554,7 @@ impl StorageManager {
             key: key.clone(),
             scope,
         };
-        let result = state
-            .get_client()
-            .send_extn_request(DevicePersistenceRequest::Get(data))
-            .await;
-
-        match result {
-            Ok(msg) => {
-                if let Some(m) = msg.payload.extract() {
-                    Ok(m)
-                } else {
-                    Err(RippleError::ParseError)
-                }
-            }
-            Err(e) => Err(e),
-        }
+        state.get_storage_backend().get(data).await
     }
 
     pub async fn delete(
@@ -588,20 +569,7 @@ impl StorageManager {
             key: key.clone(),
             scope,
         };
-        let result = state
-            .get_client()
-            .send_extn_request(DevicePersistenceRequest::Delete(data))
-            .await;
-        match result {
-            Ok(msg) => {
-                if let Some(m) = msg.payload.extract() {
-                    Ok(m)
-                } else {
-                    Err(RippleError::ParseError)
-                }
-            }
-            Err(e) => Err(e),
-        }
+        state.get_storage_backend().delete(data).await
     }
 
     pub fn get_firebolt_error(property: &StorageProperty) -> JsonRpcErrorType {
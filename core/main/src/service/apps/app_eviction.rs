@@ -0,0 +1,223 @@
+// Copyright 2025 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+use std::{fmt, time::Instant};
+
+use ripple_sdk::api::{
+    firebolt::fb_lifecycle::LifecycleState, manifest::device_manifest::LifecycleConfiguration,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EvictionReason {
+    MaxLoadedApps,
+    MinAvailableMemory,
+}
+
+impl fmt::Display for EvictionReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvictionReason::MaxLoadedApps => write!(f, "max_loaded_apps"),
+            EvictionReason::MinAvailableMemory => write!(f, "min_available_memory"),
+        }
+    }
+}
+
+/// What the policy needs to know about a loaded app.
+#[derive(Debug, Clone)]
+pub struct LoadedApp {
+    pub app_id: String,
+    pub state: LifecycleState,
+    pub last_active: Instant,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Eviction {
+    pub app_id: String,
+    pub reason: EvictionReason,
+}
+
+/*
+Keeps the loaded apps within the `max_loaded_apps` and `min_available_memory_kb` limits of the
+lifecycle configuration. It is checked before a new app is loaded and evicts the least recently
+active apps first. Prioritized apps, the app in the foreground and the app being launched are
+never evicted, and apps already unloading do not count as loaded. Memory is only freed once the
+evicted app finished unloading, so low memory evicts a single app per launch.
+*/
+#[derive(Debug, Clone)]
+pub struct EvictionPolicy {
+    max_loaded_apps: u64,
+    min_available_memory_kb: u64,
+    prioritized: Vec<String>,
+}
+
+impl From<&LifecycleConfiguration> for EvictionPolicy {
+    fn from(config: &LifecycleConfiguration) -> Self {
+        EvictionPolicy {
+            max_loaded_apps: config.max_loaded_apps,
+            min_available_memory_kb: config.min_available_memory_kb,
+            prioritized: config.prioritized.clone(),
+        }
+    }
+}
+
+impl EvictionPolicy {
+    pub fn get_max_loaded_apps(&self) -> u64 {
+        self.max_loaded_apps
+    }
+
+    pub fn get_min_available_memory_kb(&self) -> u64 {
+        self.min_available_memory_kb
+    }
+
+    pub fn is_memory_low(&self, available_memory_kb: u64) -> bool {
+        available_memory_kb < self.min_available_memory_kb
+    }
+
+    /// Apps to evict before `launching` is loaded, in the order they should be unloaded.
+    pub fn get_evictions(
+        &self,
+        apps: &[LoadedApp],
+        launching: &str,
+        available_memory_kb: Option<u64>,
+    ) -> Vec<Eviction> {
+        let loaded = apps
+            .iter()
+            .filter(|a| a.state != LifecycleState::Unloading && a.app_id != launching)
+            .count() as u64;
+        let mut candidates: Vec<&LoadedApp> = apps
+            .iter()
+            .filter(|a| {
+                a.app_id != launching
+                    && !matches!(
+                        a.state,
+                        LifecycleState::Unloading | LifecycleState::Foreground
+                    )
+                    && !self.prioritized.contains(&a.app_id)
+            })
+            .collect();
+        candidates.sort_by_key(|a| a.last_active);
+
+        let over_limit = if self.max_loaded_apps > 0 {
+            (loaded + 1).saturating_sub(self.max_loaded_apps) as usize
+        } else {
+            0
+        };
+        let (count, reason) = if over_limit > 0 {
+            (over_limit, EvictionReason::MaxLoadedApps)
+        } else if available_memory_kb.map_or(false, |m| self.is_memory_low(m)) {
+            (1, EvictionReason::MinAvailableMemory)
+        } else {
+            return Vec::new();
+        };
+
+        candidates
+            .into_iter()
+            .take(count)
+            .map(|a| Eviction {
+                app_id: a.app_id.clone(),
+                reason,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn policy(max_loaded_apps: u64, prioritized: &[&str]) -> EvictionPolicy {
+        EvictionPolicy {
+            max_loaded_apps,
+            min_available_memory_kb: 1024,
+            prioritized: prioritized.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+
+    fn apps(states: &[(&str, LifecycleState)]) -> Vec<LoadedApp> {
+        let start = Instant::now();
+        states
+            .iter()
+            .enumerate()
+            .map(|(i, (app_id, state))| LoadedApp {
+                app_id: app_id.to_string(),
+                state: *state,
+                last_active: start + Duration::from_secs(i as u64),
+            })
+            .collect()
+    }
+
+    fn evicted(evictions: Vec<Eviction>) -> Vec<String> {
+        evictions.into_iter().map(|e| e.app_id).collect()
+    }
+
+    #[test]
+    fn test_evicts_least_recently_active() {
+        let apps = apps(&[
+            ("launcher", LifecycleState::Background),
+            ("netflix", LifecycleState::Inactive),
+            ("youtube", LifecycleState::Suspended),
+            ("live", LifecycleState::Foreground),
+        ]);
+        assert!(policy(5, &[])
+            .get_evictions(&apps, "prime", None)
+            .is_empty());
+        let evictions = policy(3, &["launcher"]).get_evictions(&apps, "prime", None);
+        assert_eq!(evicted(evictions.clone()), vec!["netflix", "youtube"]);
+        assert_eq!(evictions[0].reason, EvictionReason::MaxLoadedApps);
+        // nothing left to evict but the foreground app
+        assert_eq!(
+            evicted(policy(1, &["launcher"]).get_evictions(&apps, "prime", None)),
+            vec!["netflix", "youtube"]
+        );
+    }
+
+    #[test]
+    fn test_unloading_and_launching_apps() {
+        let apps = apps(&[
+            ("netflix", LifecycleState::Unloading),
+            ("youtube", LifecycleState::Inactive),
+            ("prime", LifecycleState::Inactive),
+        ]);
+        // prime is relaunched and netflix is on its way out
+        assert!(policy(2, &[])
+            .get_evictions(&apps, "prime", None)
+            .is_empty());
+        assert_eq!(
+            evicted(policy(2, &[]).get_evictions(&apps, "live", None)),
+            vec!["youtube"]
+        );
+    }
+
+    #[test]
+    fn test_low_memory() {
+        let apps = apps(&[
+            ("netflix", LifecycleState::Inactive),
+            ("youtube", LifecycleState::Background),
+        ]);
+        let policy = policy(0, &[]);
+        assert!(policy.get_evictions(&apps, "prime", Some(2048)).is_empty());
+        let evictions = policy.get_evictions(&apps, "prime", Some(512));
+        assert_eq!(
+            evictions,
+            vec![Eviction {
+                app_id: "netflix".to_owned(),
+                reason: EvictionReason::MinAvailableMemory
+            }]
+        );
+    }
+}
@@ -19,19 +19,20 @@ use std::{
     collections::HashMap,
     env, fs,
     sync::{Arc, RwLock},
+    time::{Duration, Instant},
 };
 
 use ripple_sdk::{
     api::{
-        apps::{AppError, AppManagerResponse, AppMethod, AppSession, AppSession2_0, StateChange},
-        device::{device_user_grants_data::EvaluateAt, entertainment_data::NavigationIntent},
+        apps::{AppError, AppManagerResponse, AppMethod, AppSession, StateChange},
+        device::{
+            device_info_request::DeviceInfoRequest, device_user_grants_data::EvaluateAt,
+            entertainment_data::NavigationIntent,
+        },
         firebolt::{
             fb_capabilities::{DenyReason, DenyReasonWithCap, FireboltPermission},
             fb_discovery::DISCOVERY_EVENT_ON_NAVIGATE_TO,
-            fb_lifecycle::{
-                Lifecycle2_0AppEvent, Lifecycle2_0AppEventData, LifecycleManagerState,
-                LifecycleState, LifecycleStateChangeEvent,
-            },
+            fb_lifecycle::LifecycleState,
             fb_lifecycle_management::{
                 CompletedSessionResponse, PendingSessionResponse, SessionResponse,
                 LCM_EVENT_ON_SESSION_TRANSITION_CANCELED,
@@ -39,12 +40,14 @@ use ripple_sdk::{
             },
             fb_metrics::{AppLifecycleState, AppLifecycleStateChange},
             fb_secondscreen::SECOND_SCREEN_EVENT_ON_LAUNCH_REQUEST,
+            fb_telemetry::TelemetryAppEviction,
         },
         gateway::rpc_gateway_api::{AppIdentification, CallerSession},
     },
+    extn::extn_client_message::ExtnResponse,
     log::{debug, error, warn},
     serde_json::{self},
-    tokio::sync::{mpsc, oneshot},
+    tokio::sync::oneshot,
     utils::{error::RippleError, time_utils::Timer},
     uuid::Uuid,
 };
@@ -71,9 +74,12 @@ use ripple_sdk::{
 use serde_json::{json, Value};
 
 use crate::{
-    broker::{broker_utils::BrokerUtils, endpoint_broker::BrokerCallback},
+    broker::broker_utils::BrokerUtils,
     service::{
-        apps::app_events::AppEvents,
+        apps::{
+            app_events::AppEvents,
+            app_eviction::{EvictionPolicy, LoadedApp},
+        },
         extn::ripple_client::RippleClient,
         telemetry_builder::TelemetryBuilder,
         user_grants::{GrantHandler, GrantPolicyEnforcer, GrantState},
@@ -89,6 +95,7 @@ const APP_ID_TITLE_FILE_NAME: &str = "appInfo.json";
 const MIGRATED_APPS_FILE_NAME: &str = "migrations.json";
 const APP_ID_TITLE_DIR_NAME: &str = "app_info";
 const MIGRATED_APPS_DIR_NAME: &str = "apps";
+const AVAILABLE_MEMORY_TIMEOUT_MS: u64 = 1000;
 
 #[derive(Debug, Clone)]
 pub struct App {
@@ -102,15 +109,8 @@ pub struct App {
     pub app_id: String,
     pub app_metrics_version: Option<String>, // Provided by app via call to Metrics.appInfo
     pub is_app_init_params_invoked: bool,
-}
-
-#[derive(Debug, Clone)]
-pub struct App2_0 {
-    // Ripple currently only manages the session ID (app_instance_id) for App Lifecycle 2.0.
-    // No additional business logic is implemented.
-    // Extend this structure as more AI 2.0-specific features are added.
-    pub app_id: String,
-    pub current_session: AppSession2_0,
+    /// Last time the app was loaded or moved to the foreground, used to pick apps to evict
+    pub last_active: Instant,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -126,50 +126,6 @@ pub struct AppManagerState {
     migrated_apps_persist_path: String,
 }
 
-#[derive(Debug, Clone, Default)]
-pub struct AppManagerState2_0 {
-    // Ripple currently only manages the session ID (app_instance_id) for App Lifecycle 2.0.
-    // No additional business logic is implemented.
-    // Extend this structure as more AI 2.0-specific features are added.
-    apps: Arc<RwLock<HashMap<String, App2_0>>>,
-}
-
-impl AppManagerState2_0 {
-    pub fn new() -> Self {
-        AppManagerState2_0 {
-            apps: Arc::new(RwLock::new(HashMap::new())),
-        }
-    }
-
-    pub fn get(&self, app_id: &str) -> Option<App2_0> {
-        self.apps.read().unwrap().get(app_id).cloned()
-    }
-
-    pub fn insert(&self, app_id: String, app: App2_0) {
-        let mut apps = self.apps.write().unwrap();
-        let _ = apps.insert(app_id, app);
-    }
-
-    pub fn remove(&self, app_id: &str) -> Option<App2_0> {
-        let mut apps = self.apps.write().unwrap();
-        apps.remove(app_id)
-    }
-
-    pub fn get_app_id_from_session_id(&self, session_id: &str) -> Option<String> {
-        if let Some((_, app)) = self
-            .apps
-            .read()
-            .unwrap()
-            .iter()
-            .find(|(_, app)| app.current_session.app_instance_id.eq(session_id))
-        {
-            Some(app.app_id.clone())
-        } else {
-            None
-        }
-    }
-}
-
 impl AppManagerState {
     pub fn new(saved_dir: &str) -> Self {
         let app_title_persist_path = Self::get_storage_path(saved_dir, APP_ID_TITLE_DIR_NAME);
@@ -349,6 +305,9 @@ impl AppManagerState {
         let mut apps = self.apps.write().unwrap();
         if let Some(app) = apps.get_mut(app_id) {
             app.state = state;
+            if state == LifecycleState::Foreground {
+                app.last_active = Instant::now();
+            }
         }
     }
 
@@ -357,6 +316,19 @@ impl AppManagerState {
         let _ = apps.insert(app_id, app);
     }
 
+    pub fn get_loaded_apps(&self) -> Vec<LoadedApp> {
+        self.apps
+            .read()
+            .unwrap()
+            .values()
+            .map(|app| LoadedApp {
+                app_id: app.app_id.clone(),
+                state: app.state,
+                last_active: app.last_active,
+            })
+            .collect()
+    }
+
     pub fn get(&self, app_id: &str) -> Option<App> {
         self.apps.read().unwrap().get(app_id).cloned()
     }
@@ -452,237 +424,7 @@ impl DelegatedLauncherHandler {
         }
     }
 
-    #[allow(dead_code)]
-    fn create_new_app_session(platform_state: &PlatformState, event: LifecycleStateChangeEvent) {
-        let LifecycleStateChangeEvent {
-            app_id,
-            app_instance_id,
-            navigation_intent,
-            ..
-        } = event;
-
-        let mut session = AppSession2_0::new(app_id.clone(), app_instance_id);
-        if let Some(intent) = navigation_intent {
-            session.set_navigation_intent(intent);
-        }
-        platform_state.lifecycle2_app_state.insert(
-            app_id.clone(),
-            App2_0 {
-                app_id,
-                current_session: session,
-            },
-        );
-    }
-
-    #[allow(dead_code)]
-    async fn emit_lifecycle_app_event(
-        platform_state: &PlatformState,
-        app_id: &str,
-        event_ctor: fn(Lifecycle2_0AppEventData) -> Lifecycle2_0AppEvent,
-        old_state: LifecycleManagerState,
-        new_state: LifecycleManagerState,
-        source: Option<String>,
-    ) {
-        let event = event_ctor(Lifecycle2_0AppEventData {
-            previous: old_state.into(),
-            state: new_state.into(),
-            source,
-        });
-        AppEvents::emit_to_app(
-            platform_state,
-            app_id.to_string(),
-            event.as_event_name(),
-            &event.as_event_data_json().unwrap_or_default(),
-        )
-        .await;
-    }
-
-    async fn on_app_lifecycle_state_changed(
-        platform_state: &PlatformState,
-        event: LifecycleStateChangeEvent,
-    ) {
-        info!("on_app_lifecycle_2_state_changed: {:?}", event);
-        let LifecycleStateChangeEvent {
-            app_id,
-            old_state,
-            new_state,
-            ..
-        } = event.clone();
-
-        use Lifecycle2_0AppEvent::*;
-        use LifecycleManagerState::*;
-
-        // Update the navigation intent
-        if let Some(intent) = &event.navigation_intent {
-            // Get the App2_0 instance, update its session, and re-insert it
-            if let Some(mut app) = platform_state.lifecycle2_app_state.get(&app_id) {
-                app.current_session.set_navigation_intent(intent.clone());
-                platform_state
-                    .lifecycle2_app_state
-                    .insert(app_id.clone(), app);
-            }
-        }
-
-        match (old_state.clone(), new_state.clone()) {
-            (_, Loading) => {
-                Self::create_new_app_session(platform_state, event);
-            }
-            (_, Initializing) => {
-                // Ripple does not maintain the state of the app in the AppManagerState2_0
-                debug!(
-                    "on_app_lifecycle_state_changed : {} is in Initializing state",
-                    event.app_id
-                );
-            }
-            (Initializing, Paused) => {
-                Self::emit_lifecycle_app_event(
-                    platform_state,
-                    &app_id,
-                    OnStart,
-                    old_state,
-                    new_state,
-                    None,
-                )
-                .await;
-            }
-            (Initializing, Suspended) => {
-                Self::emit_lifecycle_app_event(
-                    platform_state,
-                    &app_id,
-                    OnStartSuspend,
-                    old_state,
-                    new_state,
-                    None,
-                )
-                .await;
-            }
-            (Paused, Active) => {
-                Self::emit_lifecycle_app_event(
-                    platform_state,
-                    &app_id,
-                    OnActivate,
-                    old_state,
-                    new_state,
-                    None,
-                )
-                .await;
-            }
-            (Active, Paused) => {
-                Self::emit_lifecycle_app_event(
-                    platform_state,
-                    &app_id,
-                    OnPause,
-                    old_state,
-                    new_state,
-                    None,
-                )
-                .await;
-            }
-            (Paused, Suspended) => {
-                Self::emit_lifecycle_app_event(
-                    platform_state,
-                    &app_id,
-                    OnSuspend,
-                    old_state,
-                    new_state,
-                    None,
-                )
-                .await;
-            }
-            (Suspended, Paused) => {
-                Self::emit_lifecycle_app_event(
-                    platform_state,
-                    &app_id,
-                    OnResume,
-                    old_state,
-                    new_state,
-                    None,
-                )
-                .await;
-            }
-            (Suspended, Hibernated) => {
-                Self::emit_lifecycle_app_event(
-                    platform_state,
-                    &app_id,
-                    OnHibernate,
-                    old_state,
-                    new_state,
-                    None,
-                )
-                .await;
-            }
-            (Hibernated, Suspended) => {
-                Self::emit_lifecycle_app_event(
-                    platform_state,
-                    &app_id,
-                    OnRestore,
-                    old_state,
-                    new_state,
-                    None,
-                )
-                .await;
-            }
-            (_, Terminating) => {
-                // Clean up the app session and the emit onDestroy app event.
-                platform_state.lifecycle2_app_state.remove(&app_id);
-                Self::emit_lifecycle_app_event(
-                    platform_state,
-                    &app_id,
-                    OnDestroy,
-                    old_state,
-                    new_state,
-                    None,
-                )
-                .await;
-            }
-            _ => {
-                debug!(
-                "on_app_lifecycle_state_changed : Unhandled state transition in Ripple: {:?} -> {:?}",
-                old_state.as_string(), new_state.as_string()
-            );
-            }
-        }
-    }
-
-    async fn set_up_lifecycle_manager_listener(&mut self) {
-        info!("Setting up lifecycle manager thunder listener");
-        let mut state = self.platform_state.clone();
-        let (sender, mut recv) = mpsc::channel(10);
-        let broker_callback = BrokerCallback { sender };
-        BrokerUtils::process_internal_subscription(
-            &mut state,
-            "lifecycle2.onAppLifecycleStateChanged",
-            Some(json!({"listen": true})),
-            None,
-            Some(broker_callback),
-        )
-        .await;
-        tokio::spawn(async move {
-            while let Some(e) = recv.recv().await {
-                debug!("Received lifecycle manager output: {:?}", e);
-                if let Some(p) = e.data.params {
-                    match serde_json::from_value::<LifecycleStateChangeEvent>(p) {
-                        Ok(event) => {
-                            Self::on_app_lifecycle_state_changed(&state, event).await;
-                        }
-                        Err(e) => {
-                            error!("Failed to deserialize LifecycleStateChangeEvent: {:?}", e);
-                        }
-                    }
-                }
-            }
-        });
-    }
-
     pub async fn start(&mut self) {
-        if std::env::var("RIPPLE_LIFECYCLE_2_ENABLED")
-            .ok()
-            .and_then(|s| s.parse::<bool>().ok())
-            .unwrap_or(false)
-        {
-            self.set_up_lifecycle_manager_listener().await;
-        }
-
         while let Some(data) = self.app_mgr_req_rx.recv().await {
             // App request
             debug!("DelegatedLauncherHandler: App request: data={:?}", data);
@@ -944,6 +686,7 @@ impl DelegatedLauncherHandler {
                     // because the other one is unloading, remove the old session now
                     self.end_session(&app_id).await.ok();
                 }
+                self.evict_apps(&app_id).await;
                 Ok(AppManagerResponse::Session(
                     self.precheck_then_load_or_activate(session, true).await,
                 ))
@@ -1183,6 +926,7 @@ impl DelegatedLauncherHandler {
             app_id: app_id.clone(),
             app_metrics_version: None,
             is_app_init_params_invoked: false,
+            last_active: Instant::now(),
         };
         platform_state
             .app_manager_state
@@ -1586,6 +1330,82 @@ impl DelegatedLauncherHandler {
         Ok(AppManagerResponse::None)
     }
 
+    /// Unloads the apps the eviction policy picks to make room for the app being launched.
+    async fn evict_apps(&mut self, launching_app_id: &str) {
+        let config = self
+            .platform_state
+            .get_device_manifest()
+            .get_lifecycle_configuration();
+        let policy = EvictionPolicy::from(&config);
+        let available_memory_kb = if policy.get_min_available_memory_kb() > 0 {
+            self.get_available_memory_kb().await
+        } else {
+            None
+        };
+        let apps = self.platform_state.app_manager_state.get_loaded_apps();
+        let loaded_apps = apps
+            .iter()
+            .filter(|a| a.state != LifecycleState::Unloading)
+            .count() as u64;
+
+        for eviction in policy.get_evictions(&apps, launching_app_id, available_memory_kb) {
+            info!(
+                "evict_apps: unloading app_id={} reason={} launching={}",
+                eviction.app_id, eviction.reason, launching_app_id
+            );
+            TelemetryBuilder::send_app_eviction(
+                &self.platform_state,
+                TelemetryAppEviction {
+                    app_id: eviction.app_id.clone(),
+                    reason: eviction.reason.to_string(),
+                    launching_app_id: launching_app_id.to_owned(),
+                    loaded_apps,
+                    max_loaded_apps: policy.get_max_loaded_apps(),
+                    available_memory_kb,
+                    min_available_memory_kb: policy.get_min_available_memory_kb(),
+                    ripple_session_id: String::new(),
+                },
+            );
+            if let Err(e) = self.unload(&eviction.app_id).await {
+                error!(
+                    "evict_apps: app_id={} could not be unloaded {:?}",
+                    eviction.app_id, e
+                );
+            }
+        }
+    }
+
+    /// Moves the app to Unloading through Inactive, the finished timer ends its session if
+    /// the app does not finish in time.
+    async fn unload(&mut self, app_id: &str) -> Result<AppManagerResponse, AppError> {
+        let app = self
+            .platform_state
+            .app_manager_state
+            .get(app_id)
+            .ok_or(AppError::NotFound)?;
+        if app.state != LifecycleState::Inactive {
+            self.set_state(app_id, LifecycleState::Inactive).await?;
+        }
+        self.set_state(app_id, LifecycleState::Unloading).await
+    }
+
+    async fn get_available_memory_kb(&self) -> Option<u64> {
+        let client = self.platform_state.get_client();
+        let request = client.send_extn_request(DeviceInfoRequest::AvailableMemory);
+        match tokio::time::timeout(Duration::from_millis(AVAILABLE_MEMORY_TIMEOUT_MS), request)
+            .await
+        {
+            Ok(Ok(response)) => match response.payload.extract() {
+                Some(ExtnResponse::Value(v)) => v.as_u64(),
+                _ => None,
+            },
+            _ => {
+                warn!("get_available_memory_kb: available memory not known");
+                None
+            }
+        }
+    }
+
     async fn check_finished(&mut self, app_id: &str) -> Result<AppManagerResponse, AppError> {
         debug!("check_finished: app_id={}", app_id);
         let entry = self.platform_state.app_manager_state.get(app_id);
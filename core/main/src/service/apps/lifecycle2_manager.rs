@@ -0,0 +1,552 @@
+// Copyright 2025 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use ripple_sdk::{
+    api::{
+        apps::AppSession2_0,
+        firebolt::fb_lifecycle::{
+            AppLifecycleState2_0, Lifecycle2_0AppEvent, Lifecycle2_0AppEventData,
+            LifecycleManagerState, LifecycleStateChangeEvent,
+        },
+    },
+    log::{debug, error, info, warn},
+    tokio::sync::mpsc,
+    utils::time_utils::Timer,
+};
+use serde_json::json;
+
+use crate::{
+    broker::{broker_utils::BrokerUtils, endpoint_broker::BrokerCallback},
+    service::{apps::app_events::AppEvents, telemetry_builder::TelemetryBuilder},
+    state::platform_state::PlatformState,
+};
+
+type AppEventCtor = fn(Lifecycle2_0AppEventData) -> Lifecycle2_0AppEvent;
+
+pub fn is_lifecycle2_enabled() -> bool {
+    std::env::var("RIPPLE_LIFECYCLE_2_ENABLED")
+        .ok()
+        .and_then(|s| s.parse::<bool>().ok())
+        .unwrap_or(false)
+}
+
+#[derive(Debug, Clone)]
+pub struct App2_0 {
+    pub app_id: String,
+    pub current_session: AppSession2_0,
+    pub state: LifecycleManagerState,
+    pub previous_state: Option<LifecycleManagerState>,
+}
+
+impl App2_0 {
+    pub fn new(session: AppSession2_0, state: LifecycleManagerState) -> Self {
+        App2_0 {
+            app_id: session.app_id.clone(),
+            current_session: session,
+            state,
+            previous_state: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AppManagerState2_0 {
+    apps: Arc<RwLock<HashMap<String, App2_0>>>,
+}
+
+impl AppManagerState2_0 {
+    pub fn new() -> Self {
+        AppManagerState2_0 {
+            apps: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    pub fn get(&self, app_id: &str) -> Option<App2_0> {
+        self.apps.read().unwrap().get(app_id).cloned()
+    }
+
+    pub fn insert(&self, app_id: String, app: App2_0) {
+        let mut apps = self.apps.write().unwrap();
+        let _ = apps.insert(app_id, app);
+    }
+
+    pub fn remove(&self, app_id: &str) -> Option<App2_0> {
+        let mut apps = self.apps.write().unwrap();
+        apps.remove(app_id)
+    }
+
+    pub fn get_state(&self, app_id: &str) -> Option<LifecycleManagerState> {
+        self.apps.read().unwrap().get(app_id).map(|app| app.state)
+    }
+
+    pub fn get_previous_state(&self, app_id: &str) -> Option<LifecycleManagerState> {
+        self.apps
+            .read()
+            .unwrap()
+            .get(app_id)
+            .and_then(|app| app.previous_state)
+    }
+
+    fn set_state(&self, app_id: &str, state: LifecycleManagerState) {
+        if let Some(app) = self.apps.write().unwrap().get_mut(app_id) {
+            app.previous_state = Some(app.state);
+            app.state = state;
+        }
+    }
+
+    fn set_navigation_intent(&self, app_id: &str, intent: String) {
+        if let Some(app) = self.apps.write().unwrap().get_mut(app_id) {
+            app.current_session.set_navigation_intent(intent);
+        }
+    }
+
+    fn is_in_state(
+        &self,
+        app_id: &str,
+        app_instance_id: &str,
+        state: LifecycleManagerState,
+    ) -> bool {
+        self.apps.read().unwrap().get(app_id).map_or(false, |app| {
+            app.state == state && app.current_session.app_instance_id.eq(app_instance_id)
+        })
+    }
+
+    pub fn get_app_id_from_session_id(&self, session_id: &str) -> Option<String> {
+        self.apps
+            .read()
+            .unwrap()
+            .values()
+            .find(|app| app.current_session.app_instance_id.eq(session_id))
+            .map(|app| app.app_id.clone())
+    }
+}
+
+/*
+Tracks the Lifecycle 2.0 state of the apps from the state changes of the platform lifecycle
+manager and emits the matching lifecycle events to the apps. Transitions the 2.0 lifecycle does
+not allow are logged and ignored, and the previous state of an event is the state Ripple tracked
+for the app. An app that stays in Initializing longer than `app_ready_timeout_ms`, or in
+Terminating longer than `app_finished_timeout_ms`, is terminated through the lifecycle manager.
+It stays tracked until the lifecycle manager reports it unloaded.
+*/
+pub struct Lifecycle2Manager {
+    platform_state: PlatformState,
+    timers: HashMap<String, Timer>,
+    ready_timeout_ms: u64,
+    finished_timeout_ms: u64,
+}
+
+impl Lifecycle2Manager {
+    pub fn new(platform_state: PlatformState) -> Self {
+        let policy = platform_state.get_device_manifest().get_lifecycle_policy();
+        Lifecycle2Manager {
+            platform_state,
+            timers: HashMap::new(),
+            ready_timeout_ms: policy.app_ready_timeout_ms,
+            finished_timeout_ms: policy.app_finished_timeout_ms,
+        }
+    }
+
+    pub async fn start(mut self) {
+        info!("Setting up lifecycle manager thunder listener");
+        let mut state = self.platform_state.clone();
+        let (sender, mut recv) = mpsc::channel(10);
+        let broker_callback = BrokerCallback { sender };
+        BrokerUtils::process_internal_subscription(
+            &mut state,
+            "lifecycle2.onAppLifecycleStateChanged",
+            Some(json!({"listen": true})),
+            None,
+            Some(broker_callback),
+        )
+        .await;
+        while let Some(e) = recv.recv().await {
+            debug!("Received lifecycle manager output: {:?}", e);
+            if let Some(p) = e.data.params {
+                match serde_json::from_value::<LifecycleStateChangeEvent>(p) {
+                    Ok(event) => self.on_app_lifecycle_state_changed(event).await,
+                    Err(e) => {
+                        error!("Failed to deserialize LifecycleStateChangeEvent: {:?}", e);
+                    }
+                }
+            }
+        }
+        error!("Lifecycle manager listener ended");
+    }
+
+    async fn on_app_lifecycle_state_changed(&mut self, event: LifecycleStateChangeEvent) {
+        info!("on_app_lifecycle_2_state_changed: {:?}", event);
+        let LifecycleStateChangeEvent {
+            app_id,
+            app_instance_id,
+            old_state,
+            new_state,
+            navigation_intent,
+        } = event;
+        let apps = &self.platform_state.lifecycle2_app_state;
+
+        let tracked = match apps.get(&app_id) {
+            Some(app) if new_state != LifecycleManagerState::Loading => {
+                if app.current_session.app_instance_id != app_instance_id {
+                    warn!(
+                        "on_app_lifecycle_state_changed: {} changed instance without loading",
+                        app_id
+                    );
+                }
+                app.state
+            }
+            _ => {
+                // a new instance, or an app that was loaded before Ripple started
+                let from = if new_state == LifecycleManagerState::Loading {
+                    LifecycleManagerState::Unloaded
+                } else {
+                    old_state
+                };
+                apps.insert(
+                    app_id.clone(),
+                    App2_0::new(AppSession2_0::new(app_id.clone(), app_instance_id), from),
+                );
+                from
+            }
+        };
+
+        /*
+        The lifecycle manager owns the state, an event missed or dropped earlier must not make
+        every later transition look invalid. Loading starts a new instance from Unloaded.
+        */
+        let from = if tracked != old_state && new_state != LifecycleManagerState::Loading {
+            warn!(
+                "on_app_lifecycle_state_changed: {} reported from {} but was {}, resyncing",
+                app_id,
+                old_state.as_string(),
+                tracked.as_string()
+            );
+            apps.set_state(&app_id, old_state);
+            old_state
+        } else {
+            tracked
+        };
+        if !Self::is_valid_transition(from, new_state) {
+            warn!(
+                "on_app_lifecycle_state_changed: {} can not move from {} to {}",
+                app_id,
+                from.as_string(),
+                new_state.as_string()
+            );
+            return;
+        }
+
+        if let Some(intent) = navigation_intent {
+            apps.set_navigation_intent(&app_id, intent);
+        }
+        apps.set_state(&app_id, new_state);
+        if let Some(timer) = self.timers.remove(&app_id) {
+            timer.cancel();
+        }
+
+        match new_state {
+            LifecycleManagerState::Initializing => {
+                self.start_timeout(&app_id, new_state, self.ready_timeout_ms)
+            }
+            LifecycleManagerState::Terminating => {
+                self.start_timeout(&app_id, new_state, self.finished_timeout_ms)
+            }
+            LifecycleManagerState::Unloaded => {
+                apps.remove(&app_id);
+            }
+            _ => {}
+        }
+
+        if let Some(event_ctor) = Self::get_app_event(from, new_state) {
+            let event = event_ctor(Lifecycle2_0AppEventData {
+                previous: from.into(),
+                state: new_state.into(),
+                source: None,
+            });
+            AppEvents::emit_to_app(
+                &self.platform_state,
+                app_id,
+                event.as_event_name(),
+                &event.as_event_data_json().unwrap_or_default(),
+            )
+            .await;
+        }
+    }
+
+    fn start_timeout(&mut self, app_id: &str, state: LifecycleManagerState, timeout_ms: u64) {
+        let app_instance_id = match self.platform_state.lifecycle2_app_state.get(app_id) {
+            Some(app) => app.current_session.app_instance_id,
+            None => return,
+        };
+        let platform_state = self.platform_state.clone();
+        let app_id_c = app_id.to_owned();
+        let timer = Timer::start(timeout_ms, async move {
+            let apps = &platform_state.lifecycle2_app_state;
+            if apps.is_in_state(&app_id_c, &app_instance_id, state) {
+                warn!(
+                    "{} still {} after {}ms, terminating it",
+                    app_id_c,
+                    state.as_string(),
+                    timeout_ms
+                );
+                if state == LifecycleManagerState::Initializing {
+                    TelemetryBuilder::send_app_load_stop(&platform_state, app_id_c.clone(), false);
+                }
+                if let Err(e) = BrokerUtils::process_internal_main_request(
+                    &platform_state,
+                    "lifecycle2.terminateApp",
+                    Some(json!({ "appInstanceId": app_instance_id })),
+                )
+                .await
+                {
+                    error!("Failed to terminate {}: {:?}", app_id_c, e);
+                }
+            }
+        });
+        self.timers.insert(app_id.to_owned(), timer);
+    }
+
+    pub fn is_valid_transition(from: LifecycleManagerState, to: LifecycleManagerState) -> bool {
+        use LifecycleManagerState::*;
+        match (from, to) {
+            (Unloaded, Loading) => true,
+            (Loading, Initializing | Unloaded) => true,
+            (Initializing, Paused | Suspended) => true,
+            (Paused, Active | Suspended) => true,
+            (Active, Paused) => true,
+            (Suspended, Paused | Hibernated) => true,
+            (Hibernated, Suspended) => true,
+            (Terminating, Unloaded) => true,
+            // Terminating can be reached from any running state
+            (Initializing | Paused | Active | Suspended | Hibernated, Terminating) => true,
+            _ => false,
+        }
+    }
+
+    fn get_app_event(
+        from: LifecycleManagerState,
+        to: LifecycleManagerState,
+    ) -> Option<AppEventCtor> {
+        use Lifecycle2_0AppEvent::*;
+        use LifecycleManagerState::*;
+        let event: AppEventCtor = match (from, to) {
+            (Initializing, Paused) => OnStart,
+            (Initializing, Suspended) => OnStartSuspend,
+            (Paused, Active) => OnActivate,
+            (Active, Paused) => OnPause,
+            (Paused, Suspended) => OnSuspend,
+            (Suspended, Paused) => OnResume,
+            (Suspended, Hibernated) => OnHibernate,
+            (Hibernated, Suspended) => OnRestore,
+            (_, Terminating) => OnDestroy,
+            _ => return None,
+        };
+        Some(event)
+    }
+
+    /// The Lifecycle 2.0 state an app would report, None if it is not loaded.
+    pub fn get_app_state(
+        platform_state: &PlatformState,
+        app_id: &str,
+    ) -> Option<AppLifecycleState2_0> {
+        platform_state
+            .lifecycle2_app_state
+            .get_state(app_id)
+            .map(AppLifecycleState2_0::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ripple_sdk::tokio;
+    use ripple_tdk::utils::test_utils::Mockable;
+    use std::time::Duration;
+    use LifecycleManagerState::*;
+
+    fn event(
+        app_id: &str,
+        old_state: LifecycleManagerState,
+        new_state: LifecycleManagerState,
+    ) -> LifecycleStateChangeEvent {
+        LifecycleStateChangeEvent {
+            app_id: app_id.to_owned(),
+            app_instance_id: format!("{}-1", app_id),
+            old_state,
+            new_state,
+            navigation_intent: None,
+        }
+    }
+
+    #[test]
+    fn test_valid_transitions() {
+        let path = [
+            Unloaded,
+            Loading,
+            Initializing,
+            Paused,
+            Active,
+            Paused,
+            Suspended,
+            Hibernated,
+            Suspended,
+            Terminating,
+            Unloaded,
+        ];
+        for states in path.windows(2) {
+            assert!(
+                Lifecycle2Manager::is_valid_transition(states[0], states[1]),
+                "{:?}",
+                states
+            );
+        }
+        assert!(!Lifecycle2Manager::is_valid_transition(Active, Active));
+        assert!(!Lifecycle2Manager::is_valid_transition(Active, Suspended));
+        assert!(!Lifecycle2Manager::is_valid_transition(Paused, Hibernated));
+        assert!(!Lifecycle2Manager::is_valid_transition(
+            Unloaded,
+            Terminating
+        ));
+        assert!(!Lifecycle2Manager::is_valid_transition(Terminating, Active));
+        assert!(!Lifecycle2Manager::is_valid_transition(Hibernated, Active));
+    }
+
+    #[test]
+    fn test_app_events() {
+        let name = |from, to| {
+            Lifecycle2Manager::get_app_event(from, to).map(|ctor| {
+                ctor(Lifecycle2_0AppEventData {
+                    previous: AppLifecycleState2_0::from(from),
+                    state: AppLifecycleState2_0::from(to),
+                    source: None,
+                })
+                .as_event_name()
+            })
+        };
+        assert_eq!(name(Initializing, Paused), Some("lifecycle.onStart"));
+        assert_eq!(name(Suspended, Paused), Some("lifecycle.onResume"));
+        assert_eq!(name(Hibernated, Suspended), Some("lifecycle.onRestore"));
+        assert_eq!(name(Active, Terminating), Some("lifecycle.onDestroy"));
+        assert_eq!(name(Loading, Initializing), None);
+    }
+
+    #[tokio::test]
+    async fn test_tracks_states() {
+        let platform_state = PlatformState::mock();
+        let mut manager = Lifecycle2Manager::new(platform_state.clone());
+        let apps = &platform_state.lifecycle2_app_state;
+
+        for (old, new) in [
+            (Unloaded, Loading),
+            (Loading, Initializing),
+            (Initializing, Paused),
+            (Paused, Active),
+        ] {
+            manager
+                .on_app_lifecycle_state_changed(event("app1", old, new))
+                .await;
+        }
+        assert_eq!(apps.get_state("app1"), Some(Active));
+        assert_eq!(apps.get_previous_state("app1"), Some(Paused));
+        assert_eq!(
+            apps.get_app_id_from_session_id("app1-1"),
+            Some("app1".to_owned())
+        );
+
+        // not a 2.0 transition, the state stays
+        manager
+            .on_app_lifecycle_state_changed(event("app1", Active, Hibernated))
+            .await;
+        assert_eq!(apps.get_state("app1"), Some(Active));
+
+        // a transition missed by Ripple, the tracked state is resynced to the event
+        manager
+            .on_app_lifecycle_state_changed(event("app1", Suspended, Paused))
+            .await;
+        assert_eq!(apps.get_previous_state("app1"), Some(Suspended));
+        assert_eq!(
+            Lifecycle2Manager::get_app_state(&platform_state, "app1"),
+            Some(AppLifecycleState2_0::Paused)
+        );
+
+        // invalid from the tracked Active, valid from the reported Suspended
+        manager
+            .on_app_lifecycle_state_changed(event("app1", Paused, Active))
+            .await;
+        manager
+            .on_app_lifecycle_state_changed(event("app1", Suspended, Hibernated))
+            .await;
+        assert_eq!(apps.get_state("app1"), Some(Hibernated));
+        assert_eq!(apps.get_previous_state("app1"), Some(Suspended));
+        manager
+            .on_app_lifecycle_state_changed(event("app1", Hibernated, Suspended))
+            .await;
+        manager
+            .on_app_lifecycle_state_changed(event("app1", Suspended, Paused))
+            .await;
+
+        manager
+            .on_app_lifecycle_state_changed(event("app1", Paused, Terminating))
+            .await;
+        manager
+            .on_app_lifecycle_state_changed(event("app1", Terminating, Unloaded))
+            .await;
+        assert!(apps.get("app1").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_transition_timeout() {
+        let platform_state = PlatformState::mock();
+        let mut manager = Lifecycle2Manager::new(platform_state.clone());
+        manager.ready_timeout_ms = 10;
+        let apps = &platform_state.lifecycle2_app_state;
+
+        manager
+            .on_app_lifecycle_state_changed(event("app1", Unloaded, Loading))
+            .await;
+        manager
+            .on_app_lifecycle_state_changed(event("app1", Loading, Initializing))
+            .await;
+        manager
+            .on_app_lifecycle_state_changed(event("app2", Unloaded, Loading))
+            .await;
+        manager
+            .on_app_lifecycle_state_changed(event("app2", Loading, Initializing))
+            .await;
+        manager
+            .on_app_lifecycle_state_changed(event("app2", Initializing, Paused))
+            .await;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(apps.get_state("app2"), Some(Paused));
+
+        // the timed out app stays tracked until the lifecycle manager unloads it
+        assert_eq!(apps.get_state("app1"), Some(Initializing));
+        manager
+            .on_app_lifecycle_state_changed(event("app1", Initializing, Terminating))
+            .await;
+        assert_eq!(apps.get_previous_state("app1"), Some(Initializing));
+        manager
+            .on_app_lifecycle_state_changed(event("app1", Terminating, Unloaded))
+            .await;
+        assert!(apps.get("app1").is_none());
+    }
+}
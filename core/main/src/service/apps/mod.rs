@@ -16,5 +16,7 @@
 //
 
 pub mod app_events;
+pub mod app_eviction;
 pub mod delegated_launcher_handler;
+pub mod lifecycle2_manager;
 pub mod provider_broker;
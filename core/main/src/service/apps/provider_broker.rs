@@ -15,12 +15,12 @@
 // SPDX-License-Identifier: Apache-2.0
 //
 
-use arrayvec::ArrayVec;
 use ripple_sdk::{
     api::{
         firebolt::{
             fb_capabilities::{CapEvent, FireboltCap},
             fb_general::ListenRequest,
+            fb_lifecycle::{LifecycleManagerState, LifecycleState},
             fb_lifecycle_management::{
                 LifecycleManagementEventRequest, LifecycleManagementProviderEvent,
             },
@@ -31,16 +31,17 @@ use ripple_sdk::{
             },
         },
         gateway::rpc_gateway_api::{CallContext, CallerSession},
+        manifest::{app_library::AppLibrary, device_manifest::ProviderSelectionPolicy},
     },
     log::{debug, error, info, warn},
     serde_json,
     tokio::sync::oneshot,
-    utils::channel_utils::oneshot_send_and_log,
+    utils::{channel_utils::oneshot_send_and_log, time_utils::Timer},
     uuid::Uuid,
 };
 
 use std::{
-    collections::HashMap,
+    collections::{HashMap, VecDeque},
     sync::{Arc, RwLock},
 };
 
@@ -49,7 +50,10 @@ use crate::{
     state::{cap::cap_state::CapState, platform_state::PlatformState},
 };
 
-const REQUEST_QUEUE_CAPACITY: usize = 3;
+const PROVIDER_NOT_FOUND_ERROR_CODE: i32 = 32001;
+const PROVIDER_TIMEOUT_ERROR_CODE: i32 = 32002;
+const PROVIDER_UNAVAILABLE_ERROR_CODE: i32 = 32003;
+const PROVIDER_QUEUE_FULL_ERROR_CODE: i32 = 32004;
 
 #[derive(Debug)]
 pub enum ProviderError {
@@ -61,9 +65,12 @@ pub enum ProviderError {
 
 #[derive(Default, Clone)]
 pub struct ProviderBrokerState {
-    provider_methods: Arc<RwLock<HashMap<String, ProviderMethod>>>,
+    // providers of each capability:method, in the order they registered
+    provider_methods: Arc<RwLock<HashMap<String, Vec<ProviderMethod>>>>,
     active_sessions: Arc<RwLock<HashMap<String, ProviderSession>>>,
-    request_queue: Arc<RwLock<ArrayVec<ProviderBrokerRequest, REQUEST_QUEUE_CAPACITY>>>,
+    request_queue: Arc<RwLock<VecDeque<QueuedRequest>>>,
+    // This is a map <capability, app_id> set by the launcher
+    chosen_providers: Arc<RwLock<HashMap<String, String>>>,
 }
 
 impl std::fmt::Debug for ProviderBrokerState {
@@ -76,6 +83,7 @@ pub struct ProviderBroker {}
 
 #[derive(Clone, Debug)]
 struct ProviderMethod {
+    capability: String,
     event_name: String,
     provider: CallContext,
 }
@@ -86,6 +94,7 @@ struct ProviderSession {
     provider: ProviderMethod,
     _capability: String,
     focused: bool,
+    timer: Option<Timer>,
 }
 
 #[derive(Debug)]
@@ -98,6 +107,13 @@ pub struct ProviderBrokerRequest {
     pub app_id: Option<String>,
 }
 
+#[derive(Debug)]
+struct QueuedRequest {
+    id: String,
+    request: ProviderBrokerRequest,
+    timer: Option<Timer>,
+}
+
 #[derive(Debug)]
 struct ProviderCaller {
     session: CallerSession,
@@ -148,13 +164,12 @@ impl ProviderBroker {
     ) {
         let mut provider_methods = pst.provider_broker_state.provider_methods.write().unwrap();
         let cap_method = format!("{}:{}", capability, method);
-        if let Some(method) = provider_methods.get(&cap_method) {
-            // unregister the capability if it is provided by the session
-            // that is making the unregister call
-            if method.provider.session_id == provider.session_id {
+        if let Some(methods) = provider_methods.get_mut(&cap_method) {
+            // only the session that registered a provider can unregister it
+            methods.retain(|method| method.provider.session_id != provider.session_id);
+            if methods.is_empty() {
                 provider_methods.remove(&cap_method);
             }
-            ProviderBroker::remove_request(pst, &capability);
         }
 
         // TODO Add permissions
@@ -176,16 +191,21 @@ impl ProviderBroker {
         AppEvents::add_listener(pst, event_name.clone(), provider.clone(), listen_request);
         {
             let mut provider_methods = pst.provider_broker_state.provider_methods.write().unwrap();
-            provider_methods.insert(
-                cap_method,
-                ProviderMethod {
-                    event_name,
-                    provider,
-                },
-            );
+            let methods = provider_methods.entry(cap_method).or_default();
+            // a session registering again moves to the end as the most recent provider
+            methods.retain(|method| method.provider.session_id != provider.session_id);
+            methods.push(ProviderMethod {
+                capability: capability.clone(),
+                event_name,
+                provider,
+            });
+        }
+        // drained first, a request for another method of the capability goes back to the queue
+        let mut pending = Vec::new();
+        while let Some(request) = ProviderBroker::remove_request(pst, &capability) {
+            pending.push(request);
         }
-        let existing = ProviderBroker::remove_request(pst, &capability);
-        if let Some(request) = existing {
+        for request in pending {
             info!("register_provider: Found pending provider request, invoking");
             ProviderBroker::invoke_method(pst, request).await;
         }
@@ -205,7 +225,7 @@ impl ProviderBroker {
         let caps_keys = provider_methods.keys();
         let all_caps = caps_keys.cloned().collect::<Vec<String>>();
         for cap in all_caps {
-            if let Some(provider) = provider_methods.get(&cap) {
+            for provider in provider_methods.get(&cap).into_iter().flatten() {
                 if let Some(list) = result.get_mut(&provider.provider.app_id) {
                     list.push(provider.event_name.clone());
                 } else {
@@ -219,6 +239,98 @@ impl ProviderBroker {
         ProviderResult::new(result)
     }
 
+    /// Apps currently providing any method of the capability, the selected one first.
+    pub fn get_providers(pst: &PlatformState, capability: &str) -> Vec<String> {
+        let provider_methods = pst.provider_broker_state.provider_methods.read().unwrap();
+        let mut providers: Vec<&ProviderMethod> = provider_methods
+            .values()
+            .flatten()
+            .filter(|method| method.capability == capability)
+            .collect();
+        if let Some(selected) = ProviderBroker::select_provider(pst, capability, &providers) {
+            let selected = providers.remove(selected);
+            providers.insert(0, selected);
+        }
+        let mut app_ids: Vec<String> = Vec::new();
+        for method in providers {
+            if !app_ids.contains(&method.provider.app_id) {
+                app_ids.push(method.provider.app_id.clone());
+            }
+        }
+        app_ids
+    }
+
+    /// Provider picked for the capability under the launcher selection policy, None clears it.
+    pub fn set_chosen_provider(pst: &PlatformState, capability: String, app_id: Option<String>) {
+        let mut chosen_providers = pst.provider_broker_state.chosen_providers.write().unwrap();
+        match app_id {
+            Some(app_id) => {
+                chosen_providers.insert(capability, app_id);
+            }
+            None => {
+                chosen_providers.remove(&capability);
+            }
+        }
+    }
+
+    fn select_provider(
+        pst: &PlatformState,
+        capability: &str,
+        providers: &[&ProviderMethod],
+    ) -> Option<usize> {
+        let chosen = pst
+            .provider_broker_state
+            .chosen_providers
+            .read()
+            .unwrap()
+            .get(capability)
+            .cloned();
+        ProviderBroker::select_provider_index(
+            pst.get_device_manifest().get_provider_selection_policy(),
+            providers,
+            chosen.as_deref(),
+            |app_id| ProviderBroker::is_foreground(pst, app_id),
+            |app_id| AppLibrary::get_provider_priority(&pst.app_library_state, app_id),
+        )
+    }
+
+    fn select_provider_index(
+        policy: ProviderSelectionPolicy,
+        providers: &[&ProviderMethod],
+        chosen: Option<&str>,
+        is_foreground: impl Fn(&str) -> bool,
+        priority: impl Fn(&str) -> Option<u32>,
+    ) -> Option<usize> {
+        let app_ids: Vec<&str> = providers
+            .iter()
+            .map(|method| method.provider.app_id.as_str())
+            .collect();
+        // a provider chosen through setProvider takes precedence over the policy
+        if let Some(index) = chosen.and_then(|chosen| app_ids.iter().rposition(|id| *id == chosen))
+        {
+            return Some(index);
+        }
+        let selected = match policy {
+            ProviderSelectionPolicy::Foreground => app_ids.iter().rposition(|id| is_foreground(id)),
+            ProviderSelectionPolicy::Priority => app_ids
+                .iter()
+                .enumerate()
+                .filter_map(|(i, id)| priority(id).map(|p| (i, p)))
+                // ties go to the most recently registered
+                .max_by_key(|(i, p)| (*p, *i))
+                .map(|(i, _)| i),
+            ProviderSelectionPolicy::Launcher => None,
+        };
+        selected.or_else(|| app_ids.len().checked_sub(1))
+    }
+
+    fn is_foreground(pst: &PlatformState, app_id: &str) -> bool {
+        pst.app_manager_state
+            .get(app_id)
+            .map_or(false, |app| app.state == LifecycleState::Foreground)
+            || pst.lifecycle2_app_state.get_state(app_id) == Some(LifecycleManagerState::Active)
+    }
+
     pub async fn invoke_method(
         pst: &PlatformState,
         request: ProviderBrokerRequest,
@@ -235,7 +347,20 @@ impl ProviderBroker {
 
         let provider_opt = {
             let provider_methods = pst.provider_broker_state.provider_methods.read().unwrap();
-            provider_methods.get(&cap_method).cloned()
+            let providers: Vec<&ProviderMethod> = provider_methods
+                .get(&cap_method)
+                .into_iter()
+                .flatten()
+                .collect();
+            // a request for a specific app goes to that app's provider
+            let for_app = request.app_id.as_ref().and_then(|app_id| {
+                providers
+                    .iter()
+                    .rposition(|method| method.provider.app_id == *app_id)
+            });
+            for_app
+                .or_else(|| ProviderBroker::select_provider(pst, &request.capability, &providers))
+                .map(|i| providers[i].clone())
         };
 
         if let Some(provider_method) = provider_opt {
@@ -281,29 +406,99 @@ impl ProviderBroker {
                 .await;
                 provider_app_id = Some(provider_method.provider.app_id);
             }
+        } else if pst
+            .get_device_manifest()
+            .get_provider_request_queue_capacity()
+            > 0
+        {
+            ProviderBroker::queue_request(pst, request);
         } else {
             // If no provider found, send error response
-            request
-                .tx
-                .send(ProviderResponsePayload::GenericError(
-                    GenericProviderError {
-                        code: 32001,
-                        message: format!("Provider not found for {}", request.method),
-                        data: None,
-                    },
-                ))
-                .unwrap();
+            oneshot_send_and_log(
+                request.tx,
+                ProviderResponsePayload::GenericError(GenericProviderError {
+                    code: PROVIDER_NOT_FOUND_ERROR_CODE,
+                    message: format!("Provider not found for {}", request.method),
+                    data: None,
+                }),
+                "ProviderResponse",
+            );
         }
 
         provider_app_id
     }
 
+    fn queue_request(pst: &PlatformState, request: ProviderBrokerRequest) {
+        let manifest = pst.get_device_manifest();
+        let mut request_queue = pst.provider_broker_state.request_queue.write().unwrap();
+        if request_queue.len() >= manifest.get_provider_request_queue_capacity() {
+            warn!(
+                "queue_request: queue full, rejecting request for {}",
+                request.capability
+            );
+            oneshot_send_and_log(
+                request.tx,
+                ProviderResponsePayload::GenericError(GenericProviderError {
+                    code: PROVIDER_QUEUE_FULL_ERROR_CODE,
+                    message: format!("Too many pending requests for {}", request.method),
+                    data: None,
+                }),
+                "ProviderResponse",
+            );
+            return;
+        }
+        let id = Uuid::new_v4().to_string();
+        debug!("queued provider request {} {}", id, request.capability);
+        let timeout_ms = manifest.get_provider_response_timeout_ms(&request.capability);
+        let timer = (timeout_ms > 0).then(|| {
+            let pst_c = pst.clone();
+            let id_c = id.clone();
+            Timer::start(timeout_ms, async move {
+                ProviderBroker::on_queued_timeout(&pst_c, &id_c, timeout_ms)
+            })
+        });
+        request_queue.push_back(QueuedRequest { id, request, timer });
+    }
+
+    fn on_queued_timeout(pst: &PlatformState, id: &str, timeout_ms: u64) {
+        let queued = {
+            let mut request_queue = pst.provider_broker_state.request_queue.write().unwrap();
+            let index = request_queue.iter().position(|queued| queued.id == id);
+            index.and_then(|index| request_queue.remove(index))
+        };
+        if let Some(queued) = queued {
+            warn!(
+                "no provider registered for {} within {}ms",
+                queued.request.capability, timeout_ms
+            );
+            oneshot_send_and_log(
+                queued.request.tx,
+                ProviderResponsePayload::GenericError(GenericProviderError {
+                    code: PROVIDER_TIMEOUT_ERROR_CODE,
+                    message: format!("Provider not found within {}ms", timeout_ms),
+                    data: None,
+                }),
+                "ProviderResponse",
+            );
+        }
+    }
+
     fn start_provider_session(
         pst: &PlatformState,
         request: ProviderBrokerRequest,
         provider: ProviderMethod,
     ) -> String {
         let c_id = Uuid::new_v4().to_string();
+        let timeout_ms = pst
+            .get_device_manifest()
+            .get_provider_response_timeout_ms(&request.capability);
+        let timer = (timeout_ms > 0).then(|| {
+            let pst_c = pst.clone();
+            let c_id_c = c_id.clone();
+            Timer::start(timeout_ms, async move {
+                ProviderBroker::on_provider_timeout(&pst_c, &c_id_c, timeout_ms)
+            })
+        });
         let mut active_sessions = pst.provider_broker_state.active_sessions.write().unwrap();
         debug!("started provider session {} {}", c_id, request.capability);
         active_sessions.insert(
@@ -316,31 +511,65 @@ impl ProviderBroker {
                 provider,
                 _capability: request.capability,
                 focused: false,
+                timer,
             },
         );
         c_id
     }
 
+    fn on_provider_timeout(pst: &PlatformState, c_id: &str, timeout_ms: u64) {
+        let session = {
+            let mut active_sessions = pst.provider_broker_state.active_sessions.write().unwrap();
+            active_sessions.remove(c_id)
+        };
+        if let Some(mut session) = session {
+            warn!(
+                "provider session {} timed out after {}ms, provider={}",
+                c_id, timeout_ms, session.provider.provider.app_id
+            );
+            // the timer is the one running this, there is nothing left to cancel
+            session.timer = None;
+            ProviderBroker::end_session(
+                pst,
+                session,
+                ProviderResponsePayload::GenericError(GenericProviderError {
+                    code: PROVIDER_TIMEOUT_ERROR_CODE,
+                    message: format!("Provider did not respond within {}ms", timeout_ms),
+                    data: None,
+                }),
+            );
+        }
+    }
+
+    /// Answers the caller of a finished session and releases the provider focus.
+    fn end_session(pst: &PlatformState, session: ProviderSession, result: ProviderResponsePayload) {
+        if let Some(timer) = session.timer {
+            timer.cancel();
+        }
+        oneshot_send_and_log(session.caller.tx, result, "ProviderResponse");
+        if session.focused {
+            let app_id = session.provider.provider.app_id;
+            let event = LifecycleManagementEventRequest::Provide(
+                LifecycleManagementProviderEvent::Remove(app_id),
+            );
+            let client = pst.clone().get_client();
+            if let Err(e) = client.send_event(event) {
+                error!("send event error {:?}", e);
+            }
+        }
+    }
+
     pub async fn provider_response(pst: &PlatformState, resp: ProviderResponse) {
         debug!(
             "provider_response, {}, {:?}",
             resp.correlation_id, resp.result
         );
-        let mut active_sessions = pst.provider_broker_state.active_sessions.write().unwrap();
-        match active_sessions.remove(&resp.correlation_id) {
-            Some(session) => {
-                oneshot_send_and_log(session.caller.tx, resp.result, "ProviderResponse");
-                if session.focused {
-                    let app_id = session.provider.provider.app_id;
-                    let event = LifecycleManagementEventRequest::Provide(
-                        LifecycleManagementProviderEvent::Remove(app_id),
-                    );
-                    let client = pst.clone().get_client();
-                    if let Err(e) = client.send_event(event) {
-                        error!("send event error {:?}", e);
-                    }
-                }
-            }
+        let session = {
+            let mut active_sessions = pst.provider_broker_state.active_sessions.write().unwrap();
+            active_sessions.remove(&resp.correlation_id)
+        };
+        match session {
+            Some(session) => ProviderBroker::end_session(pst, session, resp.result),
             None => {
                 error!("Ignored provider response because there was no active session waiting")
             }
@@ -353,7 +582,7 @@ impl ProviderBroker {
         let all_cids = cid_keys.cloned().collect::<Vec<String>>();
         let mut clear_cids = Vec::<String>::new();
         // find all the sessions where either the caller or the provider are being unregistered and clear that session
-        // the oneshot for the caller should then get descoped and called with an error
+        // the caller is answered with an error once the locks are released
         for cid in all_cids {
             if let Some(session) = active_sessions.get(&cid) {
                 if session.provider.provider.session_id == session_id {
@@ -367,8 +596,23 @@ impl ProviderBroker {
             }
         }
 
+        let mut ended_sessions = Vec::new();
         for cid in clear_cids {
-            active_sessions.remove(&cid);
+            if let Some(session) = active_sessions.remove(&cid) {
+                ended_sessions.push(session);
+            }
+        }
+        drop(active_sessions);
+        for session in ended_sessions {
+            ProviderBroker::end_session(
+                pst,
+                session,
+                ProviderResponsePayload::GenericError(GenericProviderError {
+                    code: PROVIDER_UNAVAILABLE_ERROR_CODE,
+                    message: "Provider session ended before responding".to_owned(),
+                    data: None,
+                }),
+            );
         }
         let mut provider_methods = pst.provider_broker_state.provider_methods.write().unwrap();
         // find all providers for the session being unregistered
@@ -377,8 +621,9 @@ impl ProviderBroker {
         let caps_keys = provider_methods.keys();
         let all_caps = caps_keys.cloned().collect::<Vec<String>>();
         for cap in all_caps {
-            if let Some(provider) = provider_methods.get(&cap) {
-                if provider.provider.session_id == session_id {
+            if let Some(providers) = provider_methods.get_mut(&cap) {
+                providers.retain(|provider| provider.provider.session_id != session_id);
+                if providers.is_empty() {
                     clear_caps.push(cap);
                 }
             }
@@ -403,12 +648,12 @@ impl ProviderBroker {
     fn remove_request(pst: &PlatformState, capability: &String) -> Option<ProviderBrokerRequest> {
         let mut request_queue = pst.provider_broker_state.request_queue.write().unwrap();
         let mut iter = request_queue.iter();
-        let cap = iter.position(|request| request.capability.eq(capability));
-        if let Some(index) = cap {
-            let request = request_queue.remove(index);
-            return Some(request);
+        let cap = iter.position(|queued| queued.request.capability.eq(capability));
+        let queued = request_queue.remove(cap?)?;
+        if let Some(timer) = queued.timer {
+            timer.cancel();
         }
-        None
+        Some(queued.request)
     }
 
     pub async fn focus(
@@ -435,3 +680,235 @@ impl ProviderBroker {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ripple_sdk::{serde_json::json, tokio};
+    use ripple_tdk::utils::test_utils::Mockable;
+
+    fn start_session(
+        pst: &PlatformState,
+        provider: CallContext,
+    ) -> (String, oneshot::Receiver<ProviderResponsePayload>) {
+        let (tx, rx) = oneshot::channel();
+        let request = ProviderBrokerRequest {
+            capability: "xrn:firebolt:capability:test:provider".to_owned(),
+            method: "test.provide".to_owned(),
+            caller: CallerSession::default(),
+            request: ProviderRequestPayload::Generic(json!({})),
+            tx,
+            app_id: None,
+        };
+        let method = ProviderMethod {
+            capability: "xrn:firebolt:capability:test:provider".to_owned(),
+            event_name: "test.onRequestProvide".to_owned(),
+            provider,
+        };
+        let c_id = ProviderBroker::start_provider_session(pst, request, method);
+        (c_id, rx)
+    }
+
+    fn error_code(result: ProviderResponsePayload) -> i32 {
+        match result {
+            ProviderResponsePayload::GenericError(e) => e.code,
+            other => panic!("expected an error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_provider_timeout_answers_caller() {
+        let pst = PlatformState::mock();
+        let (c_id, rx) = start_session(&pst, CallContext::mock());
+
+        ProviderBroker::on_provider_timeout(&pst, &c_id, 10);
+
+        assert_eq!(error_code(rx.await.unwrap()), PROVIDER_TIMEOUT_ERROR_CODE);
+        assert!(pst
+            .provider_broker_state
+            .active_sessions
+            .read()
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_unregister_session_answers_caller() {
+        let pst = PlatformState::mock();
+        let provider = CallContext::mock();
+        let (_, rx) = start_session(&pst, provider.clone());
+        let (other_c_id, _other_rx) = start_session(&pst, CallContext::mock());
+
+        ProviderBroker::unregister_session(&pst, provider.session_id).await;
+
+        assert_eq!(
+            error_code(rx.await.unwrap()),
+            PROVIDER_UNAVAILABLE_ERROR_CODE
+        );
+        let active_sessions = pst.provider_broker_state.active_sessions.read().unwrap();
+        assert_eq!(active_sessions.len(), 1);
+        assert!(active_sessions.contains_key(&other_c_id));
+    }
+
+    fn provider(app_id: &str) -> ProviderMethod {
+        let mut ctx = CallContext::mock();
+        ctx.app_id = app_id.to_owned();
+        ProviderMethod {
+            capability: "xrn:firebolt:capability:test:provider".to_owned(),
+            event_name: "test.onRequestProvide".to_owned(),
+            provider: ctx,
+        }
+    }
+
+    #[test]
+    fn test_select_provider_index() {
+        let methods = [provider("app1"), provider("app2"), provider("app3")];
+        let providers: Vec<&ProviderMethod> = methods.iter().collect();
+        let select =
+            |policy, chosen, foreground: &'static str, priorities: &'static [(&str, u32)]| {
+                ProviderBroker::select_provider_index(
+                    policy,
+                    &providers,
+                    chosen,
+                    |app_id| app_id == foreground,
+                    |app_id| {
+                        priorities
+                            .iter()
+                            .find(|(id, _)| *id == app_id)
+                            .map(|(_, p)| *p)
+                    },
+                )
+            };
+
+        assert_eq!(
+            select(ProviderSelectionPolicy::Foreground, None, "app1", &[]),
+            Some(0)
+        );
+        // nothing in the foreground, the most recent provider
+        assert_eq!(
+            select(ProviderSelectionPolicy::Foreground, None, "", &[]),
+            Some(2)
+        );
+        assert_eq!(
+            select(
+                ProviderSelectionPolicy::Priority,
+                None,
+                "",
+                &[("app1", 5), ("app2", 1)]
+            ),
+            Some(0)
+        );
+        assert_eq!(
+            select(
+                ProviderSelectionPolicy::Priority,
+                None,
+                "",
+                &[("app1", 5), ("app2", 5)]
+            ),
+            Some(1)
+        );
+        assert_eq!(
+            select(ProviderSelectionPolicy::Launcher, Some("app2"), "app1", &[]),
+            Some(1)
+        );
+        assert_eq!(
+            select(ProviderSelectionPolicy::Launcher, Some("gone"), "app1", &[]),
+            Some(2)
+        );
+        // the choice wins under the other policies as well
+        assert_eq!(
+            select(
+                ProviderSelectionPolicy::Foreground,
+                Some("app2"),
+                "app1",
+                &[]
+            ),
+            Some(1)
+        );
+        assert_eq!(
+            select(
+                ProviderSelectionPolicy::Priority,
+                Some("app3"),
+                "",
+                &[("app1", 5)]
+            ),
+            Some(2)
+        );
+        assert_eq!(
+            select(
+                ProviderSelectionPolicy::Foreground,
+                Some("gone"),
+                "app1",
+                &[]
+            ),
+            Some(0)
+        );
+        assert_eq!(
+            ProviderBroker::select_provider_index(
+                ProviderSelectionPolicy::Foreground,
+                &[],
+                None,
+                |_| true,
+                |_| None
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_chosen_provider_under_foreground_policy() {
+        let pst = PlatformState::mock();
+        assert_eq!(
+            pst.get_device_manifest().get_provider_selection_policy(),
+            ProviderSelectionPolicy::Foreground
+        );
+        let capability = "xrn:firebolt:capability:test:provider";
+        let methods = [provider("app1"), provider("app2")];
+        let providers: Vec<&ProviderMethod> = methods.iter().collect();
+
+        ProviderBroker::set_chosen_provider(&pst, capability.to_owned(), Some("app1".to_owned()));
+        assert_eq!(
+            ProviderBroker::select_provider(&pst, capability, &providers),
+            Some(0)
+        );
+        ProviderBroker::set_chosen_provider(&pst, capability.to_owned(), None);
+        assert_eq!(
+            ProviderBroker::select_provider(&pst, capability, &providers),
+            Some(1)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_multiple_providers() {
+        let pst = PlatformState::mock();
+        let capability = "xrn:firebolt:capability:test:provider";
+        let register = |method: ProviderMethod, listen: bool| {
+            ProviderBroker::register_or_unregister_provider(
+                &pst,
+                capability.to_owned(),
+                "test.provide".to_owned(),
+                method.event_name,
+                method.provider,
+                ListenRequest { listen },
+            )
+        };
+        let app1 = provider("app1");
+        let app2 = provider("app2");
+        register(app1.clone(), true).await;
+        register(app2.clone(), true).await;
+        assert_eq!(
+            ProviderBroker::get_providers(&pst, capability),
+            vec!["app2".to_owned(), "app1".to_owned()]
+        );
+
+        // the second registration does not replace the first
+        register(app2, false).await;
+        assert_eq!(
+            ProviderBroker::get_providers(&pst, capability),
+            vec!["app1".to_owned()]
+        );
+
+        ProviderBroker::unregister_session(&pst, app1.provider.session_id).await;
+        assert!(ProviderBroker::get_providers(&pst, capability).is_empty());
+    }
+}
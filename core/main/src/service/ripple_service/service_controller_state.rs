@@ -14,18 +14,21 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 //
-use std::{collections::HashMap, net::SocketAddr, sync::Arc};
+use std::{collections::HashMap, io::Write, net::SocketAddr, sync::Arc};
 
 use futures::{stream::SplitStream, SinkExt, StreamExt};
 use ripple_sdk::api::gateway::rpc_gateway_api::JsonRpcApiResponse;
 use ripple_sdk::{
-    api::{gateway::rpc_gateway_api::ApiMessage, manifest::extn_manifest::ExtnSymbol},
+    api::{
+        gateway::rpc_gateway_api::ApiMessage,
+        manifest::extn_manifest::{ExtnManifest, ExtnSymbol, ServiceCredential},
+    },
     extn::{
         extn_client_message::{ExtnMessage, ExtnPayload, ExtnResponse},
         extn_id::ExtnId,
     },
     framework::ripple_contract::RippleContract,
-    log::{error, info, trace},
+    log::{error, info, trace, warn},
     service::{
         service_event_state::ServiceEventState,
         service_message::{Id, JsonRpcMessage, ServiceMessage},
@@ -63,11 +66,45 @@ pub struct ServiceInfo {
     callback_list: Arc<Mutex<HashMap<u64, BrokerCallback>>>,
 }
 
+/// Reasons a service handshake is refused.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ServiceAuthError {
+    Unlisted,
+    MissingCredential,
+    InvalidCredential,
+}
+
+impl std::fmt::Display for ServiceAuthError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ServiceAuthError::Unlisted => write!(f, "service is not in the manifest"),
+            ServiceAuthError::MissingCredential => write!(f, "service credential missing"),
+            ServiceAuthError::InvalidCredential => write!(f, "service credential invalid"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum ExpectedCredential {
+    None,
+    Token(String),
+    // the token file could not be written, nothing can match
+    Unavailable,
+}
+
+#[derive(Debug, Clone)]
+struct ListedService {
+    symbol: ExtnSymbol,
+    credential: ExpectedCredential,
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct ServiceControllerState {
     pub service_info: Arc<Mutex<ServiceRegistry>>,
     pub service_event_state: ServiceEventState,
     pub service_notification_processor: ServiceNotificationProcessor,
+    services: Arc<HashMap<String, ListedService>>,
+    allow_unlisted_services: bool,
 }
 
 impl ServiceInfo {
@@ -117,11 +154,80 @@ impl ServiceInfo {
 }
 
 impl ServiceControllerState {
-    pub fn new() -> Self {
+    pub fn new(extn_manifest: &ExtnManifest) -> Self {
+        let services = extn_manifest
+            .get_all_extns()
+            .into_iter()
+            .map(|symbol| {
+                let credential = match symbol.get_service_credential() {
+                    None => ExpectedCredential::None,
+                    Some(ServiceCredential::Secret(secret)) => ExpectedCredential::Token(secret),
+                    Some(ServiceCredential::TokenFile(path)) => Self::write_token_file(&path),
+                };
+                (symbol.id.clone(), ListedService { symbol, credential })
+            })
+            .collect();
         ServiceControllerState {
             service_info: Arc::new(Mutex::new(ServiceRegistry::default())),
             service_event_state: ServiceEventState::new(),
             service_notification_processor: ServiceNotificationProcessor::new(),
+            services: Arc::new(services),
+            allow_unlisted_services: extn_manifest.allow_unlisted_services,
+        }
+    }
+
+    /*
+    A new token is written on every boot. An existing file keeps its owner and mode, so the
+    platform can create it for the service user only, a new file is only readable by Ripple.
+    */
+    fn write_token_file(path: &str) -> ExpectedCredential {
+        use std::os::unix::fs::OpenOptionsExt;
+        let token = Uuid::new_v4().simple().to_string();
+        let written = std::fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(path)
+            .and_then(|mut file| file.write_all(token.as_bytes()));
+        match written {
+            Ok(_) => ExpectedCredential::Token(token),
+            Err(e) => {
+                error!("Cannot write service token file {}: {}", path, e);
+                ExpectedCredential::Unavailable
+            }
+        }
+    }
+
+    /// Resolves the symbol of a service handshake, services which are not in the manifest or
+    /// have no credential configured are only accepted with `allow_unlisted_services`.
+    pub fn authenticate(
+        &self,
+        service_id: &str,
+        credential: Option<&str>,
+    ) -> Result<ExtnSymbol, ServiceAuthError> {
+        let Some(service) = self.services.get(service_id) else {
+            if !self.allow_unlisted_services {
+                return Err(ServiceAuthError::Unlisted);
+            }
+            return Ok(ExtnSymbol {
+                id: service_id.to_owned(),
+                ..Default::default()
+            });
+        };
+        match (&service.credential, credential) {
+            (ExpectedCredential::None, _) if self.allow_unlisted_services => {
+                warn!("No credential configured for service {}", service_id);
+                Ok(service.symbol.clone())
+            }
+            (ExpectedCredential::None, _) => Err(ServiceAuthError::MissingCredential),
+            (_, None) => Err(ServiceAuthError::MissingCredential),
+            (ExpectedCredential::Token(expected), Some(presented))
+                if credential_matches(expected, presented) =>
+            {
+                Ok(service.symbol.clone())
+            }
+            _ => Err(ServiceAuthError::InvalidCredential),
         }
     }
     // Ripple Main processing the inbound ServiceMessage received from a service.
@@ -562,9 +668,119 @@ async fn return_invalid_service_error_message(
     }
 }
 
+// compares in constant time for credentials of the same length
+fn credential_matches(expected: &str, presented: &str) -> bool {
+    expected.len() == presented.len()
+        && expected
+            .bytes()
+            .zip(presented.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ripple_sdk::api::manifest::extn_manifest::{
+        ExtnManifestEntry, SERVICE_SECRET_CONFIG, SERVICE_TOKEN_FILE_CONFIG,
+    };
+
+    fn manifest(symbols: Vec<ExtnSymbol>, allow_unlisted_services: bool) -> ExtnManifest {
+        ExtnManifest {
+            extns: vec![ExtnManifestEntry {
+                path: "libservices".to_owned(),
+                symbols,
+                resolution: None,
+            }],
+            allow_unlisted_services,
+            ..Default::default()
+        }
+    }
+
+    fn symbol(id: &str, config: Option<(&str, String)>) -> ExtnSymbol {
+        ExtnSymbol {
+            id: id.to_owned(),
+            config: config.map(|(k, v)| HashMap::from([(k.to_owned(), v)])),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_authenticate() {
+        let token_file = std::env::temp_dir()
+            .join(format!("ripple_service_token_{}", std::process::id()))
+            .display()
+            .to_string();
+        let state = ServiceControllerState::new(&manifest(
+            vec![
+                symbol("ripple:channel:gateway:open", None),
+                symbol(
+                    "ripple:channel:gateway:secret",
+                    Some((SERVICE_SECRET_CONFIG, "s3cret".to_owned())),
+                ),
+                symbol(
+                    "ripple:channel:gateway:token",
+                    Some((SERVICE_TOKEN_FILE_CONFIG, token_file.clone())),
+                ),
+            ],
+            false,
+        ));
+
+        assert_eq!(
+            state
+                .authenticate("ripple:channel:gateway:open", None)
+                .unwrap_err(),
+            ServiceAuthError::MissingCredential
+        );
+        assert!(state
+            .authenticate("ripple:channel:gateway:secret", Some("s3cret"))
+            .is_ok());
+        assert_eq!(
+            state
+                .authenticate("ripple:channel:gateway:secret", None)
+                .unwrap_err(),
+            ServiceAuthError::MissingCredential
+        );
+        assert_eq!(
+            state
+                .authenticate("ripple:channel:gateway:secret", Some("s3cre7"))
+                .unwrap_err(),
+            ServiceAuthError::InvalidCredential
+        );
+
+        let token = std::fs::read_to_string(&token_file).unwrap();
+        assert!(state
+            .authenticate("ripple:channel:gateway:token", Some(&token))
+            .is_ok());
+        assert_eq!(
+            state
+                .authenticate("ripple:channel:gateway:token", Some("s3cret"))
+                .unwrap_err(),
+            ServiceAuthError::InvalidCredential
+        );
+        let _ = std::fs::remove_file(token_file);
+
+        assert_eq!(
+            state
+                .authenticate("ripple:channel:gateway:unknown", None)
+                .unwrap_err(),
+            ServiceAuthError::Unlisted
+        );
+        let state = ServiceControllerState::new(&manifest(
+            vec![symbol("ripple:channel:gateway:open", None)],
+            true,
+        ));
+        assert!(state
+            .authenticate("ripple:channel:gateway:open", None)
+            .is_ok());
+        assert_eq!(
+            state
+                .authenticate("ripple:channel:gateway:unknown", None)
+                .unwrap()
+                .id,
+            "ripple:channel:gateway:unknown"
+        );
+    }
 
     #[tokio::test]
     async fn test_validate_sender() {
@@ -21,8 +21,8 @@ use ripple_sdk::{
             fb_metrics::{ErrorParams, InternalInitializeParams, SystemErrorParams},
             fb_telemetry::{
                 AppLoadStart, AppLoadStop, FireboltEvent, FireboltInteraction, InternalInitialize,
-                TelemetryAppError, TelemetryPayload, TelemetrySignIn, TelemetrySignOut,
-                TelemetrySystemError,
+                TelemetryAppError, TelemetryAppEviction, TelemetryPayload, TelemetrySignIn,
+                TelemetrySignOut, TelemetrySystemError,
             },
         },
         gateway::rpc_gateway_api::{ApiMessage, CallContext, RpcRequest},
@@ -78,6 +78,13 @@ impl TelemetryBuilder {
         }
     }
 
+    pub fn send_app_eviction(ps: &PlatformState, mut eviction: TelemetryAppEviction) {
+        eviction.ripple_session_id = ps.metrics.get_device_session_id();
+        if let Err(e) = Self::send_telemetry(ps, TelemetryPayload::AppEviction(eviction)) {
+            error!("send_telemetry={:?}", e)
+        }
+    }
+
     pub fn update_session_id_and_send_telemetry(
         ps: &PlatformState,
         mut t: TelemetryPayload,
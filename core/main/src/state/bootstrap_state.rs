@@ -18,7 +18,14 @@
 use std::time::Instant;
 
 use ripple_sdk::{
-    api::{apps::AppRequest, manifest::ripple_manifest_loader::RippleManifestLoader},
+    api::{
+        apps::AppRequest,
+        manifest::{
+            device_manifest::{AppLibraryEntry, DeviceManifest},
+            extn_manifest::ExtnManifest,
+            ripple_manifest_loader::RippleManifestLoader,
+        },
+    },
     framework::bootstrap::TransientChannel,
     log::{error, info, warn},
     tokio::sync::mpsc::{self, Receiver, Sender},
@@ -95,20 +102,11 @@ pub struct BootstrapState {
 
 impl BootstrapState {
     pub fn build() -> Result<BootstrapState, RippleError> {
-        let channels_state = ChannelsState::new();
-        let client = RippleClient::new(channels_state.clone());
         let Ok((extn_manifest, device_manifest)) = RippleManifestLoader::initialize() else {
             error!("Error initializing manifests");
             return Err(RippleError::BootstrapError);
         };
         let app_manifest_result = LoadAppLibraryStep::load_app_library();
-        let platform_state = PlatformState::new(
-            extn_manifest,
-            device_manifest,
-            client,
-            app_manifest_result,
-            ripple_version_from_etc(),
-        );
 
         fn ripple_version_from_etc() -> Option<String> {
             static RIPPLE_VER_FILE_DEFAULT: &str = "/etc/rippleversion.txt";
@@ -138,10 +136,29 @@ impl BootstrapState {
             warn!("error reading versions from {}", version_file_name,);
             None
         }
-        Ok(BootstrapState {
+        Ok(Self::from_manifests(
+            extn_manifest,
+            device_manifest,
+            app_manifest_result,
+            ripple_version_from_etc(),
+        ))
+    }
+
+    /// State for booting with the given manifests instead of the ones found in the environment.
+    pub fn from_manifests(
+        extn_manifest: ExtnManifest,
+        device_manifest: DeviceManifest,
+        app_library: Vec<AppLibraryEntry>,
+        version: Option<String>,
+    ) -> BootstrapState {
+        let channels_state = ChannelsState::new();
+        let client = RippleClient::new(channels_state.clone());
+        let platform_state =
+            PlatformState::new(extn_manifest, device_manifest, client, app_library, version);
+        BootstrapState {
             start_time: Instant::now(),
             platform_state,
             channels_state,
-        })
+        }
     }
 }
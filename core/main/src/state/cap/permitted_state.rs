@@ -18,8 +18,9 @@
 use crate::broker::broker_utils::BrokerUtils;
 use std::{
     collections::{HashMap, HashSet},
+    fs,
     path::Path,
-    sync::{Arc, RwLock},
+    sync::{Arc, Mutex, RwLock},
 };
 
 use ripple_sdk::{
@@ -33,60 +34,129 @@ use ripple_sdk::{
         },
         manifest::device_manifest::DeviceManifest,
     },
+    chrono::Utc,
     framework::{file_store::FileStore, RippleResponse},
-    log::{debug, error, info},
+    log::{debug, error, info, warn},
     tokio,
     utils::error::RippleError,
 };
+use serde::{Deserialize, Serialize};
 
 use crate::state::platform_state::PlatformState;
 
-type FireboltPermissionStore = Arc<RwLock<FileStore<HashMap<String, Vec<FireboltPermission>>>>>;
+/// Permissions of an app along with the time they were fetched, in epoch millis.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredPermissions {
+    permissions: Vec<FireboltPermission>,
+    fetched_at: i64,
+}
+
+type FireboltPermissionStore = Arc<RwLock<FileStore<HashMap<String, StoredPermissions>>>>;
 
 #[derive(Debug, Clone)]
 pub struct PermittedState {
     permitted: FireboltPermissionStore,
+    /// Apps whose stale permissions are being refreshed in the background
+    refreshing: Arc<Mutex<HashSet<String>>>,
 }
 
 impl PermittedState {
     pub fn new(manifest: DeviceManifest) -> PermittedState {
         let path = get_permissions_path(manifest.configuration.saved_dir);
-        let store = if let Ok(v) = FileStore::load(path.clone()) {
-            v
-        } else {
-            FileStore::new(path, HashMap::new())
-        };
-
         PermittedState {
-            permitted: Arc::new(RwLock::new(store)),
+            permitted: Arc::new(RwLock::new(Self::load_store(path))),
+            refreshing: Arc::new(Mutex::new(HashSet::new())),
         }
     }
 
-    fn ingest(&mut self, extend_perms: HashMap<String, Vec<FireboltPermission>>) {
+    fn load_store(path: String) -> FileStore<HashMap<String, StoredPermissions>> {
+        if let Ok(v) = FileStore::load(path.clone()) {
+            return v;
+        }
+        // stores written before the fetch time was kept are loaded as stale
+        if let Ok(v) = FileStore::<HashMap<String, Vec<FireboltPermission>>>::load(path.clone()) {
+            let value = v
+                .value
+                .into_iter()
+                .map(|(app_id, permissions)| {
+                    (
+                        app_id,
+                        StoredPermissions {
+                            permissions,
+                            fetched_at: 0,
+                        },
+                    )
+                })
+                .collect();
+            return FileStore::new(path, value);
+        }
+        FileStore::new(path, HashMap::new())
+    }
+
+    fn ingest(&mut self, extend_perms: HashMap<String, Vec<FireboltPermission>>, fetched_at: i64) {
         let mut perms = self.permitted.write().unwrap();
-        perms.value.extend(extend_perms);
+        perms
+            .value
+            .extend(extend_perms.into_iter().map(|(app_id, permissions)| {
+                (
+                    app_id,
+                    StoredPermissions {
+                        permissions,
+                        fetched_at,
+                    },
+                )
+            }));
         perms.sync();
     }
 
     #[cfg(test)]
     pub fn set_permissions(&mut self, permissions: HashMap<String, Vec<FireboltPermission>>) {
-        let mut perms = self.permitted.write().unwrap();
-        perms.value = permissions;
-        perms.sync();
+        {
+            self.permitted.write().unwrap().value.clear();
+        }
+        self.ingest(permissions, Utc::now().timestamp_millis());
     }
 
-    fn get_all_permissions(&self) -> HashMap<String, Vec<FireboltPermission>> {
-        self.permitted.read().unwrap().value.clone()
-    }
     fn has_cached_permissions(&self, app_id: &String) -> bool {
         // check if the app has permissions cached
         self.permitted.read().unwrap().value.contains_key(app_id)
     }
+
+    /// Whether the app permissions were fetched more than ttl seconds ago.
+    fn is_stale(&self, app_id: &str, ttl_secs: u64) -> bool {
+        match self.permitted.read().unwrap().value.get(app_id) {
+            Some(stored) => {
+                let age = Utc::now().timestamp_millis() - stored.fetched_at;
+                age < 0 || age as u64 > ttl_secs * 1000
+            }
+            None => true,
+        }
+    }
+
+    /// Marks a background refresh of the app permissions as started, false if one is
+    /// already in flight.
+    fn start_refresh(&self, app_id: &str) -> bool {
+        self.refreshing.lock().unwrap().insert(app_id.to_owned())
+    }
+
+    fn end_refresh(&self, app_id: &str) {
+        self.refreshing.lock().unwrap().remove(app_id);
+    }
+
+    fn get_fetched_at(&self, app_id: &str) -> Option<i64> {
+        self.permitted
+            .read()
+            .unwrap()
+            .value
+            .get(app_id)
+            .map(|stored| stored.fetched_at)
+    }
+
     pub fn check_cap_role(&self, app_id: &str, role_info: &RoleInfo) -> Result<bool, RippleError> {
         let role = role_info
             .role
             .unwrap_or(ripple_sdk::api::firebolt::fb_capabilities::CapabilityRole::Use);
-        if let Some(perms) = self.get_all_permissions().get(app_id) {
+        if let Some(perms) = self.get_app_permissions(app_id) {
             for perm in perms {
                 if perm.cap.as_str() == role_info.capability.as_str() && perm.role == role {
                     return Ok(true);
@@ -111,10 +181,12 @@ impl PermittedState {
     }
 
     pub fn get_app_permissions(&self, app_id: &str) -> Option<Vec<FireboltPermission>> {
-        if let Some(perms) = self.get_all_permissions().get(app_id) {
-            return Some(perms.clone());
-        }
-        None
+        self.permitted
+            .read()
+            .unwrap()
+            .value
+            .get(app_id)
+            .map(|stored| stored.permissions.clone())
     }
 }
 
@@ -123,6 +195,20 @@ fn get_permissions_path(saved_dir: String) -> String {
     dir_path.into_os_string().into_string().unwrap()
 }
 
+/// Reads the device permissions file, a map of app id to the list of permissions of the app.
+fn read_device_permissions(
+    path: &str,
+) -> Result<HashMap<String, Vec<FireboltPermission>>, RippleError> {
+    let contents = fs::read_to_string(path).map_err(|e| {
+        error!("Failed to read device permissions {}: {:?}", path, e);
+        RippleError::NotAvailable
+    })?;
+    serde_json::from_str(&contents).map_err(|e| {
+        error!("Invalid device permissions {}: {:?}", path, e);
+        RippleError::ParseError
+    })
+}
+
 pub struct PermissionHandler;
 
 impl PermissionHandler {
@@ -148,15 +234,41 @@ impl PermissionHandler {
         }
 
         if state.get_device_manifest().get_features().cloud_permissions {
+            let permitted_state = &state.cap_state.permitted_state;
+            let cached = permitted_state.get_app_permissions(app_id);
             if allow_cached {
-                if let Some(permissions) =
-                    state.cap_state.permitted_state.get_app_permissions(app_id)
-                {
-                    let mut permissions_copy = permissions;
-                    return Self::process_permissions(state, app_id, &mut permissions_copy);
+                if let Some(mut permissions) = cached.clone() {
+                    let ttl = state.get_device_manifest().get_permissions_cache_ttl();
+                    if permitted_state.is_stale(app_id, ttl)
+                        && permitted_state.start_refresh(app_id)
+                    {
+                        // serve the stored permissions and refresh them in the background
+                        let state_c = state.clone();
+                        let app_id_c = app_id.to_owned();
+                        tokio::spawn(async move {
+                            if Self::cloud_fetch_and_store(&state_c, &app_id_c)
+                                .await
+                                .is_err()
+                            {
+                                warn!("Failed to refresh stale permissions for {}", app_id_c);
+                            }
+                            state_c.cap_state.permitted_state.end_refresh(&app_id_c);
+                        });
+                    }
+                    let fetched_at = permitted_state.get_fetched_at(app_id).unwrap_or_default();
+                    return Self::process_permissions(state, app_id, &mut permissions, fetched_at);
                 }
             }
-            Self::cloud_fetch_and_store(state, app_id).await
+            match Self::cloud_fetch_and_store(state, app_id).await {
+                Err(e) if cached.is_some() => {
+                    warn!(
+                        "fetch_and_store: cloud permissions not available for {}, using stored permissions: {:?}",
+                        app_id, e
+                    );
+                    Ok(())
+                }
+                result => result,
+            }
         } else {
             // Never use cache, always fetch from device.
             Self::device_fetch_and_store(state, app_id).await
@@ -185,7 +297,12 @@ impl PermissionHandler {
                     Ok(permissions) => {
                         debug!("Response: {:?}", permissions);
                         let mut permission_response = permissions;
-                        Self::process_permissions(state, app_id, &mut permission_response)
+                        Self::process_permissions(
+                            state,
+                            app_id,
+                            &mut permission_response,
+                            Utc::now().timestamp_millis(),
+                        )
                     }
                     Err(_) => {
                         error!("cloud_fetch_and_store : Failed to get permission distributor.getPermissions");
@@ -205,15 +322,38 @@ impl PermissionHandler {
         }
     }
 
-    pub async fn device_fetch_and_store(_state: &PlatformState, _app_id: &str) -> RippleResponse {
-        error!("device_fetch_and_store: Not supported");
-        Err(RippleError::NotAvailable)
+    pub async fn device_fetch_and_store(state: &PlatformState, app_id: &str) -> RippleResponse {
+        let Some(path) = state.get_device_manifest().get_device_permissions_path() else {
+            error!("device_fetch_and_store: No device permissions configured");
+            return Err(RippleError::NotAvailable);
+        };
+        let mut device_permissions = read_device_permissions(&path)?;
+        let app_id_alias = Self::get_distributor_alias_for_app_id(state, app_id);
+        let permissions = device_permissions
+            .remove(app_id)
+            .or_else(|| device_permissions.remove(&app_id_alias));
+        match permissions {
+            Some(mut permissions) => Self::process_permissions(
+                state,
+                app_id,
+                &mut permissions,
+                Utc::now().timestamp_millis(),
+            ),
+            None => {
+                error!(
+                    "device_fetch_and_store: No permissions for {} in {}",
+                    app_id, path
+                );
+                Err(RippleError::NotAvailable)
+            }
+        }
     }
 
     fn process_permissions(
         state: &PlatformState,
         app_id: &str,
         permissions: &mut Vec<FireboltPermission>,
+        fetched_at: i64,
     ) -> RippleResponse {
         info!("Permissions fetched for {}", app_id);
         let dep_lookup = &state.get_device_manifest().capabilities.dependencies;
@@ -241,7 +381,7 @@ impl PermissionHandler {
             .collect::<HashMap<_, _>>();
 
         let mut permitted_state = state.cap_state.permitted_state.clone();
-        permitted_state.ingest(map.clone());
+        permitted_state.ingest(map.clone(), fetched_at);
         info!("Permissions: {:?}", map);
 
         Ok(())
@@ -407,3 +547,107 @@ impl PermissionHandler {
         (use_granted, manage_granted, provide_granted)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        service::extn::ripple_client::RippleClient, state::bootstrap_state::ChannelsState,
+    };
+    use ripple_sdk::{api::manifest::extn_manifest::ExtnManifest, serde_json::json, tokio};
+
+    fn temp_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("ripple_{}_{}", name, std::process::id()))
+            .into_os_string()
+            .into_string()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_load_store_without_fetch_time() {
+        let path = temp_path("legacy_perms");
+        fs::write(
+            &path,
+            json!({"app1": ["xrn:firebolt:capability:device:name"]}).to_string(),
+        )
+        .unwrap();
+        let state = PermittedState {
+            permitted: Arc::new(RwLock::new(PermittedState::load_store(path.clone()))),
+            refreshing: Arc::new(Mutex::new(HashSet::new())),
+        };
+        assert_eq!(state.get_app_permissions("app1").unwrap().len(), 1);
+        assert!(state.is_stale("app1", 60));
+
+        let mut state_c = state.clone();
+        state_c.ingest(
+            HashMap::from([(
+                "app1".to_owned(),
+                state.get_app_permissions("app1").unwrap(),
+            )]),
+            Utc::now().timestamp_millis(),
+        );
+        assert!(!state.is_stale("app1", 60));
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_single_refresh_in_flight() {
+        let state = PermittedState {
+            permitted: Arc::new(RwLock::new(PermittedState::load_store(temp_path(
+                "refresh_perms",
+            )))),
+            refreshing: Arc::new(Mutex::new(HashSet::new())),
+        };
+        assert!(state.start_refresh("app1"));
+        assert!(!state.clone().start_refresh("app1"));
+        assert!(state.start_refresh("app2"));
+        state.end_refresh("app1");
+        assert!(state.start_refresh("app1"));
+    }
+
+    #[tokio::test]
+    async fn test_device_fetch_and_store() {
+        let path = temp_path("device_perms");
+        fs::write(
+            &path,
+            json!({"app1": ["xrn:firebolt:capability:device:name", "xrn:firebolt:capability:device:model"]})
+                .to_string(),
+        )
+        .unwrap();
+        let (_, mut manifest) = DeviceManifest::load_from_content(
+            include_str!("../../../../../examples/manifest/device-manifest-example.json")
+                .to_string(),
+        )
+        .unwrap();
+        manifest.configuration.features.cloud_permissions = false;
+        manifest.configuration.device_permissions_path = Some(path.clone());
+        let saved_dir = temp_path("device_perms_saved");
+        manifest.configuration.saved_dir = saved_dir.clone();
+        let state = PlatformState::new(
+            ExtnManifest::default(),
+            manifest,
+            RippleClient::new(ChannelsState::new()),
+            vec![],
+            None,
+        );
+
+        assert!(PermissionHandler::fetch_and_store(&state, "app1", false)
+            .await
+            .is_ok());
+        assert_eq!(
+            state
+                .cap_state
+                .permitted_state
+                .get_app_permissions("app1")
+                .unwrap()
+                .len(),
+            2
+        );
+        assert!(PermissionHandler::fetch_and_store(&state, "app2", false)
+            .await
+            .is_err());
+        let _ = fs::remove_file(path);
+        let _ = fs::remove_dir_all(saved_dir);
+    }
+}
@@ -19,6 +19,7 @@ pub mod bootstrap_state;
 pub mod openrpc_state;
 pub mod ops_metrics_state;
 pub mod platform_state;
+pub mod rate_limit_state;
 pub mod ripple_cache;
 pub mod session_state;
 pub mod cap {
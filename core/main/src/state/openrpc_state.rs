@@ -113,9 +113,19 @@ use ripple_sdk::{
 use serde_json::Value;
 use std::{
     collections::HashMap,
-    sync::{Arc, RwLock},
+    sync::{Arc, OnceLock, RwLock},
 };
 
+/// Firebolt open rpc content used instead of the file, see [set_firebolt_open_rpc]
+static FIREBOLT_OPEN_RPC: OnceLock<String> = OnceLock::new();
+
+/// Makes every [OpenRpcState] created afterwards use the given Firebolt open rpc instead of
+/// loading it from file, e.g. for a gateway booted in a test process. Only the first call takes
+/// effect, false is returned for the others.
+pub fn set_firebolt_open_rpc(content: String) -> bool {
+    FIREBOLT_OPEN_RPC.set(content).is_ok()
+}
+
 #[derive(Debug, Clone)]
 pub enum ApiSurface {
     Firebolt,
@@ -197,7 +207,10 @@ impl OpenRpcState {
         extn_sdks: Vec<String>,
         provider_registrations: Vec<String>,
     ) -> OpenRpcState {
-        let open_rpc_path = load_firebolt_open_rpc_path().expect("Need valid open-rpc file");
+        let open_rpc_path = match FIREBOLT_OPEN_RPC.get() {
+            Some(content) => content.clone(),
+            None => load_firebolt_open_rpc_path().expect("Need valid open-rpc file"),
+        };
         let version_manifest: FireboltVersionManifest = serde_json::from_str(&open_rpc_path)
             .expect("Failed parsing FireboltVersionManifest from open RPC file");
         let firebolt_open_rpc: FireboltOpenRpc = version_manifest.clone().into();
@@ -45,11 +45,11 @@ use std::{collections::HashMap, sync::Arc};
 use crate::{
     broker::{endpoint_broker::EndpointBrokerState, rules::rules_engine::RuleEngine},
     firebolt::rpc_router::RouterState,
+    processor::storage::storage_backend::{get_storage_backend, StorageBackend},
     service::{
         apps::{
-            app_events::AppEventsState,
-            delegated_launcher_handler::{AppManagerState, AppManagerState2_0},
-            provider_broker::ProviderBrokerState,
+            app_events::AppEventsState, delegated_launcher_handler::AppManagerState,
+            lifecycle2_manager::AppManagerState2_0, provider_broker::ProviderBrokerState,
         },
         extn::ripple_client::RippleClient,
         ripple_service::service_controller_state::ServiceControllerState,
@@ -58,7 +58,7 @@ use crate::{
 
 use super::{
     cap::cap_state::CapState, openrpc_state::OpenRpcState, ops_metrics_state::OpMetricState,
-    ripple_cache::RippleCache, session_state::SessionState,
+    rate_limit_state::RateLimitState, ripple_cache::RippleCache, session_state::SessionState,
 };
 
 /// Platform state encapsulates the internal state of the Ripple Main application.
@@ -118,6 +118,8 @@ pub struct PlatformState {
     pub lifecycle2_app_state: AppManagerState2_0,
     pub service_controller_state: ServiceControllerState,
     pub policy_state: PolicyState,
+    pub rate_limit_state: RateLimitState,
+    storage_backend: Arc<dyn StorageBackend>,
 }
 
 impl PlatformState {
@@ -134,13 +136,21 @@ impl PlatformState {
         let extn_sdks = extn_manifest.extn_sdks.clone();
         let provider_registations = extn_manifest.provider_registrations.clone();
         let metrics_state = OpMetricState::default();
+        let app_library_state = AppLibraryState::new(app_library);
+        let rate_limit_state = RateLimitState::new(manifest.get_rate_limits(), &app_library_state);
+        let service_controller_state = ServiceControllerState::new(&extn_manifest);
+        let storage_backend = get_storage_backend(
+            manifest.get_storage_backend(),
+            client.clone(),
+            &manifest.configuration.saved_dir,
+        );
         Self {
             extn_manifest: Arc::new(extn_manifest),
             cap_state: CapState::new(manifest.clone()),
             session_state: SessionState::default(),
             device_manifest: Arc::new(manifest.clone()),
             ripple_client: client.clone(),
-            app_library_state: AppLibraryState::new(app_library),
+            app_library_state,
             app_events_state: AppEventsState::default(),
             provider_broker_state: ProviderBrokerState::default(),
             app_manager_state: AppManagerState::new(&manifest.configuration.saved_dir.clone()),
@@ -157,8 +167,10 @@ impl PlatformState {
                 client,
             ),
             lifecycle2_app_state: AppManagerState2_0::new(),
-            service_controller_state: ServiceControllerState::new(),
+            service_controller_state,
             policy_state: PolicyState::default(),
+            rate_limit_state,
+            storage_backend,
         }
     }
 
@@ -207,6 +219,10 @@ impl PlatformState {
         self.ripple_client.clone()
     }
 
+    pub fn get_storage_backend(&self) -> Arc<dyn StorageBackend> {
+        self.storage_backend.clone()
+    }
+
     pub async fn respond(&self, msg: ExtnMessage) -> Result<(), RippleError> {
         self.get_client().respond(msg).await
     }
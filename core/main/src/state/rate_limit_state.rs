@@ -0,0 +1,225 @@
+// Copyright 2025 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use ripple_sdk::api::manifest::{
+    app_library::AppLibraryState,
+    device_manifest::{RateLimit, RateLimits},
+};
+
+#[derive(Debug, PartialEq)]
+pub enum RateLimitCheck {
+    Allowed,
+    /// `first` is set on the first rejection since the app was last allowed
+    Throttled {
+        first: bool,
+    },
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    updated: Instant,
+    throttled: bool,
+}
+
+impl TokenBucket {
+    fn new(limit: &RateLimit, now: Instant) -> Self {
+        TokenBucket {
+            tokens: limit.burst as f64,
+            updated: now,
+            throttled: false,
+        }
+    }
+
+    fn refill(&mut self, limit: &RateLimit, now: Instant) {
+        let elapsed = now.duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * limit.rate as f64).min(limit.burst as f64);
+        self.updated = now;
+    }
+}
+
+/*
+Token buckets per app and per app and method. A request takes a token from the app bucket and
+from the method bucket if the method has a limit, it is rejected if either of them is empty.
+*/
+#[derive(Debug, Clone, Default)]
+pub struct RateLimitState {
+    defaults: RateLimits,
+    app_limits: HashMap<String, RateLimits>,
+    buckets: Arc<Mutex<HashMap<String, TokenBucket>>>,
+}
+
+impl RateLimitState {
+    pub fn new(defaults: RateLimits, app_library: &AppLibraryState) -> Self {
+        // method names are matched case insensitive
+        let defaults = Self::lowercase(&defaults);
+        let app_limits = app_library
+            .default_apps
+            .iter()
+            .filter_map(|entry| {
+                let overrides = Self::lowercase(entry.rate_limits.as_ref()?);
+                Some((entry.app_id.clone(), defaults.with_overrides(&overrides)))
+            })
+            .collect();
+        RateLimitState {
+            defaults,
+            app_limits,
+            buckets: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    fn lowercase(limits: &RateLimits) -> RateLimits {
+        RateLimits {
+            app: limits.app.clone(),
+            methods: limits
+                .methods
+                .iter()
+                .map(|(method, limit)| (method.to_lowercase(), limit.clone()))
+                .collect(),
+        }
+    }
+
+    pub fn check(&self, app_id: &str, method: &str) -> RateLimitCheck {
+        let limits = self.app_limits.get(app_id).unwrap_or(&self.defaults);
+        if limits.is_empty() {
+            return RateLimitCheck::Allowed;
+        }
+        let method = method.to_lowercase();
+        let mut checks: Vec<(String, &RateLimit)> = Vec::new();
+        if let Some(limit) = &limits.app {
+            checks.push((app_id.to_owned(), limit));
+        }
+        if let Some(limit) = limits.methods.get(&method) {
+            checks.push((format!("{}:{}", app_id, method), limit));
+        }
+
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+        let mut allowed = true;
+        for (key, limit) in checks.iter() {
+            let bucket = buckets
+                .entry(key.clone())
+                .or_insert_with(|| TokenBucket::new(limit, now));
+            bucket.refill(limit, now);
+            allowed &= bucket.tokens >= 1.0;
+        }
+
+        let mut first = false;
+        for (key, _) in checks.iter() {
+            if let Some(bucket) = buckets.get_mut(key) {
+                if allowed {
+                    bucket.tokens -= 1.0;
+                } else if bucket.tokens < 1.0 && !bucket.throttled {
+                    first = true;
+                }
+                bucket.throttled = !allowed && bucket.tokens < 1.0;
+            }
+        }
+        if allowed {
+            RateLimitCheck::Allowed
+        } else {
+            RateLimitCheck::Throttled { first }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ripple_sdk::api::manifest::{
+        apps::AppManifest,
+        device_manifest::{AppLibraryEntry, AppManifestLoad, BootState},
+    };
+
+    fn limit(rate: u32, burst: u32) -> RateLimit {
+        RateLimit { rate, burst }
+    }
+
+    #[test]
+    fn test_app_limit() {
+        let defaults = RateLimits {
+            app: Some(limit(0, 2)),
+            methods: HashMap::new(),
+        };
+        let state = RateLimitState::new(defaults, &AppLibraryState::default());
+        assert_eq!(state.check("app1", "device.name"), RateLimitCheck::Allowed);
+        assert_eq!(state.check("app1", "device.model"), RateLimitCheck::Allowed);
+        assert_eq!(
+            state.check("app1", "device.name"),
+            RateLimitCheck::Throttled { first: true }
+        );
+        assert_eq!(
+            state.check("app1", "device.name"),
+            RateLimitCheck::Throttled { first: false }
+        );
+        assert_eq!(state.check("app2", "device.name"), RateLimitCheck::Allowed);
+    }
+
+    #[test]
+    fn test_method_limit_and_app_override() {
+        let defaults = RateLimits {
+            app: None,
+            methods: HashMap::from([("Device.name".to_owned(), limit(0, 1))]),
+        };
+        let app_library = AppLibraryState::new(vec![AppLibraryEntry {
+            app_id: "app2".to_owned(),
+            manifest: AppManifestLoad::Embedded(AppManifest::default()),
+            boot_state: BootState::Unloaded,
+            rate_limits: Some(RateLimits {
+                app: None,
+                methods: HashMap::from([("device.name".to_owned(), limit(0, 3))]),
+            }),
+            provider_priority: None,
+        }]);
+        let state = RateLimitState::new(defaults, &app_library);
+
+        assert_eq!(state.check("app1", "device.name"), RateLimitCheck::Allowed);
+        assert_eq!(
+            state.check("app1", "device.name"),
+            RateLimitCheck::Throttled { first: true }
+        );
+        assert_eq!(state.check("app1", "device.model"), RateLimitCheck::Allowed);
+
+        for _ in 0..3 {
+            assert_eq!(state.check("app2", "device.name"), RateLimitCheck::Allowed);
+        }
+        assert_eq!(
+            state.check("app2", "device.name"),
+            RateLimitCheck::Throttled { first: true }
+        );
+    }
+
+    #[test]
+    fn test_refill() {
+        let state = RateLimitState::new(
+            RateLimits {
+                app: Some(limit(1000, 1)),
+                methods: HashMap::new(),
+            },
+            &AppLibraryState::default(),
+        );
+        assert_eq!(state.check("app1", "device.name"), RateLimitCheck::Allowed);
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        assert_eq!(state.check("app1", "device.name"), RateLimitCheck::Allowed);
+    }
+}
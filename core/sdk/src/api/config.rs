@@ -192,6 +192,8 @@ mod tests {
                         "https://example.com/app1/manifest".to_string(),
                     ),
                     boot_state: BootState::Inactive,
+                    rate_limits: None,
+                    provider_priority: None,
                 }],
                 providers: HashMap::new(),
             },
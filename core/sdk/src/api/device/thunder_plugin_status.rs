@@ -0,0 +1,717 @@
+// Copyright 2025 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    sync::{Arc, RwLock},
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::{
+    api::gateway::rpc_gateway_api::JsonRpcApiResponse, framework::RippleResponse,
+    utils::error::RippleError,
+};
+
+// defautl timeout for plugin activation in seconds
+const DEFAULT_PLUGIN_ACTIVATION_TIMEOUT: i64 = 8;
+
+// As per thunder 4_4 documentation, the statechange event is published under the method "client.events.1.statechange"
+// But it didn't work, most probably a documentation issue.
+// const STATE_CHANGE_EVENT_METHOD: &str = "client.events.1.statechange";
+
+const STATE_CHANGE_EVENT_METHOD: &str = "thunder.Broker.Controller.events.statechange";
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct Status {
+    pub callsign: String,
+    pub state: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ThunderError {
+    pub code: i32,
+    pub message: String,
+}
+
+impl ThunderError {
+    pub fn get_state(&self) -> State {
+        match self.message.as_str() {
+            "ERROR_INPROGRESS" | "ERROR_PENDING_CONDITIONS" => State::InProgress,
+            "ERROR_UNKNOWN_KEY" => State::Missing,
+            _ => State::Unknown,
+        }
+    }
+}
+
+impl Status {
+    pub fn to_state(&self) -> State {
+        match self.state.as_str() {
+            "activated" | "resumed" | "suspended" => State::Activated,
+            "deactivated" => State::Deactivated,
+            "deactivation" => State::Deactivation,
+            "activation" | "precondition" => State::Activation,
+            _ => State::Unavailable,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StateChangeEvent {
+    pub callsign: String,
+    pub state: State,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub enum State {
+    Activated,
+    Activation,
+    Deactivated,
+    Deactivation,
+    Unavailable,
+    Precondition,
+    Suspended,
+    Resumed,
+    Missing,
+    Error,
+    InProgress,
+    Unknown,
+}
+
+impl State {
+    pub fn is_activated(&self) -> bool {
+        matches!(self, State::Activated)
+    }
+    pub fn is_activating(&self) -> bool {
+        matches!(self, State::Activation)
+    }
+    pub fn is_missing(&self) -> bool {
+        matches!(self, State::Missing)
+    }
+    pub fn is_unavailable(&self) -> bool {
+        matches!(self, State::Unavailable | State::Unknown | State::Missing)
+    }
+}
+
+/// Forwards a request which waited for its plugin to be activated.
+#[async_trait]
+pub trait PendingRequestSender<R>: Send + Sync {
+    async fn send_pending(&self, request: R) -> RippleResponse;
+}
+
+/// Fails a request whose plugin could not be activated.
+#[async_trait]
+pub trait PendingRequestCallback<R>: Send + Sync {
+    async fn send_pending_error(&self, request: R, error: RippleError);
+}
+
+#[derive(Debug, Clone)]
+pub struct ThunderPluginState<R> {
+    pub state: State,
+    pub activation_timestamp: DateTime<Utc>,
+    pub pending_requests: Vec<R>,
+}
+
+/*
+Tracks the lifecycle of the Thunder plugins behind a connection through the Controller plugin.
+Requests for a plugin which is not known to be activated are held here until the status or
+activation response, or the statechange event, says whether they can be sent. The type of the held
+requests is up to the connection, which also provides the ids of the Controller requests so they
+do not collide with its own.
+*/
+#[derive(Debug, Clone)]
+pub struct PluginStatusManager<R> {
+    pub status: Arc<RwLock<HashMap<String, ThunderPluginState<R>>>>,
+    pub inprogress_plugins_request: Arc<RwLock<HashMap<u64, String>>>,
+    plugins: Arc<Vec<String>>,
+    next_id: fn() -> u64,
+}
+
+impl<R> PluginStatusManager<R>
+where
+    R: Clone + Debug + Send + Sync,
+{
+    /// `plugins` are the callsigns updated from a status response for all plugins.
+    pub fn new(plugins: Vec<String>, next_id: fn() -> u64) -> Self {
+        Self {
+            status: Arc::new(RwLock::new(HashMap::new())),
+            inprogress_plugins_request: Arc::new(RwLock::new(HashMap::new())),
+            plugins: Arc::new(plugins),
+            next_id,
+        }
+    }
+
+    fn get_controller_call_sign() -> String {
+        "Controller.1.".to_string()
+    }
+
+    pub fn update_status(&self, plugin_name: String, state: State) {
+        info!(
+            "Updating the status of the plugin: {:?} to state: {:?}",
+            plugin_name, state
+        );
+        let mut status = self.status.write().unwrap();
+        // get the current plugin state from hashmap and update the State
+        if let Some(plugin_state) = status.get_mut(&plugin_name) {
+            plugin_state.state = state;
+        } else {
+            // if the plugin is not present in the hashmap, add it
+            status.insert(
+                plugin_name,
+                ThunderPluginState {
+                    state,
+                    activation_timestamp: Utc::now(),
+                    pending_requests: Vec::new(),
+                },
+            );
+        }
+    }
+
+    pub fn add_request_to_pending_list(&self, plugin_name: String, request: R) {
+        let mut status = self.status.write().unwrap();
+        if let Some(plugin_state) = status.get_mut(&plugin_name) {
+            plugin_state.pending_requests.push(request);
+        } else {
+            status.insert(
+                plugin_name.clone(),
+                ThunderPluginState {
+                    state: State::Unknown,
+                    activation_timestamp: Utc::now(),
+                    pending_requests: vec![request],
+                },
+            );
+        }
+        // update the time stamp
+        if let Some(plugin_state) = status.get_mut(&plugin_name) {
+            plugin_state.activation_timestamp = Utc::now();
+        }
+    }
+
+    // clear all pending requests for the given plugin and return the list of requests to the caller
+    // Also return a flag to indicate if activation time has expired.
+    pub fn retrieve_pending_requests(&self, plugin_name: String) -> (Vec<R>, bool) {
+        let mut status = self.status.write().unwrap();
+        if let Some(plugin_state) = status.get_mut(&plugin_name) {
+            let pending_requests = plugin_state.pending_requests.clone();
+            plugin_state.pending_requests.clear();
+            // check if the activation time has expired.
+            let now = Utc::now();
+            if now - plugin_state.activation_timestamp
+                > Duration::seconds(DEFAULT_PLUGIN_ACTIVATION_TIMEOUT)
+            {
+                return (pending_requests, true);
+            } else {
+                return (pending_requests, false);
+            }
+        }
+        (Vec::new(), false)
+    }
+
+    pub fn get_all_pending_requests(&self, plugin_name: String) -> Vec<R> {
+        let status = self.status.read().unwrap();
+        if let Some(plugin_state) = status.get(&plugin_name) {
+            plugin_state.pending_requests.clone()
+        } else {
+            Vec::new()
+        }
+    }
+
+    pub fn clear_all_pending_requests(&self, plugin_name: String) {
+        let mut status = self.status.write().unwrap();
+        if let Some(plugin_state) = status.get_mut(&plugin_name) {
+            plugin_state.pending_requests.clear();
+        }
+    }
+
+    pub fn get_status(&self, plugin_name: String) -> Option<ThunderPluginState<R>> {
+        let status = self.status.read().unwrap();
+        status.get(&plugin_name).cloned()
+    }
+
+    pub fn generate_plugin_activation_request(&self, plugin_name: String) -> String {
+        let id = (self.next_id)();
+        let controller_call_sign = Self::get_controller_call_sign();
+
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": format!("{}activate", controller_call_sign),
+            "params": json!({
+                "callsign": plugin_name,
+            })
+        })
+        .to_string();
+        // Add this request to the inprogress_plugins_request
+        self.add_thunder_request_to_inprogress_list(id, request.clone());
+        request
+    }
+
+    pub fn generate_plugin_status_request(&self, plugin_name: Option<String>) -> String {
+        let id = (self.next_id)();
+        let controller_call_sign = Self::get_controller_call_sign();
+        let mut method = format!("{}status", controller_call_sign);
+        if let Some(p) = plugin_name {
+            method = format!("{}status@{}", controller_call_sign, p);
+        }
+
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": method,
+        })
+        .to_string();
+        // Add this request to the inprogress_plugins_request
+        self.add_thunder_request_to_inprogress_list(id, request.clone());
+        request
+    }
+
+    pub fn generate_state_change_subscribe_request(&self) -> String {
+        let id = (self.next_id)();
+        let controller_call_sign = Self::get_controller_call_sign();
+
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": format!("{}register", controller_call_sign),
+            "params": json!({
+                "event": "statechange",
+                "id": "thunder.Broker.Controller.events"
+            })
+        })
+        .to_string();
+        // Add this request to the inprogress_plugins_request
+        self.add_thunder_request_to_inprogress_list(id, request.clone());
+        request
+    }
+
+    fn add_thunder_request_to_inprogress_list(&self, id: u64, request: String) {
+        let mut inprogress_plugins_request = self.inprogress_plugins_request.write().unwrap();
+        inprogress_plugins_request.insert(id, request);
+    }
+
+    // send the pending requests of an activated plugin, unless they waited for too long
+    async fn send_pending_requests<S, C>(&self, sender: &S, callback: &C, plugin_name: String)
+    where
+        S: PendingRequestSender<R>,
+        C: PendingRequestCallback<R>,
+    {
+        let (pending_requests, expired) = self.retrieve_pending_requests(plugin_name);
+        for pending_request in pending_requests {
+            if expired {
+                error!("Expired request: {:?}", pending_request);
+                callback
+                    .send_pending_error(pending_request, RippleError::ServiceError)
+                    .await;
+            } else {
+                let _ = sender.send_pending(pending_request).await;
+            }
+        }
+    }
+
+    pub async fn is_controller_response<S, C>(&self, sender: S, callback: C, result: &[u8]) -> bool
+    where
+        S: PendingRequestSender<R>,
+        C: PendingRequestCallback<R>,
+    {
+        let data = match serde_json::from_slice::<JsonRpcApiResponse>(result) {
+            Ok(data) => data,
+            Err(_) => return false,
+        };
+
+        if let Some(method) = data.method {
+            info!("is_controller_response Method: {:?}", method);
+            if method == STATE_CHANGE_EVENT_METHOD {
+                // intercept the statechange event and update plugin status.
+                let params = match data.params {
+                    Some(params) => params,
+                    None => return false,
+                };
+
+                let event: StateChangeEvent = match serde_json::from_value(params) {
+                    Ok(event) => event,
+                    Err(_) => return false,
+                };
+
+                self.update_status(event.callsign.clone(), event.state.clone());
+
+                if event.state.is_activated() {
+                    // get the pending requests and process.
+                    self.send_pending_requests(&sender, &callback, event.callsign)
+                        .await;
+                }
+
+                return true;
+            }
+        }
+
+        if let Some(id) = data.id {
+            let inprogress_plugins_request = self.inprogress_plugins_request.read().unwrap();
+            return inprogress_plugins_request.contains_key(&id);
+        }
+
+        false
+    }
+
+    async fn on_activate_response<S, C>(
+        &self,
+        sender: &S,
+        callback: &C,
+        data: &JsonRpcApiResponse,
+        request: &str,
+    ) where
+        S: PendingRequestSender<R>,
+        C: PendingRequestCallback<R>,
+    {
+        let result = match &data.result {
+            Some(result) => result,
+            None => return,
+        };
+
+        let callsign = match request.split("callsign\":").last() {
+            Some(callsign) => callsign.trim_matches(|c| c == '"' || c == '}'),
+            None => return,
+        };
+
+        if result.is_null() {
+            self.update_status(callsign.to_string(), State::Activated);
+            self.send_pending_requests(sender, callback, callsign.to_string())
+                .await;
+        } else if let Some(_e) = &data.error {
+            self.on_thunder_error_response(callback, data, &callsign.to_string())
+                .await;
+        }
+    }
+
+    async fn on_status_response<S, C>(
+        &self,
+        sender: &S,
+        callback: &C,
+        data: &JsonRpcApiResponse,
+        request: &str,
+    ) where
+        S: PendingRequestSender<R>,
+        C: PendingRequestCallback<R>,
+    {
+        let callsigns: Vec<String> = match request.split_once('@') {
+            Some((_, callsign)) => {
+                vec![callsign.trim_matches(|c| c == '"' || c == '}').to_string()]
+            }
+            None => self.plugins.to_vec(),
+        };
+
+        let result = match &data.result {
+            Some(result) => result,
+            None => {
+                self.on_thunder_error_response(callback, data, &callsigns[0])
+                    .await;
+                return;
+            }
+        };
+
+        let status_res: Vec<Status> = match serde_json::from_value(result.clone()) {
+            Ok(status_res) => status_res,
+            Err(_) => {
+                self.on_thunder_error_response(callback, data, &callsigns[0])
+                    .await;
+                return;
+            }
+        };
+
+        //filtering the status_res by matching status_res.callsign with callsigns
+        let status_res: Vec<Status> = status_res
+            .into_iter()
+            .filter(|status| callsigns.contains(&status.callsign))
+            .collect();
+
+        for status in status_res {
+            self.update_status(status.callsign.to_string(), status.to_state());
+            self.send_pending_requests(sender, callback, status.callsign)
+                .await;
+        }
+    }
+
+    async fn on_thunder_error_response<C>(
+        &self,
+        callback: &C,
+        data: &JsonRpcApiResponse,
+        plugin_name: &String,
+    ) where
+        C: PendingRequestCallback<R>,
+    {
+        let error = match &data.error {
+            Some(error) => error,
+            None => return,
+        };
+
+        error!(
+            "Error Received from Thunder on getting the status of the plugin: {:?}",
+            error
+        );
+
+        let thunder_error: ThunderError = match serde_json::from_value(error.clone()) {
+            Ok(error) => error,
+            Err(_) => return,
+        };
+
+        let state = thunder_error.get_state();
+        self.update_status(plugin_name.to_string(), state.clone());
+
+        if state.is_unavailable() {
+            let (pending_requests, _) = self.retrieve_pending_requests(plugin_name.to_string());
+
+            for pending_request in pending_requests {
+                callback
+                    .send_pending_error(pending_request, RippleError::ServiceError)
+                    .await;
+            }
+        }
+    }
+
+    pub fn get_from_inprogress_plugins_request_list(&self, id: u64) -> Option<String> {
+        let inprogress_plugins_request = self.inprogress_plugins_request.read().unwrap();
+        inprogress_plugins_request.get(&id).cloned()
+    }
+
+    pub async fn handle_controller_response<S, C>(&self, sender: S, callback: C, result: &[u8])
+    where
+        S: PendingRequestSender<R>,
+        C: PendingRequestCallback<R>,
+    {
+        let data = match serde_json::from_slice::<JsonRpcApiResponse>(result) {
+            Ok(data) => data,
+            Err(_) => return,
+        };
+
+        let id = match data.id {
+            Some(id) => id,
+            None => return,
+        };
+
+        let request = match self.get_from_inprogress_plugins_request_list(id) {
+            Some(request) => request,
+            None => return,
+        };
+
+        if request.contains("Controller.1.activate") {
+            // handle activate response
+            self.on_activate_response(&sender, &callback, &data, &request)
+                .await;
+        } else if request.contains("Controller.1.status") {
+            // handle status response
+            self.on_status_response(&sender, &callback, &data, &request)
+                .await;
+        } else if request.contains("Controller.1.register") {
+            // nothing to do here
+            info!("StatusManger Received response for register request");
+        }
+
+        let mut inprogress_plugins_request = self.inprogress_plugins_request.write().unwrap();
+        inprogress_plugins_request.remove(&id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use tokio::sync::mpsc::{self, Receiver, Sender};
+
+    static TEST_ID: AtomicU64 = AtomicU64::new(1);
+
+    fn next_id() -> u64 {
+        TEST_ID.fetch_add(1, Ordering::Relaxed)
+    }
+
+    #[derive(Clone)]
+    struct TestChannel {
+        sender: Sender<Result<String, RippleError>>,
+    }
+
+    #[async_trait]
+    impl PendingRequestSender<String> for TestChannel {
+        async fn send_pending(&self, request: String) -> RippleResponse {
+            self.sender
+                .send(Ok(request))
+                .await
+                .map_err(|_| RippleError::SendFailure)
+        }
+    }
+
+    #[async_trait]
+    impl PendingRequestCallback<String> for TestChannel {
+        async fn send_pending_error(&self, _request: String, error: RippleError) {
+            let _ = self.sender.send(Err(error)).await;
+        }
+    }
+
+    fn setup() -> (
+        PluginStatusManager<String>,
+        TestChannel,
+        Receiver<Result<String, RippleError>>,
+    ) {
+        let (sender, receiver) = mpsc::channel(10);
+        let status_manager = PluginStatusManager::new(
+            vec!["TestPlugin".to_owned(), "OtherPlugin".to_owned()],
+            next_id,
+        );
+        (status_manager, TestChannel { sender }, receiver)
+    }
+
+    fn response(
+        id: u64,
+        result: Option<serde_json::Value>,
+        error: Option<serde_json::Value>,
+    ) -> JsonRpcApiResponse {
+        JsonRpcApiResponse {
+            id: Some(id),
+            jsonrpc: "2.0".to_string(),
+            result,
+            error,
+            method: None,
+            params: None,
+        }
+    }
+
+    #[test]
+    fn test_generate_state_change_subscribe_request() {
+        let (status_manager, _, _) = setup();
+        let request = status_manager.generate_state_change_subscribe_request();
+        assert!(request.contains("register"));
+        assert!(request.contains("statechange"));
+    }
+
+    #[tokio::test]
+    async fn test_on_activate_response() {
+        let (status_manager, channel, mut receiver) = setup();
+        status_manager.add_request_to_pending_list("TestPlugin".to_string(), "req".to_owned());
+
+        let data = response(1, Some(serde_json::json!(null)), None);
+        let request = r#"{"jsonrpc":"2.0","id":1,"method":"Controller.1.activate","params":{"callsign":"TestPlugin"}}"#;
+        status_manager
+            .on_activate_response(&channel, &channel, &data, request)
+            .await;
+        let status = status_manager.get_status("TestPlugin".to_string());
+        assert_eq!(status.unwrap().state, State::Activated);
+        assert_eq!(receiver.recv().await.unwrap(), Ok("req".to_owned()));
+        assert!(status_manager
+            .get_all_pending_requests("TestPlugin".to_string())
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_on_status_response() {
+        let (status_manager, channel, _receiver) = setup();
+
+        let data = response(
+            1,
+            Some(serde_json::json!([{"callsign":"TestPlugin","state":"activated"}])),
+            None,
+        );
+        let request = r#"{"jsonrpc":"2.0","id":1,"method":"Controller.1.status@TestPlugin"}"#;
+        status_manager
+            .on_status_response(&channel, &channel, &data, request)
+            .await;
+        let status = status_manager.get_status("TestPlugin".to_string());
+        assert_eq!(status.unwrap().state, State::Activated);
+    }
+
+    #[tokio::test]
+    async fn test_status_response_for_all_plugins() {
+        let (status_manager, channel, _receiver) = setup();
+        let request = status_manager.generate_plugin_status_request(None);
+        let id: u64 = serde_json::from_str::<serde_json::Value>(&request).unwrap()["id"]
+            .as_u64()
+            .unwrap();
+        let data = response(
+            id,
+            Some(serde_json::json!([
+                {"callsign":"TestPlugin","state":"activated"},
+                {"callsign":"OtherPlugin","state":"deactivated"},
+                {"callsign":"UnknownPlugin","state":"activated"}
+            ])),
+            None,
+        );
+        let result = serde_json::to_vec(&data).unwrap();
+        assert!(
+            status_manager
+                .is_controller_response(channel.clone(), channel.clone(), &result)
+                .await
+        );
+        status_manager
+            .handle_controller_response(channel.clone(), channel, &result)
+            .await;
+
+        assert_eq!(
+            status_manager
+                .get_status("OtherPlugin".to_string())
+                .unwrap()
+                .state,
+            State::Deactivated
+        );
+        assert!(status_manager
+            .get_status("UnknownPlugin".to_string())
+            .is_none());
+        assert!(status_manager
+            .get_from_inprogress_plugins_request_list(id)
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_on_thunder_error_response() {
+        let (status_manager, channel, mut receiver) = setup();
+        status_manager.add_request_to_pending_list("TestPlugin".to_string(), "req".to_owned());
+
+        let data = response(
+            1,
+            None,
+            Some(serde_json::json!({"code":1,"message":"ERROR_UNKNOWN_KEY"})),
+        );
+        let plugin_name = "TestPlugin".to_string();
+        status_manager
+            .on_thunder_error_response(&channel, &data, &plugin_name)
+            .await;
+        let status = status_manager.get_status("TestPlugin".to_string());
+        assert_eq!(status.unwrap().state, State::Missing);
+        assert_eq!(
+            receiver.recv().await.unwrap(),
+            Err(RippleError::ServiceError)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_state_change_event() {
+        let (status_manager, channel, mut receiver) = setup();
+        status_manager.add_request_to_pending_list("TestPlugin".to_string(), "req".to_owned());
+
+        let event = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": STATE_CHANGE_EVENT_METHOD,
+            "params": {"callsign": "TestPlugin", "state": "Activated"}
+        });
+        assert!(
+            status_manager
+                .is_controller_response(channel.clone(), channel, event.to_string().as_bytes())
+                .await
+        );
+        assert_eq!(receiver.recv().await.unwrap(), Ok("req".to_owned()));
+    }
+}
@@ -463,6 +463,9 @@ pub const JSON_RPC_STANDARD_ERROR_INVALID_PARAMS: i32 = -32602;
 
 pub const JSON_RPC_STANDARD_ERROR_METHOD_NOT_FOUND: i32 = -32601;
 
+/// Server error range code returned when an app goes over its request rate limit
+pub const JSON_RPC_ERROR_RATE_LIMITED: i32 = -32005;
+
 pub const CAPABILITY_GRANT_DENIED: i32 = -40400;
 
 pub const CAPABILITY_UNGRANTED: i32 = -40401;
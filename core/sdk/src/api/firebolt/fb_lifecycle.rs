@@ -100,7 +100,7 @@ pub struct CloseRequest {
     pub reason: CloseReason,
 }
 
-#[derive(Debug, PartialEq, Serialize, Clone)]
+#[derive(Debug, PartialEq, Eq, Serialize, Clone, Copy)]
 pub enum AppLifecycleState2_0 {
     #[serde(rename = "initializing")]
     Initializing,
@@ -169,7 +169,7 @@ impl Lifecycle2_0AppEvent {
     }
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
 pub enum LifecycleManagerState {
     #[serde(rename = "UNLOADED")]
     Unloaded,
@@ -202,6 +202,20 @@ impl From<LifecycleManagerState> for AppLifecycleState2_0 {
         }
     }
 }
+impl AppLifecycleState2_0 {
+    pub fn as_string(&self) -> &'static str {
+        match self {
+            AppLifecycleState2_0::Initializing => "initializing",
+            AppLifecycleState2_0::Paused => "paused",
+            AppLifecycleState2_0::Active => "active",
+            AppLifecycleState2_0::Suspended => "suspended",
+            AppLifecycleState2_0::Hibernated => "hibernated",
+            AppLifecycleState2_0::Terminating => "terminating",
+            AppLifecycleState2_0::Unknown => "unknown",
+        }
+    }
+}
+
 impl LifecycleManagerState {
     pub fn as_string(&self) -> &'static str {
         match self {
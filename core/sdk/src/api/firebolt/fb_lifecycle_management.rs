@@ -172,6 +172,14 @@ pub struct SetStateRequest {
     pub state: LifecycleState,
 }
 
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetProviderRequest {
+    pub capability: String,
+    /// None clears the choice
+    pub app_id: Option<String>,
+}
+
 #[derive(Serialize, PartialEq, Deserialize, Debug, Clone)]
 #[serde(untagged)]
 pub enum SessionResponse {
@@ -166,6 +166,21 @@ pub struct FireboltEvent {
     pub result: Value,
 }
 
+/// An app unloaded by Ripple to stay within the lifecycle limits of the device manifest.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct TelemetryAppEviction {
+    pub app_id: String,
+    /// `max_loaded_apps` or `min_available_memory`
+    pub reason: String,
+    /// App being launched when the limit was hit
+    pub launching_app_id: String,
+    pub loaded_apps: u64,
+    pub max_loaded_apps: u64,
+    pub available_memory_kb: Option<u64>,
+    pub min_available_memory_kb: u64,
+    pub ripple_session_id: String,
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
 pub enum TelemetryPayload {
     AppLoadStart(AppLoadStart),
@@ -178,6 +193,7 @@ pub enum TelemetryPayload {
     InternalInitialize(InternalInitialize),
     FireboltInteraction(FireboltInteraction), // External Service failures (service, error)
     FireboltEvent(FireboltEvent),
+    AppEviction(TelemetryAppEviction),
 }
 
 impl TelemetryPayload {
@@ -193,6 +209,7 @@ impl TelemetryPayload {
             Self::InternalInitialize(i) => i.ripple_session_id = session_id,
             Self::FireboltInteraction(f) => f.ripple_session_id = session_id,
             Self::FireboltEvent(_) => {}
+            Self::AppEviction(a) => a.ripple_session_id = session_id,
         }
     }
 }
@@ -73,6 +73,14 @@ impl AppLibrary {
         provider.cloned()
     }
 
+    pub fn get_provider_priority(state: &AppLibraryState, app_id: &str) -> Option<u32> {
+        state
+            .default_apps
+            .iter()
+            .find(|x| x.app_id == *app_id)
+            .and_then(|x| x.provider_priority)
+    }
+
     pub fn get_manifest(state: &AppLibraryState, app_id: &str) -> Option<AppManifest> {
         let mut itr = state.default_apps.iter();
         let i = itr.position(|x| x.app_id == *app_id)?;
@@ -123,11 +131,15 @@ mod tests {
             AppLibraryEntry {
                 app_id: "app1".to_string(),
                 boot_state: BootState::Foreground,
+                rate_limits: None,
+                provider_priority: None,
                 manifest: AppManifestLoad::Embedded(AppManifest::default()),
             },
             AppLibraryEntry {
                 app_id: "app2".to_string(),
                 boot_state: BootState::Unloaded,
+                rate_limits: None,
+                provider_priority: None,
                 manifest: AppManifestLoad::Embedded(AppManifest::default()),
             },
         ]
@@ -161,6 +173,8 @@ mod tests {
             Some(AppLibraryEntry {
                 app_id: "app1".to_string(),
                 boot_state: BootState::Foreground,
+                rate_limits: None,
+                provider_priority: None,
                 manifest: AppManifestLoad::Embedded(AppManifest::default()),
             })
         );
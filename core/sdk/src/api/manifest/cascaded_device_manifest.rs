@@ -45,7 +45,8 @@ use super::{
         CaptionStyle, DataGovernanceConfig, DataGovernancePolicy, DataGovernanceSettingTag,
         DefaultValues, DeviceManifest, DistributionConfiguration, IdSalt, IntentValidation,
         InternetMonitoringConfiguration, LifecycleConfiguration, PrivacySettingsStorageType,
-        RippleConfiguration, RippleFeatures, VoiceGuidance, WsConfiguration,
+        ProviderSelectionPolicy, RateLimits, RippleConfiguration, RippleFeatures,
+        StorageBackendType, VoiceGuidance, WsConfiguration,
     },
     exclusory::{AppAuthorizationRules, ExclusoryImpl},
     remote_feature::FeatureFlag,
@@ -123,6 +124,11 @@ pub struct CascadedRippleConfiguration {
     pub partner_exclusion_refresh_timeout: Option<u32>,
     pub metrics_logging_percentage: Option<u32>,
     pub internet_monitoring_configuration: Option<InternetMonitoringConfiguration>,
+    pub ca_bundle_path: Option<String>,
+    pub rate_limits: Option<RateLimits>,
+    pub permissions_cache_ttl_secs: Option<u64>,
+    pub device_permissions_path: Option<String>,
+    pub storage_backend: Option<StorageBackendType>,
 }
 
 impl MergeConfig<CascadedRippleConfiguration> for RippleConfiguration {
@@ -197,6 +203,21 @@ impl MergeConfig<CascadedRippleConfiguration> for RippleConfiguration {
         if let Some(cas_internet_monitering_conf) = cascaded.internet_monitoring_configuration {
             self.internet_monitoring_configuration = cas_internet_monitering_conf;
         }
+        if let Some(cas_ca_bundle_path) = cascaded.ca_bundle_path {
+            self.ca_bundle_path = Some(cas_ca_bundle_path)
+        }
+        if let Some(cas_rate_limits) = cascaded.rate_limits {
+            self.rate_limits = cas_rate_limits
+        }
+        if let Some(cas_permissions_cache_ttl_secs) = cascaded.permissions_cache_ttl_secs {
+            self.permissions_cache_ttl_secs = cas_permissions_cache_ttl_secs
+        }
+        if let Some(cas_device_permissions_path) = cascaded.device_permissions_path {
+            self.device_permissions_path = Some(cas_device_permissions_path)
+        }
+        if let Some(cas_storage_backend) = cascaded.storage_backend {
+            self.storage_backend = cas_storage_backend
+        }
     }
 }
 
@@ -421,6 +442,10 @@ pub struct CascadedCapabilityConfiguration {
     pub grant_policies: Option<HashMap<String, CascadedGrantPolicies>>,
     pub grant_exclusion_filters: Option<Vec<GrantExclusionFilter>>,
     pub dependencies: Option<HashMap<FireboltPermission, Vec<FireboltPermission>>>,
+    pub provider_response_timeout_ms: Option<u64>,
+    pub provider_response_timeouts_ms: Option<HashMap<String, u64>>,
+    pub provider_selection_policy: Option<ProviderSelectionPolicy>,
+    pub provider_request_queue_capacity: Option<usize>,
 }
 
 impl MergeConfig<CascadedCapabilityConfiguration> for CapabilityConfiguration {
@@ -483,6 +508,19 @@ impl MergeConfig<CascadedCapabilityConfiguration> for CapabilityConfiguration {
                 self.dependencies.insert(key, set.into_iter().collect());
             }
         }
+
+        if let Some(cas_timeout_ms) = cascaded.provider_response_timeout_ms {
+            self.provider_response_timeout_ms = cas_timeout_ms;
+        }
+        if let Some(cas_timeouts_ms) = cascaded.provider_response_timeouts_ms {
+            self.provider_response_timeouts_ms.extend(cas_timeouts_ms);
+        }
+        if let Some(cas_policy) = cascaded.provider_selection_policy {
+            self.provider_selection_policy = cas_policy;
+        }
+        if let Some(cas_capacity) = cascaded.provider_request_queue_capacity {
+            self.provider_request_queue_capacity = cas_capacity;
+        }
     }
 }
 
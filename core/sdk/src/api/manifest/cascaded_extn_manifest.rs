@@ -35,6 +35,7 @@ pub struct CascadedExtnManifest {
     pub rules_path: Option<Vec<String>>,
     pub extn_sdks: Option<Vec<String>>,
     pub provider_registrations: Option<Vec<String>>,
+    pub allow_unlisted_services: Option<bool>,
 }
 impl MergeConfig<CascadedExtnManifest> for ExtnManifest {
     fn merge_config(&mut self, cascaded: CascadedExtnManifest) {
@@ -88,6 +89,9 @@ impl MergeConfig<CascadedExtnManifest> for ExtnManifest {
             self.provider_registrations.sort();
             self.provider_registrations.dedup();
         }
+        if let Some(cas_allow_unlisted_services) = cascaded.allow_unlisted_services {
+            self.allow_unlisted_services = cas_allow_unlisted_services;
+        }
     }
 }
 
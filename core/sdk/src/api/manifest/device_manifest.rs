@@ -37,6 +37,10 @@ use crate::{
 use super::{apps::AppManifest, exclusory::ExclusoryImpl};
 pub const PARTNER_EXCLUSION_REFRESH_TIMEOUT: u32 = 12 * 60 * 60; // 12 hours
 pub const METRICS_LOGGING_PERCENTAGE_DEFAULT: u32 = 10;
+pub const WS_HANDSHAKE_TIMEOUT_MS: u64 = 5000;
+pub const WS_MAX_PENDING_HANDSHAKES: usize = 64;
+pub const PERMISSIONS_CACHE_TTL_SECS: u64 = 24 * 60 * 60; // 24 hours
+pub const PROVIDER_RESPONSE_TIMEOUT_MS: u64 = 2 * 60 * 1000; // 2 minutes
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RippleConfiguration {
@@ -70,6 +74,25 @@ pub struct RippleConfiguration {
     pub metrics_logging_percentage: u32,
     #[serde(default)]
     pub internet_monitoring_configuration: InternetMonitoringConfiguration,
+    /// PEM bundle of the certificate authorities trusted for https rule endpoints
+    #[serde(default)]
+    pub ca_bundle_path: Option<String>,
+    /// Default request limits of every app, overridden per app in the app library
+    #[serde(default)]
+    pub rate_limits: RateLimits,
+    /// Age after which stored cloud permissions are refreshed in the background
+    #[serde(default = "permissions_cache_ttl_secs_default")]
+    pub permissions_cache_ttl_secs: u64,
+    /// JSON file of permission lists keyed by app id, used when cloud permissions are off
+    #[serde(default)]
+    pub device_permissions_path: Option<String>,
+    /// Where settings and other stored values are kept
+    #[serde(default)]
+    pub storage_backend: StorageBackendType,
+}
+
+fn permissions_cache_ttl_secs_default() -> u64 {
+    PERMISSIONS_CACHE_TTL_SECS
 }
 
 fn partner_exclusion_refresh_timeout_default() -> u32 {
@@ -92,6 +115,36 @@ pub struct CapabilityConfiguration {
     pub grant_exclusion_filters: Vec<GrantExclusionFilter>,
     #[serde(default)]
     pub dependencies: HashMap<FireboltPermission, Vec<FireboltPermission>>,
+    /// Time a provider app has to answer a request before the caller gets an error, 0 waits forever
+    #[serde(default = "provider_response_timeout_ms_default")]
+    pub provider_response_timeout_ms: u64,
+    /// Per capability overrides of `provider_response_timeout_ms`
+    #[serde(default)]
+    pub provider_response_timeouts_ms: HashMap<String, u64>,
+    #[serde(default)]
+    pub provider_selection_policy: ProviderSelectionPolicy,
+    /// Requests held until a provider registers, 0 fails requests without a provider right away
+    #[serde(default)]
+    pub provider_request_queue_capacity: usize,
+}
+
+/// Picks the provider of a capability when more than one app provides it. A provider chosen
+/// through `lifecyclemanagement.setProvider` takes precedence under every policy, and every
+/// policy falls back to the most recently registered provider.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum ProviderSelectionPolicy {
+    /// A provider app in the foreground
+    #[default]
+    Foreground,
+    /// The provider with the highest `providerPriority` in the app library
+    Priority,
+    /// Only the provider chosen by the launcher through `lifecyclemanagement.setProvider`
+    Launcher,
+}
+
+fn provider_response_timeout_ms_default() -> u64 {
+    PROVIDER_RESPONSE_TIMEOUT_MS
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
@@ -203,16 +256,46 @@ pub struct ApplicationsConfiguration {
     pub distributor_app_aliases: HashMap<String, String>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct WsConfiguration {
     pub enabled: bool,
     pub gateway: String,
+    /// Time a client gets to complete the websocket upgrade
+    #[serde(default = "ws_handshake_timeout_ms_default")]
+    pub handshake_timeout_ms: u64,
+    /// Upgrades in progress at once, connections over the limit are dropped
+    #[serde(default = "ws_max_pending_handshakes_default")]
+    pub max_pending_handshakes: usize,
+    /// Open connections per app id, unlimited when not set
+    #[serde(default)]
+    pub max_connections_per_app: Option<usize>,
+}
+
+impl Default for WsConfiguration {
+    fn default() -> Self {
+        WsConfiguration {
+            enabled: false,
+            gateway: String::default(),
+            handshake_timeout_ms: ws_handshake_timeout_ms_default(),
+            max_pending_handshakes: ws_max_pending_handshakes_default(),
+            max_connections_per_app: None,
+        }
+    }
+}
+
+fn ws_handshake_timeout_ms_default() -> u64 {
+    WS_HANDSHAKE_TIMEOUT_MS
+}
+
+fn ws_max_pending_handshakes_default() -> usize {
+    WS_MAX_PENDING_HANDSHAKES
 }
 
 pub fn ws_configuration_default() -> WsConfiguration {
     WsConfiguration {
         enabled: true,
         gateway: "127.0.0.1:3473".into(),
+        ..Default::default()
     }
 }
 
@@ -220,6 +303,7 @@ pub fn ws_configuration_internal_default() -> WsConfiguration {
     WsConfiguration {
         enabled: true,
         gateway: "127.0.0.1:3474".into(),
+        ..Default::default()
     }
 }
 
@@ -281,6 +365,44 @@ pub struct AppLibraryEntry {
     pub app_id: String,
     pub manifest: AppManifestLoad,
     pub boot_state: BootState,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limits: Option<RateLimits>,
+    /// Used with the priority provider selection policy, higher is preferred
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider_priority: Option<u32>,
+}
+
+/// Token bucket refilled with `rate` requests per second, holding up to `burst` requests.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct RateLimit {
+    pub rate: u32,
+    pub burst: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+pub struct RateLimits {
+    /// Shared by all the requests of an app
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app: Option<RateLimit>,
+    /// Per method, checked on top of the app limit
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub methods: HashMap<String, RateLimit>,
+}
+
+impl RateLimits {
+    /// Limits of an app, the app library entry wins over the device defaults.
+    pub fn with_overrides(&self, overrides: &RateLimits) -> RateLimits {
+        let mut methods = self.methods.clone();
+        methods.extend(overrides.methods.clone());
+        RateLimits {
+            app: overrides.app.clone().or_else(|| self.app.clone()),
+            methods,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.app.is_none() && self.methods.is_empty()
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
@@ -529,6 +651,16 @@ pub struct AppAuthorizationRules {
     pub app_ignore_rules: HashMap<String, Vec<String>>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum StorageBackendType {
+    /// The device persistent store, Thunder PersistentStore on RDK devices
+    #[default]
+    Thunder,
+    /// One JSON file per namespace under `saved_dir`, for hosts without Thunder
+    File,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub enum PrivacySettingsStorageType {
@@ -685,6 +817,11 @@ impl Default for RippleConfiguration {
             metrics_logging_percentage: metrics_logging_percentage_default(),
             internet_monitoring_configuration: Default::default(),
             log_signal_log_level: log_signal_default_level(),
+            ca_bundle_path: None,
+            rate_limits: RateLimits::default(),
+            permissions_cache_ttl_secs: PERMISSIONS_CACHE_TTL_SECS,
+            device_permissions_path: None,
+            storage_backend: StorageBackendType::default(),
         }
     }
 }
@@ -728,6 +865,14 @@ impl DeviceManifest {
         self.configuration.internal_ws_configuration.gateway.clone()
     }
 
+    pub fn get_ws_configuration(&self) -> WsConfiguration {
+        self.configuration.ws_configuration.clone()
+    }
+
+    pub fn get_internal_ws_configuration(&self) -> WsConfiguration {
+        self.configuration.internal_ws_configuration.clone()
+    }
+
     pub fn get_internal_app_id(&self) -> Option<String> {
         self.configuration.internal_app_id.clone()
     }
@@ -773,6 +918,23 @@ impl DeviceManifest {
         self.clone().capabilities.grant_policies
     }
 
+    pub fn get_provider_response_timeout_ms(&self, capability: &str) -> u64 {
+        let capabilities = &self.capabilities;
+        capabilities
+            .provider_response_timeouts_ms
+            .get(capability)
+            .copied()
+            .unwrap_or(capabilities.provider_response_timeout_ms)
+    }
+
+    pub fn get_provider_selection_policy(&self) -> ProviderSelectionPolicy {
+        self.capabilities.provider_selection_policy
+    }
+
+    pub fn get_provider_request_queue_capacity(&self) -> usize {
+        self.capabilities.provider_request_queue_capacity
+    }
+
     pub fn get_grant_exclusion_filters(&self) -> Vec<GrantExclusionFilter> {
         self.clone().capabilities.grant_exclusion_filters
     }
@@ -802,6 +964,26 @@ impl DeviceManifest {
             .internet_monitoring_configuration
             .default_monitoring_interval_seconds
     }
+
+    pub fn get_ca_bundle_path(&self) -> Option<String> {
+        self.configuration.ca_bundle_path.clone()
+    }
+
+    pub fn get_rate_limits(&self) -> RateLimits {
+        self.configuration.rate_limits.clone()
+    }
+
+    pub fn get_permissions_cache_ttl(&self) -> u64 {
+        self.configuration.permissions_cache_ttl_secs
+    }
+
+    pub fn get_device_permissions_path(&self) -> Option<String> {
+        self.configuration.device_permissions_path.clone()
+    }
+
+    pub fn get_storage_backend(&self) -> StorageBackendType {
+        self.configuration.storage_backend
+    }
 }
 
 #[cfg(test)]
@@ -822,10 +1004,12 @@ pub(crate) mod tests {
                     ws_configuration: WsConfiguration {
                         enabled: true,
                         gateway: "127.0.0.1:3473".to_string(),
+                        ..Default::default()
                     },
                     internal_ws_configuration: WsConfiguration {
                         enabled: true,
                         gateway: "127.0.0.1:3474".to_string(),
+                        ..Default::default()
                     },
                     platform_parameters: {
                         let mut params = HashMap::new();
@@ -909,6 +1093,11 @@ pub(crate) mod tests {
                     internet_monitoring_configuration: InternetMonitoringConfiguration {
                         default_monitoring_interval_seconds: 180,
                     },
+                    ca_bundle_path: None,
+                    rate_limits: RateLimits::default(),
+                    permissions_cache_ttl_secs: PERMISSIONS_CACHE_TTL_SECS,
+                    device_permissions_path: None,
+                    storage_backend: StorageBackendType::Thunder,
                 },
                 capabilities: CapabilityConfiguration {
                     supported: vec!["main[manage]".to_string(), "test".to_string()],
@@ -919,6 +1108,10 @@ pub(crate) mod tests {
                         catalog: Some("test-catalog".to_string()),
                     }],
                     dependencies: HashMap::new(),
+                    provider_response_timeout_ms: PROVIDER_RESPONSE_TIMEOUT_MS,
+                    provider_response_timeouts_ms: HashMap::new(),
+                    provider_selection_policy: ProviderSelectionPolicy::Foreground,
+                    provider_request_queue_capacity: 0,
                 },
                 lifecycle: LifecycleConfiguration {
                     app_ready_timeout_ms: 30000,
@@ -1236,4 +1429,21 @@ pub(crate) mod tests {
                 .accessibility_audio_description_settings
         );
     }
+
+    #[test]
+    fn test_get_provider_response_timeout_ms() {
+        let mut manifest = DeviceManifest::mock();
+        assert_eq!(
+            manifest.get_provider_response_timeout_ms("xrn:firebolt:capability:input:keyboard"),
+            PROVIDER_RESPONSE_TIMEOUT_MS
+        );
+        manifest
+            .capabilities
+            .provider_response_timeouts_ms
+            .insert("xrn:firebolt:capability:input:keyboard".to_owned(), 0);
+        assert_eq!(
+            manifest.get_provider_response_timeout_ms("xrn:firebolt:capability:input:keyboard"),
+            0
+        );
+    }
 }
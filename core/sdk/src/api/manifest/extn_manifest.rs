@@ -41,6 +41,10 @@ pub struct ExtnManifest {
     pub extn_sdks: Vec<String>,
     #[serde(default = "default_providers")]
     pub provider_registrations: Vec<String>,
+    /// Accepts service handshakes for ids which have no symbol in the manifest, or whose symbol
+    /// configures no credential
+    #[serde(default)]
+    pub allow_unlisted_services: bool,
 }
 
 /// Some unit tests which use defaults are failing because we need default providers for unit testing
@@ -57,6 +61,7 @@ impl Default for ExtnManifest {
             rules_path: Vec::new(),
             extn_sdks: Vec::new(),
             provider_registrations: default_providers(),
+            allow_unlisted_services: false,
         }
     }
 }
@@ -102,7 +107,44 @@ pub struct ExtnSymbol {
     pub config: Option<HashMap<String, String>>,
 }
 
+/// Symbol config key holding the shared secret of a websocket service
+pub const SERVICE_SECRET_CONFIG: &str = "service_secret";
+/// Symbol config key holding the path of the per boot token file of a websocket service
+pub const SERVICE_TOKEN_FILE_CONFIG: &str = "service_token_file";
+
+/// Credential a websocket service presents in its handshake.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ServiceCredential {
+    Secret(String),
+    /// Ripple writes a new token to the file on every boot, the platform is expected to create
+    /// the file readable by the service user only.
+    TokenFile(String),
+}
+
+impl ServiceCredential {
+    /// Current value of the credential, None if the token file cannot be read.
+    pub fn read(&self) -> Option<String> {
+        match self {
+            ServiceCredential::Secret(secret) => Some(secret.clone()),
+            ServiceCredential::TokenFile(path) => fs::read_to_string(path)
+                .map_err(|e| warn!("Cannot read service token file {}: {}", path, e))
+                .ok()
+                .map(|token| token.trim().to_owned()),
+        }
+    }
+}
+
 impl ExtnSymbol {
+    pub fn get_service_credential(&self) -> Option<ServiceCredential> {
+        let config = self.config.as_ref()?;
+        if let Some(secret) = config.get(SERVICE_SECRET_CONFIG) {
+            return Some(ServiceCredential::Secret(secret.clone()));
+        }
+        config
+            .get(SERVICE_TOKEN_FILE_CONFIG)
+            .map(|path| ServiceCredential::TokenFile(path.clone()))
+    }
+
     pub fn get_launcher_capability(&self) -> Option<ExtnId> {
         if let Ok(cap) = ExtnId::try_from(self.id.clone()) {
             if cap.is_launcher_channel() {
@@ -249,6 +291,7 @@ pub(crate) mod tests {
                 rules_path: Vec::new(),
                 extn_sdks: Vec::new(),
                 provider_registrations: Vec::new(),
+                allow_unlisted_services: false,
             }
         }
     }
@@ -273,6 +316,35 @@ pub(crate) mod tests {
         );
     }
 
+    #[test]
+    fn test_get_service_credential() {
+        let mut symbol = ExtnSymbol {
+            id: "ripple:channel:gateway:service".to_owned(),
+            ..Default::default()
+        };
+        assert!(symbol.get_service_credential().is_none());
+
+        symbol.config = Some(HashMap::from([(
+            SERVICE_TOKEN_FILE_CONFIG.to_owned(),
+            "/nonexistent/token".to_owned(),
+        )]));
+        let credential = symbol.get_service_credential().unwrap();
+        assert_eq!(
+            credential,
+            ServiceCredential::TokenFile("/nonexistent/token".to_owned())
+        );
+        assert!(credential.read().is_none());
+
+        symbol.config = Some(HashMap::from([(
+            SERVICE_SECRET_CONFIG.to_owned(),
+            "s3cret".to_owned(),
+        )]));
+        assert_eq!(
+            symbol.get_service_credential().unwrap().read(),
+            Some("s3cret".to_owned())
+        );
+    }
+
     #[test]
     fn test_get_symbol() {
         let dist_channel = ExtnId::new_channel(ExtnClassId::Distributor, "test".into()).to_string();
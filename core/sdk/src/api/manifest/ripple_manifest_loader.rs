@@ -16,8 +16,9 @@
 //
 
 use log::{error, info};
-use serde::Deserialize;
-use std::collections::HashMap;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 
 use crate::{
@@ -78,7 +79,7 @@ impl RippleManifestLoader {
             .and_then(|s| s.parse::<bool>().ok())
             .unwrap_or(false);
         let base_path = Self::try_load_base_path();
-        let mut country_code = std::env::var("RIPPLE_COUNTRY").unwrap_or_default();
+        let country_code = std::env::var("RIPPLE_COUNTRY").unwrap_or_default();
         let device_type = Some(std::env::var("RIPPLE_DEVICE_PLATFORM").unwrap_or("box".to_owned()));
         let manifest_config = if cascaded_config {
             Path::new(&base_path)
@@ -88,14 +89,7 @@ impl RippleManifestLoader {
         } else {
             None
         };
-
-        if country_code.is_empty() {
-            if let Some(c) = manifest_config.clone() {
-                if let Some(t) = c.default.tag {
-                    country_code = t.clone();
-                }
-            }
-        }
+        let country_code = Self::resolve_country_code(country_code, manifest_config.as_ref());
         info!(
             "RIPPLE_CASCADED_CONFIGURATION={}, RIPPLE_COUNTRY={}, RIPPLE_DEVICE_PLATFORM={} ",
             cascaded_config,
@@ -124,6 +118,41 @@ impl RippleManifestLoader {
         }
     }
 
+    /// Loader for the manifests of the given country and platform instead of the ones in the
+    /// environment, a broken `ripple.config.json` is an error instead of an empty config.
+    pub fn for_target(
+        base_path: String,
+        country_code: Option<String>,
+        device_type: Option<String>,
+    ) -> Result<Self, ManifestLoadError> {
+        let config_path = Path::new(&base_path)
+            .join("ripple.config.json")
+            .to_string_lossy()
+            .into_owned();
+        let manifest_config = read_manifest::<RippleManifestConfig>(&config_path)?;
+        let country_code =
+            Self::resolve_country_code(country_code.unwrap_or_default(), Some(&manifest_config));
+        Ok(RippleManifestLoader {
+            cascaded_config: true,
+            manifest_config: Some(manifest_config),
+            base_path,
+            country_code,
+            device_type: Some(device_type.unwrap_or("box".to_owned())),
+        })
+    }
+
+    fn resolve_country_code(
+        country_code: String,
+        manifest_config: Option<&RippleManifestConfig>,
+    ) -> String {
+        if !country_code.is_empty() {
+            return country_code;
+        }
+        manifest_config
+            .and_then(|c| c.default.tag.clone())
+            .unwrap_or(country_code)
+    }
+
     fn try_load_base_path() -> String {
         if cfg!(feature = "local_dev") {
             if let Ok(path) = std::env::var("RIPPLE_CONFIG_BASE_PATH") {
@@ -165,6 +194,7 @@ impl RippleManifestLoader {
             base_path: self.base_path.clone(),
             country_code: self.country_code.clone(),
             device_type: self.device_type.clone(),
+            tag: None,
         }
     }
 }
@@ -175,9 +205,42 @@ pub struct RippleConfigLoader {
     base_path: String,
     country_code: String,
     device_type: Option<String>,
+    tag: Option<String>,
+}
+
+/// A manifest file which could not be read or deserialized.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ManifestLoadError {
+    pub path: String,
+    /// Position of the error in the file as reported by serde_json, also part of the message
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl std::fmt::Display for ManifestLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// A merged manifest with the files it was merged from.
+#[derive(Debug, Clone)]
+pub struct ManifestReport<T> {
+    pub manifest: T,
+    /// Files in merge order
+    pub sources: Vec<String>,
+    /// The file which last set each field of the merged manifest
+    pub provenance: BTreeMap<String, String>,
 }
 
 impl RippleConfigLoader {
+    /// Uses the given tag instead of the tags of the country or the default tag.
+    pub fn with_tag(mut self, tag: Option<String>) -> Self {
+        self.tag = tag;
+        self
+    }
+
     fn resolve_path(&self, path: &str) -> String {
         if !path.is_empty() && path.starts_with("/") {
             Path::new(&self.base_path)
@@ -378,7 +441,10 @@ impl RippleConfigLoader {
 
         let mut tags_to_process = Vec::new();
         let mut country_match_found = false; // Flag to track if a country match occurred
-        if let Some(build) = build_config {
+        if let Some(tag) = self.tag.as_deref() {
+            tags_to_process.push(tag);
+            country_match_found = true;
+        } else if let Some(build) = build_config {
             if let Some(country_map) = &build.country {
                 if let Some(country_config) = country_map.get(country_code) {
                     if let Some(tags) = &country_config.tags {
@@ -452,6 +518,115 @@ impl RippleConfigLoader {
             panic!("get_device_manifest called in non-cascaded mode after initialization");
         }
     }
+
+    /// Merges the extension manifests like `get_extn_manifest`, but stops at the first file
+    /// which cannot be loaded and tracks where every field came from.
+    pub fn inspect_extn_manifest(&self) -> Result<ManifestReport<ExtnManifest>, ManifestLoadError> {
+        let mut report = self.inspect::<ExtnManifest, CascadedExtnManifest>(true)?;
+        sort_rules_paths_by_keywords(&mut report.manifest.rules_path);
+        Ok(report)
+    }
+
+    /// Merges the device manifests like `get_device_manifest`, but stops at the first file
+    /// which cannot be loaded and tracks where every field came from.
+    pub fn inspect_device_manifest(
+        &self,
+    ) -> Result<ManifestReport<DeviceManifest>, ManifestLoadError> {
+        self.inspect::<DeviceManifest, CascadedDeviceManifest>(false)
+    }
+
+    fn inspect<T, C>(&self, is_extn: bool) -> Result<ManifestReport<T>, ManifestLoadError>
+    where
+        T: Default + Serialize + DeserializeOwned + MergeConfig<C>,
+        C: DeserializeOwned,
+    {
+        let (default_path, paths, test_path) = self.get_manifest_paths(is_extn);
+        let mut report = ManifestReport {
+            manifest: T::default(),
+            sources: Vec::new(),
+            provenance: BTreeMap::new(),
+        };
+        let mut merged = Value::Null;
+        if let Some(path) = &default_path {
+            report.manifest = read_manifest::<T>(path)?;
+            merged = to_value(&report.manifest, path)?;
+            record_provenance(&Value::Null, &merged, path, &mut report.provenance);
+            report.sources.push(path.clone());
+        }
+        let overlays = paths
+            .iter()
+            .filter(|path| Some(*path) != default_path.as_ref())
+            .chain(test_path.iter());
+        for path in overlays {
+            report.manifest.merge_config(read_manifest::<C>(path)?);
+            let next = to_value(&report.manifest, path)?;
+            record_provenance(&merged, &next, path, &mut report.provenance);
+            merged = next;
+            report.sources.push(path.clone());
+        }
+        Ok(report)
+    }
+}
+
+fn read_manifest<T: DeserializeOwned>(path: &str) -> Result<T, ManifestLoadError> {
+    let contents = std::fs::read_to_string(path).map_err(|e| ManifestLoadError {
+        path: path.to_owned(),
+        line: None,
+        column: None,
+        message: e.to_string(),
+    })?;
+    serde_json::from_str::<T>(&contents).map_err(|e| ManifestLoadError {
+        path: path.to_owned(),
+        line: Some(e.line()),
+        column: Some(e.column()),
+        message: e.to_string(),
+    })
+}
+
+fn to_value<T: Serialize>(manifest: &T, path: &str) -> Result<Value, ManifestLoadError> {
+    serde_json::to_value(manifest).map_err(|e| ManifestLoadError {
+        path: path.to_owned(),
+        line: None,
+        column: None,
+        message: e.to_string(),
+    })
+}
+
+/// Attributes every field which differs between the two merge results to the file merged last,
+/// arrays are attributed as a whole.
+fn record_provenance(
+    before: &Value,
+    after: &Value,
+    source: &str,
+    provenance: &mut BTreeMap<String, String>,
+) {
+    fn walk(
+        prefix: String,
+        before: Option<&Value>,
+        after: &Value,
+        source: &str,
+        provenance: &mut BTreeMap<String, String>,
+    ) {
+        if let Value::Object(fields) = after {
+            for (key, value) in fields {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                walk(
+                    path,
+                    before.and_then(|b| b.get(key)),
+                    value,
+                    source,
+                    provenance,
+                );
+            }
+        } else if before != Some(after) {
+            provenance.insert(prefix, source.to_owned());
+        }
+    }
+    walk(String::new(), Some(before), after, source, provenance);
 }
 
 pub fn sort_rules_paths_by_keywords(paths: &mut [String]) {
@@ -624,4 +799,108 @@ mod tests {
         env::remove_var("RIPPLE_COUNTRY");
         env::remove_var("RIPPLE_DEVICE_PLATFORM");
     }
+
+    fn write_target(name: &str, overlay: &str) -> String {
+        let dir = env::temp_dir().join(format!("ripple_manifest_{}_{}", name, std::process::id()));
+        std::fs::create_dir_all(dir.join("na")).unwrap();
+        let example = |file: &str| {
+            std::fs::read_to_string(format!("../../examples/manifest/{}", file)).unwrap()
+        };
+        let files = [
+            (
+                "ripple.config.json",
+                r#"{
+                    "default": { "device": "/manifest.json", "extn": "/extn.json", "tag": "eu" },
+                    "tags": { "na-tv": { "manifest": "/na/na.tv.manifest.json" } },
+                    "build": { "country": { "us": { "tags": ["na"] } } }
+                }"#
+                .to_owned(),
+            ),
+            ("manifest.json", example("device-manifest-example.json")),
+            ("extn.json", example("extn-manifest-example.json")),
+            ("na/na.tv.manifest.json", overlay.to_owned()),
+        ];
+        for (file, contents) in files {
+            std::fs::write(dir.join(file), contents).unwrap();
+        }
+        dir.display().to_string()
+    }
+
+    #[test]
+    fn test_inspect_manifests() {
+        let base_path = write_target(
+            "valid",
+            r#"{ "configuration": { "default_values": { "country_code": "CA" } } }"#,
+        );
+        let loader =
+            RippleManifestLoader::for_target(base_path.clone(), Some("us".to_owned()), None)
+                .unwrap()
+                .get_config_loader();
+        let overlay = format!("{}/na/na.tv.manifest.json", base_path);
+
+        // the overlay is only selected for tv
+        let report = loader.inspect_device_manifest().unwrap();
+        assert_eq!(report.sources, vec![format!("{}/manifest.json", base_path)]);
+
+        let loader = RippleManifestLoader::for_target(
+            base_path.clone(),
+            Some("us".to_owned()),
+            Some("tv".to_owned()),
+        )
+        .unwrap()
+        .get_config_loader();
+        let report = loader.inspect_device_manifest().unwrap();
+        assert_eq!(report.sources.len(), 2);
+        assert_eq!(
+            report.manifest.configuration.default_values.country_code,
+            "CA"
+        );
+        assert_eq!(
+            report
+                .provenance
+                .get("configuration.default_values.country_code"),
+            Some(&overlay)
+        );
+        assert_eq!(
+            report
+                .provenance
+                .get("configuration.default_values.language"),
+            Some(&format!("{}/manifest.json", base_path))
+        );
+        assert!(loader.inspect_extn_manifest().is_ok());
+
+        // an explicit tag replaces the tags of the country
+        let report = loader
+            .with_tag(Some("eu".to_owned()))
+            .inspect_device_manifest()
+            .unwrap();
+        assert_eq!(report.sources.len(), 1);
+    }
+
+    #[test]
+    fn test_inspect_invalid_overlay() {
+        let base_path = write_target(
+            "invalid",
+            r#"{
+                "configuration": {
+                    "ws_configuration": { "enabled": "yes", "gateway": "127.0.0.1:3473" }
+                }
+            }"#,
+        );
+        let error = RippleManifestLoader::for_target(
+            base_path.clone(),
+            Some("us".to_owned()),
+            Some("tv".to_owned()),
+        )
+        .unwrap()
+        .get_config_loader()
+        .inspect_device_manifest()
+        .unwrap_err();
+        assert_eq!(error.path, format!("{}/na/na.tv.manifest.json", base_path));
+        // the error points right after the "yes" value of the enabled field
+        assert_eq!((error.line, error.column), (Some(3), Some(58)));
+        assert!(error.to_string().ends_with("at line 3 column 58"));
+
+        assert!(RippleManifestLoader::for_target("/nonexistent".to_owned(), None, None).is_err());
+    }
 }
@@ -16,6 +16,7 @@
 //
 
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
 use crate::api::gateway::rpc_gateway_api::CallContext;
 
@@ -24,29 +25,40 @@ use crate::api::manifest::extn_manifest::ExtnManifest;
 use crate::api::manifest::ripple_manifest_loader::RippleManifestLoader;
 use crate::api::{
     gateway::rpc_gateway_api::{ApiMessage, ApiProtocol},
-    manifest::extn_manifest::ExtnSymbol,
+    manifest::extn_manifest::{ExtnSymbol, ServiceCredential},
 };
 use crate::extn::extn_id::{ExtnClassId, ExtnId};
 use crate::extn::{client::extn_client::ExtnClient, extn_client_message::ExtnMessage};
 use crate::processor::rpc_router::RouterState;
+use crate::service::service_connection::{
+    get_outbound_request_id, OutboundBuffer, ReconnectBackoff, ServiceConnectionState,
+    RECONNECT_BUFFER_CONFIG,
+};
 use crate::service::service_message::{Id, JsonRpcMessage};
 use crate::service::service_rpc_router::route_service_message;
 use crate::utils::extn_utils::ExtnStackSize;
 #[cfg(any(test, feature = "mock"))]
 use crate::utils::mock_utils::get_next_mock_service_response;
-use crate::utils::{error::RippleError, ws_utils::WebSocketUtils};
-use futures_util::{SinkExt, StreamExt};
+use crate::utils::{
+    error::RippleError,
+    ws_utils::{WebSocketConfigBuilder, WebSocketUtils},
+};
+use futures_util::{Sink, SinkExt, StreamExt};
 use jsonrpsee::core::{server::rpc_module::Methods, RpcResult};
 use log::{debug, error, info, trace, warn};
 use serde::de::DeserializeOwned;
 use serde_json::Value;
 use std::sync::{Arc, RwLock};
-use tokio::sync::{mpsc, oneshot};
+use tokio::sync::{mpsc, mpsc::error::TrySendError, oneshot};
 use tokio::sync::{mpsc::Sender as MSender, oneshot::Sender as OSender};
 use tokio_tungstenite::tungstenite::Message;
 use uuid::Uuid;
 
 use super::service_message::{JsonRpcSuccess, ServiceMessage};
+
+/// Handshake header carrying the credential of the service
+pub const SERVICE_CREDENTIAL_HEADER: &str = "X-Ripple-Service-Credential";
+
 #[derive(Debug, Clone, Default)]
 pub struct ServiceClient {
     pub service_sender: Option<MSender<ServiceMessage>>,
@@ -60,6 +72,10 @@ pub struct ServiceClient {
     pub outbound_service_rx: Arc<RwLock<Option<mpsc::Receiver<ServiceMessage>>>>,
     extn_manifest: ExtnManifest,
     device_manifest: DeviceManifest,
+    service_credential: Option<ServiceCredential>,
+    subscriptions: Arc<RwLock<HashMap<String, ServiceMessage>>>,
+    connection_state: Arc<RwLock<ServiceConnectionState>>,
+    connection_listeners: Arc<RwLock<Vec<MSender<ServiceConnectionState>>>>,
 }
 
 pub struct ServiceClientBuilder {
@@ -134,6 +150,10 @@ impl ServiceClientBuilder {
                 outbound_service_rx: Arc::new(RwLock::new(Some(service_tr))),
                 extn_manifest,
                 device_manifest,
+                service_credential: symbol.get_service_credential(),
+                subscriptions: Arc::new(RwLock::new(HashMap::new())),
+                connection_state: Arc::new(RwLock::new(ServiceConnectionState::default())),
+                connection_listeners: Arc::new(RwLock::new(Vec::new())),
             })
         } else {
             Ok(ServiceClient {
@@ -147,6 +167,10 @@ impl ServiceClientBuilder {
                 outbound_service_rx: Arc::new(RwLock::new(None)),
                 extn_manifest,
                 device_manifest,
+                service_credential: None,
+                subscriptions: Arc::new(RwLock::new(HashMap::new())),
+                connection_state: Arc::new(RwLock::new(ServiceConnectionState::default())),
+                connection_listeners: Arc::new(RwLock::new(Vec::new())),
             })
         }
     }
@@ -223,169 +247,351 @@ impl ServiceClient {
             }
         };
 
-        let mut retry_count = 0u32;
+        let mut backoff = ReconnectBackoff::default();
+        let mut buffer = OutboundBuffer::new(
+            self.get_uint_config(RECONNECT_BUFFER_CONFIG)
+                .map(Duration::from_millis),
+        );
+        let mut reconnect = false;
         loop {
             debug!("Connecting to WebSocket at {}", path);
-            Self::connect_websocket(self, &path, &mut outbound_service_rx, &mut outbound_extn_rx)
+            self.set_connection_state(ServiceConnectionState::Connecting);
+            if self
+                .connect_websocket(
+                    &path,
+                    &mut outbound_service_rx,
+                    &mut outbound_extn_rx,
+                    &mut buffer,
+                    reconnect,
+                )
+                .await
+            {
+                debug!("Initialize Ended Abruptly");
+                backoff.reset();
+                reconnect = true;
+            }
+            self.set_connection_state(ServiceConnectionState::Disconnected);
+            let delay = backoff.next_delay();
+            self.hold_outbound(&mut outbound_service_rx, &mut buffer, delay)
                 .await;
+        }
+    }
 
-            debug!("Initialize Ended Abruptly");
+    /// Waits for the next connection attempt, holding outbound service messages if buffering
+    /// is enabled and failing the ones which are past their deadline.
+    async fn hold_outbound(
+        &self,
+        outbound_service_rx: &mut mpsc::Receiver<ServiceMessage>,
+        buffer: &mut OutboundBuffer,
+        delay: Duration,
+    ) {
+        if !buffer.is_enabled() {
+            tokio::time::sleep(delay).await;
+            return;
+        }
+        let wake = tokio::time::Instant::now() + delay;
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep_until(wake) => break,
+                Some(message) = outbound_service_rx.recv() => {
+                    buffer.push(message);
+                }
+            }
+            for message in buffer.take_expired(Instant::now()) {
+                self.fail_outbound(message);
+            }
+        }
+        for message in buffer.take_expired(Instant::now()) {
+            self.fail_outbound(message);
+        }
+    }
 
-            retry_count = retry_count.saturating_add(1);
+    /// Answers a request which could not be sent, so the caller does not wait for its timeout.
+    fn fail_outbound(&self, message: ServiceMessage) {
+        let Some(id) = get_outbound_request_id(&message) else {
+            return;
+        };
+        warn!(
+            "Service request {} not sent, Ripple main is not reachable",
+            id
+        );
+        if let Some(processor) = self.response_processors.write().unwrap().remove(&id) {
+            let _ = processor.send(ServiceMessage::new_error(
+                -32000,
+                "Ripple main is not reachable".to_string(),
+                None,
+                Id::String(id),
+            ));
         }
     }
 
+    async fn send_outbound<S>(
+        &self,
+        ws_tx: &mut S,
+        message: ServiceMessage,
+        buffer: &mut OutboundBuffer,
+    ) -> bool
+    where
+        S: Sink<Message> + Unpin,
+    {
+        let text: String = message.clone().into();
+        if ws_tx.feed(Message::Text(text)).await.is_err() || ws_tx.flush().await.is_err() {
+            if let Some(message) = buffer.push(message) {
+                self.fail_outbound(message);
+            }
+            return false;
+        }
+        self.track_subscription(&message);
+        true
+    }
+
+    /*
+    Event subscriptions live in the service registry of Ripple main and are dropped with the
+    connection, the requests are kept to subscribe again after a reconnect.
+    */
+    fn track_subscription(&self, message: &ServiceMessage) {
+        let (Some(id), JsonRpcMessage::Request(request)) =
+            (get_outbound_request_id(message), &message.message)
+        else {
+            return;
+        };
+        if !self.event_processors.read().unwrap().contains_key(&id) {
+            return;
+        }
+        let listen = request
+            .params
+            .as_ref()
+            .and_then(|p| p.get("listen"))
+            .and_then(|l| l.as_bool())
+            .unwrap_or(true);
+        let mut subscriptions = self.subscriptions.write().unwrap();
+        if listen {
+            subscriptions.insert(id, message.clone());
+        } else {
+            subscriptions.retain(|_, s| match &s.message {
+                JsonRpcMessage::Request(r) => r.method != request.method,
+                _ => true,
+            });
+        }
+    }
+
+    pub fn get_connection_state(&self) -> ServiceConnectionState {
+        *self.connection_state.read().unwrap()
+    }
+
+    /// Receives every change of the connection to Ripple main.
+    pub fn subscribe_connection_state(&self) -> mpsc::Receiver<ServiceConnectionState> {
+        let (tx, rx) = mpsc::channel(8);
+        self.connection_listeners.write().unwrap().push(tx);
+        rx
+    }
+
+    fn set_connection_state(&self, state: ServiceConnectionState) {
+        {
+            let mut current = self.connection_state.write().unwrap();
+            if *current == state {
+                return;
+            }
+            *current = state;
+        }
+        info!("Service connection state {:?}", state);
+        self.connection_listeners
+            .write()
+            .unwrap()
+            .retain(|tx| !matches!(tx.try_send(state), Err(TrySendError::Closed(_))));
+    }
+
+    /// Returns true if the connection was established.
     async fn connect_websocket(
         &self,
         path: &str,
         outbound_service_rx: &mut mpsc::Receiver<ServiceMessage>,
         outbound_extn_rx: &mut Option<mpsc::Receiver<ApiMessage>>,
-    ) {
-        if let Ok((mut ws_tx, mut ws_rx)) = WebSocketUtils::get_ws_stream(path, None).await {
-            let handle_ws_message = |msg: Message| {
-                if let Message::Text(message) = msg.clone() {
-                    // Service message
-                    debug!("Received Service Message: {:#?}", message);
-                    if let Ok(sm) = serde_json::from_str::<ServiceMessage>(&message) {
-                        match sm.message {
-                            JsonRpcMessage::Request(ref _json_rpc_request) => {
-                                if let Some(sender) = &self.service_sender {
-                                    route_service_message(
-                                        sender,
-                                        &self.service_router.read().unwrap(),
-                                        sm.clone(),
-                                    )
-                                    .unwrap_or_else(|e| {
-                                        error!("Error handling service message: {:?}", e);
-                                    })
-                                } else {
-                                    error!("Service sender is not available");
-                                }
+        buffer: &mut OutboundBuffer,
+        reconnect: bool,
+    ) -> bool {
+        /* a single attempt, the reconnect loop backs off between attempts. Token files are
+        rewritten on every boot of Ripple, so they are read on every connect */
+        let mut config = WebSocketConfigBuilder::default().max_retries(0);
+        if let Some(credential) = self.service_credential.as_ref().and_then(|c| c.read()) {
+            config = config.header(SERVICE_CREDENTIAL_HEADER, credential);
+        }
+        let Ok((mut ws_tx, mut ws_rx)) =
+            WebSocketUtils::get_ws_stream(path, Some(config.build())).await
+        else {
+            return false;
+        };
+        self.set_connection_state(ServiceConnectionState::Connected);
+        if reconnect {
+            let subscriptions: Vec<ServiceMessage> = self
+                .subscriptions
+                .read()
+                .unwrap()
+                .values()
+                .cloned()
+                .collect();
+            info!("Subscribing again to {} events", subscriptions.len());
+            for message in subscriptions {
+                if !self.send_outbound(&mut ws_tx, message, buffer).await {
+                    return true;
+                }
+            }
+        }
+        let (ready, expired) = buffer.take_ready(Instant::now());
+        for message in expired {
+            self.fail_outbound(message);
+        }
+        let mut ready = ready.into_iter();
+        while let Some(message) = ready.next() {
+            if !self.send_outbound(&mut ws_tx, message, buffer).await {
+                for message in ready {
+                    buffer.push(message);
+                }
+                return true;
+            }
+        }
+        let handle_ws_message = |msg: Message| {
+            if let Message::Text(message) = msg.clone() {
+                // Service message
+                debug!("Received Service Message: {:#?}", message);
+                if let Ok(sm) = serde_json::from_str::<ServiceMessage>(&message) {
+                    match sm.message {
+                        JsonRpcMessage::Request(ref _json_rpc_request) => {
+                            if let Some(sender) = &self.service_sender {
+                                route_service_message(
+                                    sender,
+                                    &self.service_router.read().unwrap(),
+                                    sm.clone(),
+                                )
+                                .unwrap_or_else(|e| {
+                                    error!("Error handling service message: {:?}", e);
+                                })
+                            } else {
+                                error!("Service sender is not available");
                             }
-                            JsonRpcMessage::Notification(ref json_rpc_notification) => {
-                                debug!(
-                                    "Received Service Notification: {:?}",
-                                    json_rpc_notification,
-                                );
-                                let params =
-                                    json_rpc_notification.params.clone().unwrap_or_default();
-
-                                let params_map: HashMap<String, Value> =
-                                    serde_json::from_value(params).unwrap_or_default();
-                                if let Some(sender_id) = params_map.get("sender_id") {
-                                    let sender_id = sender_id.clone();
-                                    let sender_id = serde_json::from_value::<String>(sender_id);
-                                    match sender_id {
-                                        Ok(sender_id) => {
-                                            match self
-                                                .event_processors
-                                                .write()
-                                                .unwrap()
-                                                .get(&sender_id)
-                                                .cloned()
-                                            {
-                                                Some(event_processor) => {
-                                                    debug!(
+                        }
+                        JsonRpcMessage::Notification(ref json_rpc_notification) => {
+                            debug!("Received Service Notification: {:?}", json_rpc_notification,);
+                            let params = json_rpc_notification.params.clone().unwrap_or_default();
+
+                            let params_map: HashMap<String, Value> =
+                                serde_json::from_value(params).unwrap_or_default();
+                            if let Some(sender_id) = params_map.get("sender_id") {
+                                let sender_id = sender_id.clone();
+                                let sender_id = serde_json::from_value::<String>(sender_id);
+                                match sender_id {
+                                    Ok(sender_id) => {
+                                        match self
+                                            .event_processors
+                                            .write()
+                                            .unwrap()
+                                            .get(&sender_id)
+                                            .cloned()
+                                        {
+                                            Some(event_processor) => {
+                                                debug!(
                                                         "Sending service notification for sender id: {} event_processors {:?}",
                                                         sender_id, self.event_processors
                                                     );
-                                                    tokio::spawn(async move {
-                                                        if let Err(e) = event_processor.try_send(sm)
-                                                        {
-                                                            error!(
+                                                tokio::spawn(async move {
+                                                    if let Err(e) = event_processor.try_send(sm) {
+                                                        error!(
                                                                 "Failed to send service notification: {:?}",
                                                                 e
                                                             );
-                                                        }
-                                                    });
-                                                }
-                                                None => {
-                                                    warn!("No event processor found for sender id: {}", sender_id);
-                                                }
+                                                    }
+                                                });
+                                            }
+                                            None => {
+                                                warn!(
+                                                    "No event processor found for sender id: {}",
+                                                    sender_id
+                                                );
                                             }
                                         }
-                                        Err(e) => {
-                                            warn!(
-                                                "Fail to parse sender id. Service message: {:?}: {:?}",
-                                                sm, e
-                                            );
-                                        }
                                     }
-                                } else {
-                                    warn!("Service message does not contain sender id {:?}", sm);
+                                    Err(e) => {
+                                        warn!(
+                                            "Fail to parse sender id. Service message: {:?}: {:?}",
+                                            sm, e
+                                        );
+                                    }
                                 }
-                            }
-                            JsonRpcMessage::Success(ref json_rpc_success) => {
-                                debug!(
-                                    "Received Service Success: {:?} context {:?}",
-                                    json_rpc_success,
-                                    sm.context.clone().unwrap()
-                                );
-                                self.send_service_response(sm.clone());
-                            }
-                            JsonRpcMessage::Error(ref json_rpc_error) => {
-                                error!("Received Service Error: {:?}", json_rpc_error);
-                                let mut service_message = sm.clone();
-                                service_message.message =
-                                    JsonRpcMessage::Error(json_rpc_error.clone());
-                                self.send_service_response(service_message.clone());
+                            } else {
+                                warn!("Service message does not contain sender id {:?}", sm);
                             }
                         }
-
-                    // Extension message
-                    } else if let Ok(extn_message) = ExtnMessage::try_from(message) {
-                        if let Some(extn_client) = &self.extn_client {
-                            extn_client.handle_message(extn_message);
-                        } else {
-                            warn!("Received extension message but no extn_client present");
+                        JsonRpcMessage::Success(ref json_rpc_success) => {
+                            debug!(
+                                "Received Service Success: {:?} context {:?}",
+                                json_rpc_success,
+                                sm.context.clone().unwrap()
+                            );
+                            self.send_service_response(sm.clone());
                         }
-                    };
-                } else if let Message::Close(close) = msg {
-                    info!("Received Close {:?} message, exiting initialize", close);
-                    return false;
-                } else {
-                    warn!("Received unexpected message: {:?}", msg);
-                }
-                true
-            };
-            tokio::pin! {
-                let read_pin = ws_rx.next();
+                        JsonRpcMessage::Error(ref json_rpc_error) => {
+                            error!("Received Service Error: {:?}", json_rpc_error);
+                            let mut service_message = sm.clone();
+                            service_message.message = JsonRpcMessage::Error(json_rpc_error.clone());
+                            self.send_service_response(service_message.clone());
+                        }
+                    }
+
+                // Extension message
+                } else if let Ok(extn_message) = ExtnMessage::try_from(message) {
+                    if let Some(extn_client) = &self.extn_client {
+                        extn_client.handle_message(extn_message);
+                    } else {
+                        warn!("Received extension message but no extn_client present");
+                    }
+                };
+            } else if let Message::Close(close) = msg {
+                info!("Received Close {:?} message, exiting initialize", close);
+                return false;
+            } else {
+                warn!("Received unexpected message: {:?}", msg);
             }
+            true
+        };
+        tokio::pin! {
+            let read_pin = ws_rx.next();
+        }
 
-            loop {
-                tokio::select! {
-                    Some(value) = &mut read_pin => {
-                        match value {
-                            Ok(msg) => {
-                                if !handle_ws_message(msg) {
-                                     error!("handle_ws_message failed");
-                                     break;
-                                }
-                            }
-                            Err(e) => {
-                                error!("Service Websocket error on read {:?}", e);
-                                break;
+        loop {
+            tokio::select! {
+                Some(value) = &mut read_pin => {
+                    match value {
+                        Ok(msg) => {
+                            if !handle_ws_message(msg) {
+                                 error!("handle_ws_message failed");
+                                 break;
                             }
                         }
-                    },
-                    Some(request) = async {
-                        match outbound_extn_rx.as_mut() {
-                            Some(rx) => rx.recv().await,
-                            None => None,
+                        Err(e) => {
+                            error!("Service Websocket error on read {:?}", e);
+                            break;
                         }
-                    }, if outbound_extn_rx.is_some() => {
-                        trace!("IEC send: {:?}", request.jsonrpc_msg);
-                        let _feed = ws_tx.feed(Message::Text(request.jsonrpc_msg)).await;
-                        let _flush = ws_tx.flush().await;
                     }
-                    Some(request) = outbound_service_rx.recv() => {
-                        trace!("Service Message send: {:?}", request);
-                        let _feed = ws_tx.feed(Message::Text(request.into())).await;
-                        let _flush = ws_tx.flush().await;
+                },
+                Some(request) = async {
+                    match outbound_extn_rx.as_mut() {
+                        Some(rx) => rx.recv().await,
+                        None => None,
+                    }
+                }, if outbound_extn_rx.is_some() => {
+                    trace!("IEC send: {:?}", request.jsonrpc_msg);
+                    let _feed = ws_tx.feed(Message::Text(request.jsonrpc_msg)).await;
+                    let _flush = ws_tx.flush().await;
+                }
+                Some(request) = outbound_service_rx.recv() => {
+                    trace!("Service Message send: {:?}", request);
+                    if !self.send_outbound(&mut ws_tx, request, buffer).await {
+                        break;
                     }
                 }
             }
         }
+        true
     }
 
     fn send_service_response(&self, sm: ServiceMessage) {
@@ -791,6 +997,10 @@ pub mod tests {
                 event_processors: Arc::new(RwLock::new(HashMap::new())),
                 extn_manifest: ExtnManifest::default(),
                 device_manifest: DeviceManifest::default(),
+                service_credential: None,
+                subscriptions: Arc::new(RwLock::new(HashMap::new())),
+                connection_state: Arc::new(RwLock::new(ServiceConnectionState::default())),
+                connection_listeners: Arc::new(RwLock::new(Vec::new())),
             }
         }
 
@@ -1206,4 +1416,114 @@ pub mod tests {
             .await;
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_connection_state_listeners() {
+        let client = ServiceClient::mock();
+        let mut rx = client.subscribe_connection_state();
+        let closed = client.subscribe_connection_state();
+        drop(closed);
+
+        client.set_connection_state(ServiceConnectionState::Connecting);
+        client.set_connection_state(ServiceConnectionState::Connecting);
+        client.set_connection_state(ServiceConnectionState::Connected);
+        assert_eq!(rx.recv().await, Some(ServiceConnectionState::Connecting));
+        assert_eq!(rx.recv().await, Some(ServiceConnectionState::Connected));
+        assert!(rx.try_recv().is_err());
+        assert_eq!(
+            client.get_connection_state(),
+            ServiceConnectionState::Connected
+        );
+        assert_eq!(client.connection_listeners.read().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_track_subscription_and_fail_outbound() {
+        let client = ServiceClient::mock();
+        let (event_tx, _event_rx) = mpsc::channel(1);
+        let subscribe = |id: &str, listen: bool| {
+            client
+                .event_processors
+                .write()
+                .unwrap()
+                .insert(id.to_owned(), event_tx.clone());
+            ServiceMessage::new_request(
+                "device.onNameChanged".to_owned(),
+                Some(json!({ "listen": listen })),
+                Id::String(id.to_owned()),
+            )
+        };
+        client.track_subscription(&subscribe("a", true));
+        client.track_subscription(&ServiceMessage::new_request(
+            "device.name".to_owned(),
+            None,
+            Id::String("b".to_owned()),
+        ));
+        assert_eq!(client.subscriptions.read().unwrap().len(), 1);
+        client.track_subscription(&subscribe("c", false));
+        assert!(client.subscriptions.read().unwrap().is_empty());
+
+        let (tx, rx) = oneshot::channel();
+        client
+            .response_processors
+            .write()
+            .unwrap()
+            .insert("d".to_owned(), tx);
+        client.fail_outbound(ServiceMessage::new_request(
+            "device.name".to_owned(),
+            None,
+            Id::String("d".to_owned()),
+        ));
+        assert!(matches!(
+            rx.await.unwrap().message,
+            JsonRpcMessage::Error(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_connect_websocket() {
+        use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let path = format!(
+            "ws://{}/?service_handshake=ripple:channel:gateway:service1",
+            listener.local_addr().unwrap()
+        );
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut credential = None;
+            let ws = tokio_tungstenite::accept_hdr_async(stream, |request: &Request, response| {
+                credential = request
+                    .headers()
+                    .get(SERVICE_CREDENTIAL_HEADER)
+                    .map(|v| v.to_str().unwrap().to_owned());
+                Ok::<Response, _>(response)
+            })
+            .await
+            .unwrap();
+            // closing the connection ends the client loop
+            drop(ws);
+            credential
+        });
+
+        let mut client = ServiceClient::mock();
+        client.service_credential = Some(ServiceCredential::Secret("s3cret".to_owned()));
+        let mut states = client.subscribe_connection_state();
+        let (_service_tx, mut service_rx) = mpsc::channel::<ServiceMessage>(1);
+        let connected = tokio::time::timeout(
+            Duration::from_secs(5),
+            client.connect_websocket(
+                &path,
+                &mut service_rx,
+                &mut None,
+                &mut OutboundBuffer::new(None),
+                false,
+            ),
+        )
+        .await
+        .unwrap();
+        assert!(connected);
+        assert_eq!(states.recv().await, Some(ServiceConnectionState::Connected));
+        assert_eq!(server.await.unwrap(), Some("s3cret".to_owned()));
+    }
 }
@@ -0,0 +1,190 @@
+// Copyright 2025 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::service_message::{Id, JsonRpcMessage, ServiceMessage};
+
+pub const RECONNECT_INITIAL_BACKOFF_MS: u64 = 100;
+pub const RECONNECT_MAX_BACKOFF_MS: u64 = 10_000;
+/// Symbol config key for how long outbound messages are held while Ripple main is not reachable
+pub const RECONNECT_BUFFER_CONFIG: &str = "reconnect_buffer_ms";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ServiceConnectionState {
+    #[default]
+    Disconnected,
+    Connecting,
+    Connected,
+}
+
+/// Exponential backoff between connection attempts, the delay is drawn from the upper half of
+/// the current step so services restarted together do not reconnect in lockstep.
+#[derive(Debug, Clone)]
+pub struct ReconnectBackoff {
+    initial: Duration,
+    max: Duration,
+    attempt: u32,
+}
+
+impl Default for ReconnectBackoff {
+    fn default() -> Self {
+        ReconnectBackoff::new(
+            Duration::from_millis(RECONNECT_INITIAL_BACKOFF_MS),
+            Duration::from_millis(RECONNECT_MAX_BACKOFF_MS),
+        )
+    }
+}
+
+impl ReconnectBackoff {
+    pub fn new(initial: Duration, max: Duration) -> Self {
+        ReconnectBackoff {
+            initial,
+            max,
+            attempt: 0,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.attempt = 0;
+    }
+
+    pub fn next_delay(&mut self) -> Duration {
+        let step = self
+            .initial
+            .saturating_mul(2u32.saturating_pow(self.attempt))
+            .min(self.max);
+        self.attempt = self.attempt.saturating_add(1);
+        let half = step.as_millis() as u64 / 2;
+        let jitter = (Uuid::new_v4().as_u128() % (half as u128 + 1)) as u64;
+        Duration::from_millis(step.as_millis() as u64 - half + jitter)
+    }
+}
+
+/*
+Outbound service messages held while Ripple main is not reachable. Without a deadline nothing is
+held and messages wait in the outbound channel as before.
+*/
+#[derive(Debug, Default)]
+pub struct OutboundBuffer {
+    deadline: Option<Duration>,
+    messages: VecDeque<(Instant, ServiceMessage)>,
+}
+
+impl OutboundBuffer {
+    pub fn new(deadline: Option<Duration>) -> Self {
+        OutboundBuffer {
+            deadline,
+            messages: VecDeque::new(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.deadline.is_some()
+    }
+
+    /// Holds a message, returns it back if buffering is disabled.
+    pub fn push(&mut self, message: ServiceMessage) -> Option<ServiceMessage> {
+        match self.deadline {
+            Some(deadline) => {
+                self.messages
+                    .push_back((Instant::now() + deadline, message));
+                None
+            }
+            None => Some(message),
+        }
+    }
+
+    /// Removes the messages which are past their deadline.
+    pub fn take_expired(&mut self, now: Instant) -> Vec<ServiceMessage> {
+        let (expired, held): (Vec<_>, Vec<_>) =
+            self.messages.drain(..).partition(|(due, _)| *due <= now);
+        self.messages = held.into();
+        expired.into_iter().map(|(_, message)| message).collect()
+    }
+
+    /// Removes the messages to send after a reconnect in the order they were held.
+    pub fn take_ready(&mut self, now: Instant) -> (Vec<ServiceMessage>, Vec<ServiceMessage>) {
+        let expired = self.take_expired(now);
+        let ready = self
+            .messages
+            .drain(..)
+            .map(|(_, message)| message)
+            .collect();
+        (ready, expired)
+    }
+}
+
+/// Request id of an outbound service request, set by the client as a string.
+pub fn get_outbound_request_id(message: &ServiceMessage) -> Option<String> {
+    match &message.message {
+        JsonRpcMessage::Request(request) => match &request.id {
+            Id::String(id) => Some(id.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let mut backoff = ReconnectBackoff::new(Duration::from_millis(100), Duration::from_secs(1));
+        let delays: Vec<Duration> = (0..6).map(|_| backoff.next_delay()).collect();
+        let steps = [100, 200, 400, 800, 1000, 1000];
+        for (delay, step) in delays.iter().zip(steps) {
+            assert!(*delay >= Duration::from_millis(step / 2));
+            assert!(*delay <= Duration::from_millis(step));
+        }
+        backoff.reset();
+        assert!(backoff.next_delay() <= Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_outbound_buffer() {
+        let request = |id: &str| {
+            ServiceMessage::new_request("device.name".to_owned(), None, Id::String(id.to_owned()))
+        };
+        let mut disabled = OutboundBuffer::new(None);
+        assert!(disabled.push(request("a")).is_some());
+
+        let mut buffer = OutboundBuffer::new(Some(Duration::from_secs(60)));
+        assert!(buffer.push(request("a")).is_none());
+        assert!(buffer.push(request("b")).is_none());
+        assert!(buffer.take_expired(Instant::now()).is_empty());
+
+        let (ready, expired) = buffer.take_ready(Instant::now());
+        assert!(expired.is_empty());
+        let ids: Vec<Option<String>> = ready.iter().map(get_outbound_request_id).collect();
+        assert_eq!(ids, vec![Some("a".to_owned()), Some("b".to_owned())]);
+
+        buffer.push(request("c"));
+        let (ready, expired) = buffer.take_ready(Instant::now() + Duration::from_secs(61));
+        assert!(ready.is_empty());
+        assert_eq!(get_outbound_request_id(&expired[0]), Some("c".to_owned()));
+    }
+}
@@ -21,7 +21,11 @@ use futures::stream::{SplitSink, SplitStream};
 use futures_util::StreamExt;
 use log::{error, info, warn};
 use tokio::net::TcpStream;
-use tokio_tungstenite::{client_async, tungstenite::Message, WebSocketStream};
+use tokio_tungstenite::{
+    client_async,
+    tungstenite::{client::IntoClientRequest, http::HeaderValue, Message},
+    WebSocketStream,
+};
 
 use super::error::RippleError;
 
@@ -31,12 +35,18 @@ pub struct WebSocketConfig {
     pub alias: Option<String>,
     pub retry: Option<u64>,
     pub fail_after: Option<i32>,
+    /// Gives up after this many retries, independent of the `fail_after` timeout
+    pub max_retries: Option<u32>,
+    /// Extra headers sent with the handshake request
+    pub headers: Vec<(&'static str, String)>,
 }
 
 pub struct WebSocketConfigBuilder {
     alias: Option<String>,
     retry: Option<u64>,
     fail_after: Option<i32>,
+    max_retries: Option<u32>,
+    headers: Vec<(&'static str, String)>,
 }
 
 impl Default for WebSocketConfigBuilder {
@@ -45,6 +55,8 @@ impl Default for WebSocketConfigBuilder {
             alias: None,
             retry: Some(DEFAULT_RETRY_INTERVAL),
             fail_after: None,
+            max_retries: None,
+            headers: Vec::new(),
         }
     }
 }
@@ -65,11 +77,23 @@ impl WebSocketConfigBuilder {
         self
     }
 
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = Some(max_retries);
+        self
+    }
+
+    pub fn header(mut self, name: &'static str, value: String) -> Self {
+        self.headers.push((name, value));
+        self
+    }
+
     pub fn build(self) -> WebSocketConfig {
         WebSocketConfig {
             alias: self.alias,
             retry: self.retry,
             fail_after: self.fail_after,
+            max_retries: self.max_retries,
+            headers: self.headers,
         }
     }
 }
@@ -154,6 +178,7 @@ impl WebSocketUtils {
     async fn connect_tcp_port(
         tcp_port: &str,
         url_path: &str,
+        headers: &[(&'static str, String)],
     ) -> Result<
         (
             SplitSink<WebSocketStream<TcpStream>, Message>,
@@ -165,7 +190,15 @@ impl WebSocketUtils {
             Ok(v) => {
                 // Setup handshake for websocket with the tcp port
                 // Some WS servers lock on to the Port but not setup handshake till they are fully setup
-                if let Ok((stream, _)) = client_async(url_path, v).await {
+                let mut request = url_path
+                    .into_client_request()
+                    .map_err(|_| RippleError::InvalidInput)?;
+                for (name, value) in headers {
+                    let value =
+                        HeaderValue::from_str(value).map_err(|_| RippleError::InvalidInput)?;
+                    request.headers_mut().insert(*name, value);
+                }
+                if let Ok((stream, _)) = client_async(request, v).await {
                     return Ok(stream.split());
                 }
             }
@@ -206,7 +239,7 @@ impl WebSocketUtils {
         let mut delay_duration = tokio::time::Duration::from_millis(retry_every);
 
         loop {
-            match Self::connect_tcp_port(&tcp_port, &url_path).await {
+            match Self::connect_tcp_port(&tcp_port, &url_path, &config.headers).await {
                 Ok(v) => {
                     if retry_count > 0 {
                         info!(
@@ -240,6 +273,14 @@ impl WebSocketUtils {
                 }
             }
 
+            if config.max_retries.is_some_and(|max| retry_count >= max) {
+                warn!(
+                    "Websocket TCP Connection with {} failed after {} retries",
+                    url_path, retry_count
+                );
+                break Err(RippleError::NotAvailable);
+            }
+
             index += 1;
             retry_count += 1;
 
@@ -284,6 +325,8 @@ mod tests {
             alias: None,
             retry: Some(100),
             fail_after: Some(5),
+            max_retries: None,
+            headers: Vec::new(),
         };
         let result = WebSocketUtils::get_ws_stream("invalid_url", Some(config)).await;
         assert!(matches!(result, Err(RippleError::InvalidInput)));
@@ -298,4 +341,18 @@ mod tests {
         let result = WebSocketUtils::get_ws_stream("ws://127.0.0.1:0", Some(config)).await;
         assert!(matches!(result, Err(RippleError::NotAvailable)));
     }
+
+    #[tokio::test]
+    async fn test_get_ws_stream_single_attempt() {
+        let config = WebSocketConfigBuilder::default()
+            .retry(5000)
+            .max_retries(0)
+            .build();
+        let result = tokio::time::timeout(
+            Duration::from_secs(1),
+            WebSocketUtils::get_ws_stream("ws://127.0.0.1:0", Some(config)),
+        )
+        .await;
+        assert!(matches!(result, Ok(Err(RippleError::NotAvailable))));
+    }
 }
@@ -0,0 +1,90 @@
+// Copyright 2025 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+use ripple_sdk::api::{
+    firebolt::fb_capabilities::JSON_RPC_STANDARD_ERROR_METHOD_NOT_FOUND,
+    gateway::rpc_gateway_api::JsonRpcApiResponse,
+};
+use serde_json::Value;
+
+/// Asserts the response is a JSON-RPC error with the given code and returns the error.
+#[track_caller]
+pub fn assert_rpc_error(response: &JsonRpcApiResponse, code: i32) -> &Value {
+    let Some(error) = response.error.as_ref() else {
+        panic!(
+            "expected error {} but got result {:?}",
+            code, response.result
+        );
+    };
+    assert_eq!(
+        error.get("code").and_then(Value::as_i64),
+        Some(code as i64),
+        "unexpected error {}",
+        error
+    );
+    error
+}
+
+/// Asserts the response is a JSON-RPC error with the given code and a message containing `message`.
+#[track_caller]
+pub fn assert_rpc_error_message(response: &JsonRpcApiResponse, code: i32, message: &str) {
+    let error = assert_rpc_error(response, code);
+    let actual = error.get("message").and_then(Value::as_str).unwrap_or("");
+    assert!(
+        actual.contains(message),
+        "expected error message containing {:?} but got {:?}",
+        message,
+        actual
+    );
+}
+
+#[track_caller]
+pub fn assert_method_not_found(response: &JsonRpcApiResponse) {
+    assert_rpc_error(response, JSON_RPC_STANDARD_ERROR_METHOD_NOT_FOUND);
+}
+
+/// Asserts the response is a result equal to `expected`.
+#[track_caller]
+pub fn assert_rpc_result(response: &JsonRpcApiResponse, expected: &Value) {
+    if let Some(error) = &response.error {
+        panic!("expected result {} but got error {}", expected, error);
+    }
+    assert_eq!(response.result.as_ref().unwrap_or(&Value::Null), expected);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn response(value: Value) -> JsonRpcApiResponse {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_assert_rpc_error() {
+        let error = response(
+            json!({"jsonrpc": "2.0", "id": 1, "error": {"code": -32601, "message": "Method not found"}}),
+        );
+        assert_method_not_found(&error);
+        assert_rpc_error_message(&error, -32601, "not found");
+
+        let result = response(json!({"jsonrpc": "2.0", "id": 1, "result": "Living Room"}));
+        assert_rpc_result(&result, &json!("Living Room"));
+        assert!(std::panic::catch_unwind(|| assert_rpc_error(&result, -32601)).is_err());
+    }
+}
@@ -0,0 +1,52 @@
+// Copyright 2025 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+use std::fmt::Display;
+
+use serde_json::Value;
+
+#[derive(Debug, Clone)]
+pub enum HarnessError {
+    /// The gateway or the mock device could not be started
+    BootFailed(String),
+    ConnectFailed(String),
+    /// The connection to the gateway was closed
+    Closed,
+    Timeout,
+    /// The call was answered with a JSON-RPC error
+    Rpc(Value),
+    InvalidResponse(String),
+}
+
+impl std::error::Error for HarnessError {}
+
+impl Display for HarnessError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let msg = match self {
+            Self::BootFailed(reason) => format!("Failed to boot the gateway. Reason: {reason}"),
+            Self::ConnectFailed(reason) => {
+                format!("Failed to connect to the gateway. Reason: {reason}")
+            }
+            Self::Closed => "The gateway closed the connection".to_owned(),
+            Self::Timeout => "Timed out waiting for the gateway".to_owned(),
+            Self::Rpc(error) => format!("The call failed with {error}"),
+            Self::InvalidResponse(reason) => format!("Invalid response. Reason: {reason}"),
+        };
+
+        f.write_str(msg.as_str())
+    }
+}
@@ -0,0 +1,250 @@
+// Copyright 2025 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use ripple_sdk::{
+    api::gateway::rpc_gateway_api::JsonRpcApiResponse,
+    futures::{stream::SplitSink, SinkExt, StreamExt},
+    serde::de::DeserializeOwned,
+    tokio::{
+        self,
+        net::TcpStream,
+        sync::{mpsc, oneshot},
+        task::JoinHandle,
+        time::timeout,
+    },
+    tokio_tungstenite::{tungstenite::Message, WebSocketStream},
+    utils::ws_utils::{WebSocketConfigBuilder, WebSocketUtils},
+};
+use serde_json::{json, Value};
+
+use super::errors::HarnessError;
+
+/// Time to wait for a response or an event unless told otherwise.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+type WsSender = SplitSink<WebSocketStream<TcpStream>, Message>;
+type Pending = Arc<Mutex<HashMap<u64, oneshot::Sender<JsonRpcApiResponse>>>>;
+type Subscriptions = Arc<Mutex<HashMap<u64, mpsc::UnboundedSender<Value>>>>;
+
+/*
+Firebolt websocket client connected to the gateway as an app. Responses are matched to their call
+by id. Events come as responses with the id of the listen call that subscribed to them, so every
+response after the first one for a listen call goes to its subscription.
+*/
+pub struct FireboltClient {
+    app_id: String,
+    sender: tokio::sync::Mutex<WsSender>,
+    next_id: AtomicU64,
+    pending: Pending,
+    subscriptions: Subscriptions,
+    reader: JoinHandle<()>,
+    timeout: Duration,
+}
+
+/// Events of a `listen` call.
+#[derive(Debug)]
+pub struct Subscription {
+    pub event: String,
+    pub id: u64,
+    events: mpsc::UnboundedReceiver<Value>,
+}
+
+impl Subscription {
+    /// Waits for the next event, fails with [HarnessError::Timeout] if none comes in time.
+    pub async fn next_event(&mut self, wait: Duration) -> Result<Value, HarnessError> {
+        match timeout(wait, self.events.recv()).await {
+            Ok(Some(event)) => Ok(event),
+            Ok(None) => Err(HarnessError::Closed),
+            Err(_) => Err(HarnessError::Timeout),
+        }
+    }
+
+    pub async fn next_event_as<T: DeserializeOwned>(
+        &mut self,
+        wait: Duration,
+    ) -> Result<T, HarnessError> {
+        let event = self.next_event(wait).await?;
+        serde_json::from_value(event).map_err(|e| HarnessError::InvalidResponse(e.to_string()))
+    }
+}
+
+impl FireboltClient {
+    /// Connects to a gateway that takes the app id from the query, e.g. the internal gateway.
+    pub async fn connect(gateway: &str, app_id: &str) -> Result<Self, HarnessError> {
+        let config = WebSocketConfigBuilder::default()
+            .retry(50)
+            .fail_after(DEFAULT_TIMEOUT.as_secs() as i32)
+            .build();
+        let endpoint = format!("{}/?appId={}", gateway, app_id);
+        let (sender, mut receiver) = WebSocketUtils::get_ws_stream(&endpoint, Some(config))
+            .await
+            .map_err(|e| HarnessError::ConnectFailed(format!("{:?}", e)))?;
+
+        let pending: Pending = Arc::new(Mutex::new(HashMap::new()));
+        let subscriptions: Subscriptions = Arc::new(Mutex::new(HashMap::new()));
+        let (pending_c, subscriptions_c) = (pending.clone(), subscriptions.clone());
+        let reader = tokio::spawn(async move {
+            while let Some(Ok(message)) = receiver.next().await {
+                if let Message::Text(text) = message {
+                    Self::route(&text, &pending_c, &subscriptions_c);
+                }
+            }
+            // dropping the senders wakes up everyone waiting on this connection
+            pending_c.lock().unwrap().clear();
+            subscriptions_c.lock().unwrap().clear();
+        });
+
+        Ok(Self {
+            app_id: app_id.to_owned(),
+            sender: tokio::sync::Mutex::new(sender),
+            next_id: AtomicU64::new(1),
+            pending,
+            subscriptions,
+            reader,
+            timeout: DEFAULT_TIMEOUT,
+        })
+    }
+
+    /// Time to wait for the responses of this client.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn app_id(&self) -> &str {
+        &self.app_id
+    }
+
+    /// Calls the method, a JSON-RPC error is returned as a response like any result.
+    pub async fn call(
+        &self,
+        method: &str,
+        params: Option<Value>,
+    ) -> Result<JsonRpcApiResponse, HarnessError> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.call_with_id(id, method, params).await
+    }
+
+    /// Calls the method and deserializes the result, a JSON-RPC error fails with [HarnessError::Rpc].
+    pub async fn call_as<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: Option<Value>,
+    ) -> Result<T, HarnessError> {
+        let response = self.call(method, params).await?;
+        if let Some(error) = response.error {
+            return Err(HarnessError::Rpc(error));
+        }
+        serde_json::from_value(response.result.unwrap_or(Value::Null))
+            .map_err(|e| HarnessError::InvalidResponse(e.to_string()))
+    }
+
+    /// Listens to the event, e.g. `device.onNameChanged`.
+    pub async fn subscribe(&self, event: &str) -> Result<Subscription, HarnessError> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.subscriptions.lock().unwrap().insert(id, tx);
+        let response = self
+            .call_with_id(id, event, Some(json!({"listen": true})))
+            .await;
+        match response {
+            Ok(response) if response.error.is_none() => Ok(Subscription {
+                event: event.to_owned(),
+                id,
+                events: rx,
+            }),
+            Ok(response) => {
+                self.subscriptions.lock().unwrap().remove(&id);
+                Err(HarnessError::Rpc(response.error.unwrap_or_default()))
+            }
+            Err(e) => {
+                self.subscriptions.lock().unwrap().remove(&id);
+                Err(e)
+            }
+        }
+    }
+
+    pub async fn unsubscribe(
+        &self,
+        subscription: Subscription,
+    ) -> Result<JsonRpcApiResponse, HarnessError> {
+        self.subscriptions.lock().unwrap().remove(&subscription.id);
+        self.call(&subscription.event, Some(json!({"listen": false})))
+            .await
+    }
+
+    async fn call_with_id(
+        &self,
+        id: u64,
+        method: &str,
+        params: Option<Value>,
+    ) -> Result<JsonRpcApiResponse, HarnessError> {
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().unwrap().insert(id, tx);
+        let mut request = json!({"jsonrpc": "2.0", "id": id, "method": method});
+        if let Some(params) = params {
+            request["params"] = params;
+        }
+        let sent = self
+            .sender
+            .lock()
+            .await
+            .send(Message::Text(request.to_string()))
+            .await;
+        if sent.is_err() {
+            self.pending.lock().unwrap().remove(&id);
+            return Err(HarnessError::Closed);
+        }
+        match timeout(self.timeout, rx).await {
+            Ok(Ok(response)) => Ok(response),
+            Ok(Err(_)) => Err(HarnessError::Closed),
+            Err(_) => {
+                self.pending.lock().unwrap().remove(&id);
+                Err(HarnessError::Timeout)
+            }
+        }
+    }
+
+    fn route(message: &str, pending: &Pending, subscriptions: &Subscriptions) {
+        let Ok(response) = serde_json::from_str::<JsonRpcApiResponse>(message) else {
+            return;
+        };
+        let Some(id) = response.id else {
+            return;
+        };
+        if let Some(tx) = pending.lock().unwrap().remove(&id) {
+            let _ = tx.send(response);
+        } else if let Some(tx) = subscriptions.lock().unwrap().get(&id) {
+            let _ = tx.send(response.result.unwrap_or(Value::Null));
+        }
+    }
+}
+
+impl Drop for FireboltClient {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
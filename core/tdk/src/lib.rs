@@ -16,4 +16,5 @@
 //
 
 pub mod gateway;
+pub mod harness;
 pub mod utils;
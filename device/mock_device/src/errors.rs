@@ -65,6 +65,7 @@ pub enum BootFailedError {
     BadHostname,
     GetPlatformGatewayFailed,
     ServerStartFailed(MockServerWebSocketError),
+    MissingUpstreamUrl,
 }
 
 impl Display for BootFailedError {
@@ -80,6 +81,9 @@ impl Display for BootFailedError {
             Self::ServerStartFailed(err) => {
                 format!("Failed to start the WebSocket server. Error: {err}")
             }
+            Self::MissingUpstreamUrl => {
+                "The record mode needs the `upstream_url` of the Thunder to proxy.".to_owned()
+            }
         };
 
         f.write_str(msg.as_str())
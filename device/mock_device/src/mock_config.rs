@@ -14,13 +14,34 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 //
-
 use serde::Deserialize;
 
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MockMode {
+    /// Answers from the mock data
+    #[default]
+    Mock,
+    /// Proxies the connections to `upstream_url` and records the session to `record_file`
+    Record,
+    /// Answers from the mock data, repeated requests get their responses in recorded order
+    Replay,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct MockConfig {
     pub activate_all_plugins: bool,
     pub stats_file: String,
+    #[serde(default)]
+    pub mode: MockMode,
+    #[serde(default)]
+    pub upstream_url: Option<String>,
+    #[serde(default = "default_record_file")]
+    pub record_file: String,
+}
+
+fn default_record_file() -> String {
+    "mock-recording.json".to_string()
 }
 
 impl Default for MockConfig {
@@ -28,6 +49,9 @@ impl Default for MockConfig {
         Self {
             activate_all_plugins: true,
             stats_file: "stats.json".to_string(),
+            mode: MockMode::default(),
+            upstream_url: None,
+            record_file: default_record_file(),
         }
     }
 }
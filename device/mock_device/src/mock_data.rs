@@ -23,6 +23,7 @@ use std::{collections::HashMap, fmt::Display, sync::Arc};
 use crate::{
     errors::{LoadMockDataError, MockDeviceError},
     mock_server::{MessagePayload, PayloadType, PayloadTypeError},
+    mock_state::{contains_value, set_pointer, MockState, StateAccess, StateBinding},
     mock_web_socket_server::{MockWebSocketServer, ThunderRegisterParams},
 };
 
@@ -39,12 +40,27 @@ impl MockDeviceState {
     }
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParamMatch {
+    /// The request params must equal `params`
+    #[default]
+    Exact,
+    /// The request params must contain the fields of `params`
+    Partial,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct ParamResponse {
     pub params: Option<Value>,
     pub result: Option<Value>,
     pub error: Option<Value>,
     pub events: Option<Vec<EventValue>>,
+    #[serde(default, rename = "match")]
+    pub param_match: ParamMatch,
+    /// Values the request params must have at the given JSON pointers
+    pub pointers: Option<HashMap<String, Value>>,
+    pub state: Option<StateBinding>,
 }
 
 #[derive(Debug)]
@@ -55,15 +71,73 @@ pub struct ResponseSink {
 
 impl ParamResponse {
     pub fn get_key(&self, key: &Value) -> Option<Self> {
-        match &self.params {
-            Some(v) => {
-                if v.eq(key) {
-                    return Some(self.clone());
+        let params_match = match &self.params {
+            Some(v) => match self.param_match {
+                ParamMatch::Exact => v.eq(key),
+                ParamMatch::Partial => contains_value(v, key),
+            },
+            None => true,
+        };
+        let pointers_match = self.pointers.as_ref().map_or(true, |pointers| {
+            pointers
+                .iter()
+                .all(|(pointer, value)| key.pointer(pointer) == Some(value))
+        });
+        if params_match && pointers_match {
+            return Some(self.clone());
+        }
+        None
+    }
+
+    /// Resolves a stateful response for the request params. A setter stores its value and
+    /// returns the change events for the registered listeners, a getter gets the stored value
+    /// in its result.
+    pub fn with_state(
+        &self,
+        params: Option<&Value>,
+        state: &mut MockState,
+    ) -> (Self, Vec<ResponseSink>) {
+        let binding = match &self.state {
+            Some(binding) => binding,
+            None => return (self.clone(), Vec::new()),
+        };
+        let mut response = self.clone();
+        let mut events = Vec::new();
+        match binding.access {
+            StateAccess::Get => {
+                if let Some(value) = state.get(&binding.key) {
+                    response.result = Some(match &binding.pointer {
+                        Some(pointer) => {
+                            let mut result = self.result.clone().unwrap_or(json!({}));
+                            set_pointer(&mut result, pointer, value.clone());
+                            result
+                        }
+                        None => value.clone(),
+                    });
+                }
+            }
+            StateAccess::Set => {
+                let value = match &binding.pointer {
+                    Some(pointer) => params.and_then(|p| p.pointer(pointer)).cloned(),
+                    None => params.cloned(),
+                };
+                let Some(value) = value else {
+                    error!("No value in {:?} for state {}", params, binding.key);
+                    return (response, events);
+                };
+                if state.set(&binding.key, value.clone()) {
+                    if let Some(event) = &binding.event {
+                        for method in state.get_listeners(&event.name) {
+                            events.push(ResponseSink {
+                                delay: 0,
+                                data: json!({"jsonrpc": "2.0", "method": method, "params": event.get_params(&value)}),
+                            });
+                        }
+                    }
                 }
-                None
             }
-            None => Some(self.clone()),
         }
+        (response, events)
     }
     pub fn get_notification_id(&self) -> Option<String> {
         if let Some(params) = &self.params {
@@ -224,6 +298,7 @@ mod tests {
             error: None,
             events: None,
             params: None,
+            ..Default::default()
         };
         assert!(response.get_key(&Value::Null).is_some());
         let response = ParamResponse {
@@ -231,6 +306,7 @@ mod tests {
             error: None,
             events: None,
             params: Some(Value::String("Some".to_owned())),
+            ..Default::default()
         };
         assert!(response.get_key(&Value::Null).is_none());
         assert!(response
@@ -245,6 +321,7 @@ mod tests {
             error: None,
             events: None,
             params: None,
+            ..Default::default()
         };
         assert!(response.get_notification_id().is_none());
         let response = ParamResponse {
@@ -252,6 +329,7 @@ mod tests {
             error: None,
             events: None,
             params: Some(Value::String("Some".to_owned())),
+            ..Default::default()
         };
         assert!(response.get_notification_id().is_none());
 
@@ -263,6 +341,7 @@ mod tests {
                 "event": "SomeEvent",
                 "id": "SomeId"
             })),
+            ..Default::default()
         };
 
         assert!(response
@@ -278,6 +357,7 @@ mod tests {
             error: Some(json!({"code": -32010, "message": "Error Message"})),
             events: None,
             params: None,
+            ..Default::default()
         };
         let response = pr.get_all(Some(0), None)[0]
             .data
@@ -297,6 +377,7 @@ mod tests {
                 data: json!({"event": 0}),
             }]),
             params: None,
+            ..Default::default()
         };
 
         let response = pr.get_all(Some(0), None)[0]
@@ -329,4 +410,66 @@ mod tests {
             panic!("Failure in get all with thunder register params")
         }
     }
+
+    #[test]
+    fn test_param_response_partial_match() {
+        let response: ParamResponse = serde_json::from_value(json!({
+            "params": {"audioPort": "HDMI0"},
+            "match": "partial",
+            "pointers": {"/volume/level": 10},
+            "result": {"success": true}
+        }))
+        .unwrap();
+        assert!(response
+            .get_key(&json!({"audioPort": "HDMI0", "volume": {"level": 10}}))
+            .is_some());
+        assert!(response
+            .get_key(&json!({"audioPort": "HDMI0", "volume": {"level": 11}}))
+            .is_none());
+        assert!(response
+            .get_key(&json!({"audioPort": "SPDIF0", "volume": {"level": 10}}))
+            .is_none());
+    }
+
+    #[test]
+    fn test_param_response_with_state() {
+        let setter: ParamResponse = serde_json::from_value(json!({
+            "result": {"success": true},
+            "state": {
+                "key": "volume",
+                "access": "set",
+                "pointer": "/volumeLevel",
+                "event": {"name": "onVolumeChanged", "params": {"audioPort": "HDMI0"}, "pointer": "/volumeLevel"}
+            }
+        }))
+        .unwrap();
+        let getter: ParamResponse = serde_json::from_value(json!({
+            "result": {"volumeLevel": 50, "success": true},
+            "state": {"key": "volume", "access": "get", "pointer": "/volumeLevel"}
+        }))
+        .unwrap();
+        let mut state = MockState::default();
+
+        let (response, _) = getter.with_state(None, &mut state);
+        assert_eq!(response.result, getter.result);
+
+        state.register(&ThunderRegisterParams {
+            event: "onVolumeChanged".to_owned(),
+            id: "client.events".to_owned(),
+        });
+        let params = json!({"audioPort": "HDMI0", "volumeLevel": 20});
+        let (_, events) = setter.with_state(Some(&params), &mut state);
+        assert_eq!(
+            events[0].data,
+            json!({"jsonrpc": "2.0", "method": "client.events.onVolumeChanged", "params": {"audioPort": "HDMI0", "volumeLevel": 20}})
+        );
+        let (_, events) = setter.with_state(Some(&params), &mut state);
+        assert!(events.is_empty());
+
+        let (response, _) = getter.with_state(None, &mut state);
+        assert_eq!(
+            response.result,
+            Some(json!({"volumeLevel": 20, "success": true}))
+        );
+    }
 }
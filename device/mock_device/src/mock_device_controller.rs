@@ -20,8 +20,11 @@ use std::fmt::Display;
 use crate::{
     mock_data::MockData,
     mock_data::MockDeviceState,
+    mock_faults::FaultProfiles,
     mock_server::{
-        AddRequestResponseResponse, EmitEventParams, EmitEventResponse, RemoveRequestResponse,
+        AddRequestResponseResponse, ClearFaultsParams, EmitEventParams, EmitEventResponse,
+        FaultsResponse, RemoveRequestResponse, ResetStateResponse, SetPluginStateParams,
+        SetPluginStateResponse,
     },
 };
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
@@ -79,6 +82,30 @@ pub trait MockDeviceController {
         ctx: CallContext,
         req: MockData,
     ) -> RpcResult<ExtnProviderResponse>;
+
+    #[method(name = "mockdevice.resetState")]
+    async fn reset_state(&self, ctx: CallContext) -> RpcResult<ExtnProviderResponse>;
+
+    #[method(name = "mockdevice.setFaults")]
+    async fn set_faults(
+        &self,
+        ctx: CallContext,
+        req: FaultProfiles,
+    ) -> RpcResult<ExtnProviderResponse>;
+
+    #[method(name = "mockdevice.clearFaults")]
+    async fn clear_faults(
+        &self,
+        ctx: CallContext,
+        req: ClearFaultsParams,
+    ) -> RpcResult<ExtnProviderResponse>;
+
+    #[method(name = "mockdevice.setPluginState")]
+    async fn set_plugin_state(
+        &self,
+        ctx: CallContext,
+        req: SetPluginStateParams,
+    ) -> RpcResult<ExtnProviderResponse>;
 }
 
 pub struct MockDeviceController {
@@ -158,4 +185,47 @@ impl MockDeviceControllerServer for MockDeviceController {
             value: serde_json::to_value(EmitEventResponse { success: true }).unwrap(),
         })
     }
+
+    async fn reset_state(&self, _ctx: CallContext) -> RpcResult<ExtnProviderResponse> {
+        self.state.server.reset_state();
+        Ok(ExtnProviderResponse {
+            value: serde_json::to_value(ResetStateResponse { success: true }).unwrap(),
+        })
+    }
+
+    async fn set_faults(
+        &self,
+        _ctx: CallContext,
+        req: FaultProfiles,
+    ) -> RpcResult<ExtnProviderResponse> {
+        self.state.server.set_fault_profiles(req);
+        Ok(ExtnProviderResponse {
+            value: serde_json::to_value(FaultsResponse { success: true }).unwrap(),
+        })
+    }
+
+    async fn clear_faults(
+        &self,
+        _ctx: CallContext,
+        req: ClearFaultsParams,
+    ) -> RpcResult<ExtnProviderResponse> {
+        self.state.server.clear_fault_profiles(req.methods);
+        Ok(ExtnProviderResponse {
+            value: serde_json::to_value(FaultsResponse { success: true }).unwrap(),
+        })
+    }
+
+    async fn set_plugin_state(
+        &self,
+        _ctx: CallContext,
+        req: SetPluginStateParams,
+    ) -> RpcResult<ExtnProviderResponse> {
+        self.state
+            .server
+            .set_plugin_state(&req.callsign, &req.state)
+            .await;
+        Ok(ExtnProviderResponse {
+            value: serde_json::to_value(SetPluginStateResponse { success: true }).unwrap(),
+        })
+    }
 }
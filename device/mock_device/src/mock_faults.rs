@@ -0,0 +1,239 @@
+// Copyright 2025 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::mock_data::ResponseSink;
+
+pub type FaultProfiles = HashMap<String, FaultProfile>;
+
+/*
+Faults injected into the responses of a method. Everything is deterministic so a test sees the
+same behaviour on every run: the random latency comes from a generator seeded with `seed` and
+`error_rate` fails an evenly spread share of the calls, e.g. every second call for 0.5.
+*/
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct FaultProfile {
+    /// Delay before the response in milliseconds
+    pub latency: Option<u64>,
+    /// Random delay of up to this many milliseconds added to the latency
+    pub jitter: Option<u64>,
+    pub seed: Option<u64>,
+    /// Share of the calls answered with `error`, from 0.0 to 1.0
+    pub error_rate: Option<f64>,
+    pub error: Option<Value>,
+    /// Calls answered before the next one drops the connection
+    pub drop_after: Option<u64>,
+    #[serde(default)]
+    pub no_response: bool,
+    /// Sends the response cut in half so it is not valid JSON
+    #[serde(default)]
+    pub malformed: bool,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Fault {
+    pub latency: u64,
+    pub error: Option<Value>,
+    pub drop_connection: bool,
+    pub no_response: bool,
+    pub malformed: bool,
+}
+
+#[derive(Debug)]
+struct MethodFaults {
+    profile: FaultProfile,
+    calls: u64,
+    since_drop: u64,
+    rng: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct FaultState {
+    methods: HashMap<String, MethodFaults>,
+}
+
+impl Fault {
+    /// Applies the error and latency to the responses of a call.
+    pub fn apply(&self, mut responses: Vec<ResponseSink>) -> Vec<ResponseSink> {
+        if let Some(error) = &self.error {
+            let id = responses
+                .first()
+                .and_then(|r| r.data.get("id").cloned())
+                .unwrap_or(Value::Null);
+            responses = vec![ResponseSink {
+                delay: 0,
+                data: json!({"jsonrpc": "2.0", "id": id, "error": error}),
+            }];
+        }
+        if let Some(response) = responses.first_mut() {
+            response.delay += self.latency;
+        }
+        responses
+    }
+}
+
+impl FaultState {
+    pub fn set_profiles(&mut self, profiles: FaultProfiles) {
+        for (method, profile) in profiles {
+            let rng = profile.seed.unwrap_or(0);
+            self.methods.insert(
+                method.to_lowercase(),
+                MethodFaults {
+                    profile,
+                    calls: 0,
+                    since_drop: 0,
+                    rng,
+                },
+            );
+        }
+    }
+
+    /// Removes the profiles of the given methods, or all of them.
+    pub fn clear_profiles(&mut self, methods: Option<Vec<String>>) {
+        match methods {
+            Some(methods) => {
+                for method in methods {
+                    self.methods.remove(&method.to_lowercase());
+                }
+            }
+            None => self.methods.clear(),
+        }
+    }
+
+    /// Faults for the next call of the method, None if it has no profile.
+    pub fn next_fault(&mut self, method: &str) -> Option<Fault> {
+        let faults = self.methods.get_mut(&method.to_lowercase())?;
+        let profile = &faults.profile;
+        faults.calls += 1;
+
+        if let Some(drop_after) = profile.drop_after {
+            if faults.since_drop >= drop_after {
+                faults.since_drop = 0;
+                return Some(Fault {
+                    drop_connection: true,
+                    ..Default::default()
+                });
+            }
+            faults.since_drop += 1;
+        }
+
+        let mut latency = profile.latency.unwrap_or(0);
+        if let Some(jitter) = profile.jitter {
+            faults.rng = splitmix64(faults.rng);
+            latency += faults.rng % (jitter + 1);
+        }
+
+        let rate = profile.error_rate.unwrap_or(0.0).clamp(0.0, 1.0);
+        let failed =
+            (faults.calls as f64 * rate).floor() > ((faults.calls - 1) as f64 * rate).floor();
+        let error = failed.then(|| {
+            profile
+                .error
+                .clone()
+                .unwrap_or(json!({"code": -32603, "message": "ERROR_GENERAL"}))
+        });
+
+        Some(Fault {
+            latency,
+            error,
+            drop_connection: false,
+            no_response: profile.no_response,
+            malformed: profile.malformed,
+        })
+    }
+}
+
+fn splitmix64(state: u64) -> u64 {
+    let mut z = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(profile: FaultProfile) -> FaultState {
+        let mut state = FaultState::default();
+        state.set_profiles(HashMap::from([(
+            "org.rdk.System.1.getSerialNumber".to_owned(),
+            profile,
+        )]));
+        state
+    }
+
+    #[test]
+    fn test_no_profile() {
+        let mut state = FaultState::default();
+        assert!(state
+            .next_fault("org.rdk.System.1.getSerialNumber")
+            .is_none());
+    }
+
+    #[test]
+    fn test_error_rate_and_drop() {
+        let mut state = state(FaultProfile {
+            error_rate: Some(0.5),
+            drop_after: Some(3),
+            ..Default::default()
+        });
+        let faults: Vec<Fault> = (0..5)
+            .map(|_| {
+                state
+                    .next_fault("org.rdk.system.1.getserialnumber")
+                    .unwrap()
+            })
+            .collect();
+        let errors: Vec<bool> = faults.iter().map(|f| f.error.is_some()).collect();
+        assert_eq!(errors, vec![false, true, false, false, false]);
+        assert!(faults[3].drop_connection);
+        assert!(!faults[4].drop_connection);
+
+        state.clear_profiles(Some(vec!["org.rdk.System.1.getSerialNumber".to_owned()]));
+        assert!(state
+            .next_fault("org.rdk.System.1.getSerialNumber")
+            .is_none());
+    }
+
+    #[test]
+    fn test_latency_is_deterministic() {
+        let profile = FaultProfile {
+            latency: Some(100),
+            jitter: Some(50),
+            seed: Some(7),
+            ..Default::default()
+        };
+        let latencies = |mut state: FaultState| -> Vec<u64> {
+            (0..10)
+                .map(|_| {
+                    state
+                        .next_fault("org.rdk.System.1.getSerialNumber")
+                        .unwrap()
+                        .latency
+                })
+                .collect()
+        };
+        let first = latencies(state(profile.clone()));
+        assert!(first.iter().all(|l| (100..=150).contains(l)));
+        assert_eq!(first, latencies(state(profile)));
+    }
+}
@@ -0,0 +1,282 @@
+// Copyright 2025 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufWriter, Write},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use ripple_sdk::{
+    api::gateway::rpc_gateway_api::JsonRpcApiRequest,
+    log::{debug, error},
+};
+use serde_json::{json, Value};
+use tokio::{
+    sync::{mpsc, oneshot},
+    time::MissedTickBehavior,
+};
+
+use crate::{
+    mock_data::{EventValue, MockData, ParamResponse},
+    mock_web_socket_server::ThunderRegisterParams,
+};
+
+/// Changes to the recording are written out at most this often
+const RECORDING_FLUSH_INTERVAL: Duration = Duration::from_millis(500);
+
+/*
+Records the traffic proxied to a real Thunder into the mock data format. Every response is added
+to the responses of its method, events are added to the register call they were subscribed with
+and keep the time since the previous message of that subscription as their delay. A writer task
+writes the recording out periodically and whenever a proxied connection closes, so it survives
+the process being stopped without slowing down the proxied traffic.
+*/
+#[derive(Debug)]
+pub struct Recorder {
+    data: Arc<Mutex<MockData>>,
+    dirty: Arc<AtomicBool>,
+    flush_tx: mpsc::Sender<oneshot::Sender<()>>,
+}
+
+impl Recorder {
+    /// Starts the writer task, so it has to be called within a tokio runtime.
+    pub fn new(file: String) -> Self {
+        let data = Arc::new(Mutex::new(MockData::new()));
+        let dirty = Arc::new(AtomicBool::new(false));
+        let (flush_tx, flush_rx) = mpsc::channel(8);
+        tokio::spawn(Self::writer(file, data.clone(), dirty.clone(), flush_rx));
+        Self {
+            data,
+            dirty,
+            flush_tx,
+        }
+    }
+
+    pub fn session(self: &Arc<Self>) -> RecordingSession {
+        RecordingSession {
+            recorder: self.clone(),
+            pending: HashMap::new(),
+            subscriptions: HashMap::new(),
+        }
+    }
+
+    pub fn get_data(&self) -> MockData {
+        self.data.lock().unwrap().clone()
+    }
+
+    /// Writes out the pending changes and waits until they are on disk.
+    pub async fn flush(&self) {
+        let (tx, rx) = oneshot::channel();
+        if self.flush_tx.send(tx).await.is_ok() {
+            let _ = rx.await;
+        }
+    }
+
+    fn add_response(&self, method: &str, response: ParamResponse) -> usize {
+        let mut data = self.data.lock().unwrap();
+        let responses = data.entry(method.to_owned()).or_default();
+        responses.push(response);
+        self.dirty.store(true, Ordering::Release);
+        responses.len() - 1
+    }
+
+    fn add_event(&self, method: &str, index: usize, event: EventValue) {
+        let mut data = self.data.lock().unwrap();
+        if let Some(response) = data.get_mut(method).and_then(|r| r.get_mut(index)) {
+            response.events.get_or_insert_with(Vec::new).push(event);
+            self.dirty.store(true, Ordering::Release);
+        }
+    }
+
+    async fn writer(
+        file: String,
+        data: Arc<Mutex<MockData>>,
+        dirty: Arc<AtomicBool>,
+        mut flush_rx: mpsc::Receiver<oneshot::Sender<()>>,
+    ) {
+        let mut interval = tokio::time::interval(RECORDING_FLUSH_INTERVAL);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                _ = interval.tick() => Self::write_pending(&file, &data, &dirty).await,
+                request = flush_rx.recv() => {
+                    Self::write_pending(&file, &data, &dirty).await;
+                    match request {
+                        Some(tx) => {
+                            let _ = tx.send(());
+                        }
+                        // the recorder was dropped
+                        None => break,
+                    }
+                }
+            }
+        }
+    }
+
+    async fn write_pending(file: &str, data: &Mutex<MockData>, dirty: &AtomicBool) {
+        if !dirty.swap(false, Ordering::AcqRel) {
+            return;
+        }
+        let snapshot = data.lock().unwrap().clone();
+        let file = file.to_owned();
+        let _ = tokio::task::spawn_blocking(move || Self::write(&file, &snapshot)).await;
+    }
+
+    fn write(file: &str, data: &MockData) {
+        let mut writer = match File::create(file) {
+            Ok(file) => BufWriter::new(file),
+            Err(e) => {
+                error!("Failed to write recording {}: {:?}", file, e);
+                return;
+            }
+        };
+        let _ = serde_json::to_writer_pretty(&mut writer, data);
+        let _ = writer.flush();
+    }
+}
+
+#[derive(Debug)]
+struct Subscription {
+    method: String,
+    index: usize,
+    last: Instant,
+}
+
+/// Recording state of a single proxied connection.
+#[derive(Debug)]
+pub struct RecordingSession {
+    recorder: Arc<Recorder>,
+    // request id to method and params
+    pending: HashMap<u64, (String, Option<Value>)>,
+    // notification method to the register call it belongs to
+    subscriptions: HashMap<String, Subscription>,
+}
+
+impl RecordingSession {
+    pub fn on_request(&mut self, message: &str) {
+        if let Ok(request) = serde_json::from_str::<JsonRpcApiRequest>(message) {
+            if let Some(id) = request.id {
+                self.pending.insert(id, (request.method, request.params));
+            }
+        }
+    }
+
+    pub fn on_response(&mut self, message: &str) {
+        let Ok(value) = serde_json::from_str::<Value>(message) else {
+            return;
+        };
+        if let Some(id) = value.get("id").and_then(|id| id.as_u64()) {
+            if let Some((method, params)) = self.pending.remove(&id) {
+                self.record_response(method, params, &value);
+            }
+        } else if let Some(method) = value.get("method").and_then(|m| m.as_str()) {
+            self.record_event(method, &value);
+        }
+    }
+
+    fn record_response(&mut self, method: String, params: Option<Value>, value: &Value) {
+        let register = params
+            .clone()
+            .filter(|_| method.ends_with(".register"))
+            .and_then(|p| serde_json::from_value::<ThunderRegisterParams>(p).ok());
+        let response = ParamResponse {
+            // register calls are looked up by the event only
+            params: match &register {
+                Some(r) => Some(json!({"event": r.event})),
+                None => params,
+            },
+            result: value.get("result").cloned(),
+            error: value.get("error").cloned(),
+            ..Default::default()
+        };
+        let index = self.recorder.add_response(&method, response);
+        if let Some(r) = register {
+            self.subscriptions.insert(
+                format!("{}.{}", r.id, r.event),
+                Subscription {
+                    method,
+                    index,
+                    last: Instant::now(),
+                },
+            );
+        }
+    }
+
+    fn record_event(&mut self, notification: &str, value: &Value) {
+        let Some(subscription) = self.subscriptions.get_mut(notification) else {
+            debug!("Not recording event {} without a register", notification);
+            return;
+        };
+        let now = Instant::now();
+        let event = EventValue {
+            delay: Some(now.duration_since(subscription.last).as_millis() as u64),
+            data: value.get("params").cloned().unwrap_or(Value::Null),
+        };
+        subscription.last = now;
+        self.recorder
+            .add_event(&subscription.method, subscription.index, event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_record_session() {
+        let file = std::env::temp_dir().join(format!("mock-recording-{}.json", std::process::id()));
+        let recorder = Arc::new(Recorder::new(file.to_string_lossy().to_string()));
+        let mut session = recorder.session();
+
+        session
+            .on_request(r#"{"jsonrpc":"2.0","id":1,"method":"org.rdk.System.1.getTimeZoneDST"}"#);
+        session.on_request(r#"{"jsonrpc":"2.0","id":2,"method":"org.rdk.System.register","params":{"event":"onTimeZoneDSTChanged","id":"client.org.rdk.System.events"}}"#);
+        session.on_response(r#"{"jsonrpc":"2.0","id":2,"result":0}"#);
+        session.on_response(
+            r#"{"jsonrpc":"2.0","id":1,"result":{"timeZone":"America/New_York","success":true}}"#,
+        );
+        session.on_response(r#"{"jsonrpc":"2.0","method":"client.org.rdk.System.events.onTimeZoneDSTChanged","params":{"newTimeZone":"Europe/London"}}"#);
+        session.on_response(
+            r#"{"jsonrpc":"2.0","method":"client.other.events.onUnknown","params":{}}"#,
+        );
+
+        let data = recorder.get_data();
+        assert_eq!(
+            data["org.rdk.System.1.getTimeZoneDST"][0].result,
+            Some(json!({"timeZone":"America/New_York","success":true}))
+        );
+        let register = &data["org.rdk.System.register"][0];
+        assert_eq!(
+            register.params,
+            Some(json!({"event": "onTimeZoneDSTChanged"}))
+        );
+        let events = register.events.as_ref().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].data, json!({"newTimeZone":"Europe/London"}));
+
+        recorder.flush().await;
+        let written: MockData = serde_json::from_reader(File::open(&file).unwrap()).unwrap();
+        assert_eq!(written.len(), 2);
+        let _ = std::fs::remove_file(file);
+    }
+}
@@ -139,6 +139,34 @@ pub struct EmitEventResponse {
     pub success: bool,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ResetStateResponse {
+    pub success: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct FaultsResponse {
+    pub success: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ClearFaultsParams {
+    /// Methods to clear the fault profiles of, all of them if not given
+    pub methods: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SetPluginStateParams {
+    pub callsign: String,
+    /// Thunder plugin state e.g. `activated` or `deactivated`
+    pub state: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SetPluginStateResponse {
+    pub success: bool,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
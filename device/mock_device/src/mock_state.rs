@@ -0,0 +1,205 @@
+// Copyright 2025 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+use std::collections::{BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::mock_web_socket_server::ThunderRegisterParams;
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StateAccess {
+    Get,
+    Set,
+}
+
+/*
+Couples a mock response to a value kept by the mock device. Setters store the value found at
+`pointer` in the request params, or the whole params, and getters return it at `pointer` in their
+result, or as the whole result. A getter keeps its canned result until a setter stored a value.
+*/
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct StateBinding {
+    pub key: String,
+    pub access: StateAccess,
+    pub pointer: Option<String>,
+    /// Sent to the registered listeners when a setter changes the value
+    pub event: Option<StateEvent>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct StateEvent {
+    /// Thunder event name, as given in the register params
+    pub name: String,
+    /// Event params the value is set into at `pointer`, the value itself is sent without them
+    pub params: Option<Value>,
+    pub pointer: Option<String>,
+}
+
+impl StateEvent {
+    pub fn get_params(&self, value: &Value) -> Value {
+        match (&self.params, &self.pointer) {
+            (Some(params), Some(pointer)) => {
+                let mut params = params.clone();
+                set_pointer(&mut params, pointer, value.clone());
+                params
+            }
+            (Some(params), None) => params.clone(),
+            (None, _) => value.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct MockState {
+    values: HashMap<String, Value>,
+    // event name to the notification methods registered for it
+    listeners: HashMap<String, BTreeSet<String>>,
+    // plugin states set by the tests, by callsign
+    plugins: HashMap<String, String>,
+}
+
+impl MockState {
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.values.get(key)
+    }
+
+    /// Stores the value, returns true if it changed.
+    pub fn set(&mut self, key: &str, value: Value) -> bool {
+        self.values.insert(key.to_owned(), value.clone()) != Some(value)
+    }
+
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+
+    pub fn register(&mut self, params: &ThunderRegisterParams) {
+        self.listeners
+            .entry(params.event.clone())
+            .or_default()
+            .insert(format!("{}.{}", params.id, params.event));
+    }
+
+    pub fn unregister(&mut self, params: &ThunderRegisterParams) {
+        if let Some(methods) = self.listeners.get_mut(&params.event) {
+            methods.remove(&format!("{}.{}", params.id, params.event));
+        }
+    }
+
+    pub fn set_plugin_state(&mut self, callsign: &str, state: &str) {
+        self.plugins
+            .insert(callsign.to_owned(), state.to_lowercase());
+    }
+
+    pub fn get_plugin_state(&self, callsign: &str) -> Option<&String> {
+        self.plugins.get(callsign)
+    }
+
+    pub fn get_listeners(&self, event: &str) -> Vec<String> {
+        self.listeners
+            .get(event)
+            .map(|methods| methods.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Sets the value at a JSON pointer, creating the objects on the way.
+pub fn set_pointer(target: &mut Value, pointer: &str, value: Value) {
+    let mut current = target;
+    for token in pointer.split('/').skip(1) {
+        let token = token.replace("~1", "/").replace("~0", "~");
+        if !current.is_object() {
+            *current = Value::Object(Map::new());
+        }
+        current = current
+            .as_object_mut()
+            .unwrap()
+            .entry(token)
+            .or_insert(Value::Null);
+    }
+    *current = value;
+}
+
+/// True if every field of `expected` is present in `actual` with a matching value.
+pub fn contains_value(expected: &Value, actual: &Value) -> bool {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => expected
+            .iter()
+            .all(|(k, v)| actual.get(k).map(|a| contains_value(v, a)).unwrap_or(false)),
+        _ => expected.eq(actual),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_set_pointer() {
+        let mut value = json!({"success": true});
+        set_pointer(&mut value, "/volume/level", json!(10));
+        assert_eq!(value, json!({"success": true, "volume": {"level": 10}}));
+        set_pointer(&mut value, "", json!(1));
+        assert_eq!(value, json!(1));
+    }
+
+    #[test]
+    fn test_contains_value() {
+        let actual = json!({"audioPort": "HDMI0", "volume": {"level": 10, "muted": false}});
+        assert!(contains_value(&json!({"audioPort": "HDMI0"}), &actual));
+        assert!(contains_value(&json!({"volume": {"level": 10}}), &actual));
+        assert!(!contains_value(&json!({"volume": {"level": 11}}), &actual));
+        assert!(!contains_value(&json!({"port": "HDMI0"}), &actual));
+        assert!(!contains_value(
+            &json!({"audioPort": "HDMI0"}),
+            &json!("HDMI0")
+        ));
+    }
+
+    #[test]
+    fn test_state_and_listeners() {
+        let mut state = MockState::default();
+        assert!(state.set("volume", json!(10)));
+        assert!(!state.set("volume", json!(10)));
+        assert_eq!(state.get("volume"), Some(&json!(10)));
+
+        let params = ThunderRegisterParams {
+            event: "onVolumeChanged".to_owned(),
+            id: "client.org.rdk.DisplaySettings.events".to_owned(),
+        };
+        state.register(&params);
+        assert_eq!(
+            state.get_listeners("onVolumeChanged"),
+            vec!["client.org.rdk.DisplaySettings.events.onVolumeChanged".to_owned()]
+        );
+        state.unregister(&params);
+        assert!(state.get_listeners("onVolumeChanged").is_empty());
+
+        let event = StateEvent {
+            name: "onVolumeChanged".to_owned(),
+            params: Some(json!({"audioPort": "HDMI0"})),
+            pointer: Some("/volumeLevel".to_owned()),
+        };
+        assert_eq!(
+            event.get_params(&json!(10)),
+            json!({"audioPort": "HDMI0", "volumeLevel": 10})
+        );
+    }
+}
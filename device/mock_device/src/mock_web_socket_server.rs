@@ -33,7 +33,7 @@ use ripple_sdk::{
         sync::Mutex,
     },
     tokio_tungstenite::{
-        accept_hdr_async,
+        accept_hdr_async, connect_async,
         tungstenite::{handshake, Error, Message, Result},
         WebSocketStream,
     },
@@ -43,8 +43,11 @@ use serde_json::{json, Value};
 
 use crate::{
     errors::MockServerWebSocketError,
-    mock_config::MockConfig,
+    mock_config::{MockConfig, MockMode},
     mock_data::{MockData, MockDataError, ParamResponse, ResponseSink},
+    mock_faults::{FaultProfiles, FaultState},
+    mock_recorder::Recorder,
+    mock_state::MockState,
     utils::is_value_jsonrpc,
 };
 
@@ -107,6 +110,14 @@ type WSConnection = Arc<Mutex<HashMap<String, SplitSink<WebSocketStream<TcpStrea
 #[derive(Debug)]
 pub struct MockWebSocketServer {
     mock_data_v2: Arc<RwLock<MockData>>,
+    /*
+    values written by the stateful setters and the event listeners registered by the clients
+    */
+    state: Arc<RwLock<MockState>>,
+    recorder: Option<Arc<Recorder>>,
+    faults: Arc<RwLock<FaultState>>,
+    // number of times a request was answered in replay mode, by method and params
+    replay_counts: Arc<RwLock<HashMap<String, usize>>>,
     listener: TcpListener,
     conn_path: String,
     conn_headers: HeaderMap,
@@ -194,6 +205,10 @@ impl MockWebSocketServer {
             .port();
         let (stats_tx, stats_rx) = tokio::sync::mpsc::channel(10);
         tokio::spawn(StatsCollector::new(stats_rx, config.clone().stats_file).start());
+        let recorder = match config.mode {
+            MockMode::Record => Some(Arc::new(Recorder::new(config.record_file.clone()))),
+            _ => None,
+        };
 
         Ok(Self {
             listener,
@@ -209,6 +224,10 @@ impl MockWebSocketServer {
                     .map(|(k, v)| (k.to_lowercase(), v))
                     .collect(),
             )),
+            state: Arc::new(RwLock::new(MockState::default())),
+            recorder,
+            faults: Arc::new(RwLock::new(FaultState::default())),
+            replay_counts: Arc::new(RwLock::new(HashMap::new())),
             stats_channel: stats_tx,
         })
     }
@@ -296,6 +315,10 @@ impl MockWebSocketServer {
             .await
             .expect("Failed to accept");
 
+        if let (Some(recorder), Some(upstream_url)) = (&self.recorder, &self.config.upstream_url) {
+            return Self::proxy_connection(peer, ws_stream, upstream_url, recorder).await;
+        }
+
         let (send, mut recv) = ws_stream.split();
 
         debug!("New WebSocket connection: {peer}");
@@ -323,19 +346,44 @@ impl MockWebSocketServer {
 
                 debug!("Parsed message: {:?}", request_message);
 
-                let responses = match self.find_responses(request_message.clone()).await {
+                let fault = request_message
+                    .get("method")
+                    .and_then(|m| m.as_str())
+                    .and_then(|method| self.faults.write().unwrap().next_fault(method));
+                if let Some(fault) = &fault {
+                    if fault.drop_connection {
+                        warn!("Dropping connection peer={peer} for request: {msg}");
+                        break;
+                    }
+                    if fault.no_response {
+                        debug!("Not responding to request: {msg}");
+                        continue;
+                    }
+                }
+
+                let mut responses = match self.find_responses(request_message.clone()).await {
                     Some(value) => value,
                     None => {
                         warn!("No mock response found for request: {msg}");
                         continue;
                     }
                 };
+                let mut malformed = false;
+                if let Some(fault) = fault {
+                    responses = fault.apply(responses);
+                    malformed = fault.malformed;
+                }
                 let connected_peer = self.connected_peer_sinks.clone();
                 let context = request_message.clone();
                 tokio::spawn(async move {
-                    if let Err(e) =
-                        Self::send_to_sink(connected_peer, &peer.to_string(), responses, context)
-                            .await
+                    if let Err(e) = Self::send_to_sink(
+                        connected_peer,
+                        &peer.to_string(),
+                        responses,
+                        context,
+                        malformed,
+                    )
+                    .await
                     {
                         error!("Error sending data back to sink {}", e.to_string());
                     }
@@ -349,28 +397,92 @@ impl MockWebSocketServer {
         Ok(())
     }
 
+    async fn proxy_connection(
+        peer: SocketAddr,
+        ws_stream: WebSocketStream<TcpStream>,
+        upstream_url: &str,
+        recorder: &Arc<Recorder>,
+    ) -> Result<()> {
+        let (upstream, _) = connect_async(upstream_url).await?;
+        debug!("Proxying {peer} to {upstream_url}");
+        let (mut upstream_send, mut upstream_recv) = upstream.split();
+        let (mut send, mut recv) = ws_stream.split();
+        let mut session = recorder.session();
+
+        let proxied: Result<()> = async {
+            loop {
+                tokio::select! {
+                    msg = recv.next() => {
+                        let msg = match msg {
+                            Some(msg) => msg?,
+                            None => break,
+                        };
+                        if msg.is_close() {
+                            break;
+                        }
+                        if msg.is_text() {
+                            session.on_request(&msg.to_string());
+                        }
+                        upstream_send.send(msg).await?;
+                    }
+                    msg = upstream_recv.next() => {
+                        let msg = match msg {
+                            Some(msg) => msg?,
+                            None => break,
+                        };
+                        if msg.is_close() {
+                            break;
+                        }
+                        if msg.is_text() {
+                            session.on_response(&msg.to_string());
+                        }
+                        send.send(msg).await?;
+                    }
+                }
+            }
+            Ok(())
+        }
+        .await;
+        recorder.flush().await;
+        debug!("Proxied connection dropped peer={peer}");
+        proxied
+    }
+
+    /// Cuts a response to about half its length, on a char boundary.
+    fn cut_in_half(response: &mut String) {
+        let cut = (0..=response.len() / 2)
+            .rev()
+            .find(|i| response.is_char_boundary(*i))
+            .unwrap_or(0);
+        response.truncate(cut);
+    }
+
     async fn send_to_sink(
         connection: WSConnection,
         peer: &str,
         responses: Vec<ResponseSink>,
         request: Value,
+        malformed: bool,
     ) -> Result<()> {
-        let mut clients = connection.lock().await;
-        let sink = clients.get_mut(peer);
-        if let Some(sink) = sink {
-            for resp in responses {
-                let response = resp.data.to_string();
-                if resp.delay > 0 {
-                    tokio::time::sleep(Duration::from_millis(resp.delay)).await
-                }
-                if let Err(e) = sink.send(Message::Text(response.clone())).await {
-                    error!("Error sending response={e:?} for request={request}");
-                } else {
-                    debug!("sent response={response:?} for request={request}");
-                }
+        for (index, resp) in responses.into_iter().enumerate() {
+            let mut response = resp.data.to_string();
+            if malformed && index == 0 {
+                Self::cut_in_half(&mut response);
+            }
+            // the sinks of all peers are locked only for the send, not during the delay
+            if resp.delay > 0 {
+                tokio::time::sleep(Duration::from_millis(resp.delay)).await
+            }
+            let mut clients = connection.lock().await;
+            let Some(sink) = clients.get_mut(peer) else {
+                error!("No sink found for peer={peer:?}");
+                break;
+            };
+            if let Err(e) = sink.send(Message::Text(response.clone())).await {
+                error!("Error sending response={e:?} for request={request}");
+            } else {
+                debug!("sent response={response:?} for request={request}");
             }
-        } else {
-            error!("No sink found for peer={peer:?}");
         }
         Ok(())
     }
@@ -384,7 +496,11 @@ impl MockWebSocketServer {
         if let Ok(request) = serde_json::from_value::<JsonRpcApiRequest>(request_message.clone()) {
             let _ = self.stats_channel.send(request.method.clone()).await;
             if let Some(id) = request.id {
+                self.track_registration(&request);
                 debug!("activate_all_plugins={}", self.config.activate_all_plugins);
+                if let Some(response) = self.plugin_status_response(&request, id) {
+                    return Some(vec![response]);
+                }
                 if self.config.activate_all_plugins
                     && request.method.contains("Controller.1.status")
                 {
@@ -400,6 +516,8 @@ impl MockWebSocketServer {
                         data: json!({"jsonrpc":"2.0","id":id,"result":[{"callsign": callsign,"classname":classname,"state":"activated", "locator": "mock_thunder"}]}),
                     }]);
                 } else if let Some(v) = self.responses_for_key_v2(&request) {
+                    let (v, state_events) =
+                        v.with_state(request.params.as_ref(), &mut self.state.write().unwrap());
                     if v.events.is_some() {
                         if let Some(params) = request.params {
                             if let Ok(t) =
@@ -409,7 +527,9 @@ impl MockWebSocketServer {
                             }
                         }
                     }
-                    return Some(v.get_all(Some(id), None));
+                    let mut responses = v.get_all(Some(id), None);
+                    responses.extend(state_events);
+                    return Some(responses);
                 }
                 return Some(vec![ResponseSink {
                     delay: 0,
@@ -428,23 +548,103 @@ impl MockWebSocketServer {
         None
     }
 
+    fn track_registration(&self, request: &JsonRpcApiRequest) {
+        let register = request.method.ends_with(".register");
+        if !register && !request.method.ends_with(".unregister") {
+            return;
+        }
+        let params = request
+            .params
+            .clone()
+            .and_then(|p| serde_json::from_value::<ThunderRegisterParams>(p).ok());
+        if let Some(params) = params {
+            let mut state = self.state.write().unwrap();
+            if register {
+                state.register(&params);
+            } else {
+                state.unregister(&params);
+            }
+        }
+    }
+
+    fn plugin_status_response(&self, request: &JsonRpcApiRequest, id: u64) -> Option<ResponseSink> {
+        let (_, callsign) = request.method.split_once("Controller.1.status@")?;
+        let state = self
+            .state
+            .read()
+            .unwrap()
+            .get_plugin_state(callsign)?
+            .clone();
+        let classname = callsign.split('.').last().unwrap_or(callsign);
+        Some(ResponseSink {
+            delay: 0,
+            data: json!({"jsonrpc":"2.0","id":id,"result":[{"callsign": callsign,"classname":classname,"state":state, "locator": "mock_thunder"}]}),
+        })
+    }
+
+    /// Sets the state returned for the plugin by the Controller and sends the statechange event
+    /// to the registered listeners.
+    pub async fn set_plugin_state(&self, callsign: &str, state: &str) {
+        let listeners = {
+            let mut mock_state = self.state.write().unwrap();
+            mock_state.set_plugin_state(callsign, state);
+            mock_state.get_listeners("statechange")
+        };
+        // the Controller reports the state capitalized in its events
+        let mut event_state = state.to_lowercase();
+        if let Some(first) = event_state.get_mut(0..1) {
+            first.make_ascii_uppercase();
+        }
+        for method in listeners {
+            self.emit_event(
+                &json!({"jsonrpc": "2.0", "method": method, "params": {"callsign": callsign, "state": event_state}}),
+                0,
+            )
+            .await;
+        }
+    }
+
+    pub fn set_fault_profiles(&self, profiles: FaultProfiles) {
+        self.faults.write().unwrap().set_profiles(profiles);
+    }
+
+    pub fn clear_fault_profiles(&self, methods: Option<Vec<String>>) {
+        self.faults.write().unwrap().clear_profiles(methods);
+    }
+
+    /// Drops the values written by the stateful setters, getters answer their mock data again.
+    pub fn reset_state(&self) {
+        self.state.write().unwrap().clear();
+    }
+
     fn responses_for_key_v2(&self, req: &JsonRpcApiRequest) -> Option<ParamResponse> {
         let mock_data = self.mock_data_v2.read().unwrap();
         if let Some(v) = mock_data.get(&req.method.to_lowercase()).cloned() {
             if v.len() == 1 {
                 return v.first().cloned();
-            } else if let Some(params) = &req.params {
+            } else {
+                // a request without params matches the responses recorded without params
+                let params = req.params.clone().unwrap_or(Value::Null);
                 let mut new_params = params.clone();
                 if req.method.ends_with(".register") {
                     if let Some(v) = params.get("event").cloned() {
                         new_params = json!({"event": v})
                     }
                 }
-                for response in v {
-                    if response.get_key(&new_params).is_some() {
-                        return Some(response);
-                    }
+                let matches: Vec<ParamResponse> = v
+                    .into_iter()
+                    .filter(|response| response.get_key(&new_params).is_some())
+                    .collect();
+                if self.config.mode == MockMode::Replay && matches.len() > 1 {
+                    // serve the recorded responses in order and keep the last one afterwards
+                    let key = format!("{}{}", req.method.to_lowercase(), new_params);
+                    let mut replay_counts = self.replay_counts.write().unwrap();
+                    let count = replay_counts.entry(key).or_insert(0);
+                    let index = (*count).min(matches.len() - 1);
+                    *count += 1;
+                    return matches.get(index).cloned();
                 }
+                return matches.into_iter().next();
             }
         }
         None
@@ -677,4 +877,330 @@ mod tests {
         );
         assert_eq!(&response, &expected);
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_stateful_set_then_get() {
+        let mock_data = get_mock_data(json!({
+            "org.rdk.DisplaySettings.1.setVolumeLevel": [{
+                "params": {"audioPort": "HDMI0"},
+                "match": "partial",
+                "result": {"success": true},
+                "state": {"key": "volume", "access": "set", "pointer": "/volumeLevel"}
+            }],
+            "org.rdk.DisplaySettings.1.getVolumeLevel": [{
+                "result": {"volumeLevel": 50, "success": true},
+                "state": {"key": "volume", "access": "get", "pointer": "/volumeLevel"}
+            }]
+        }));
+        let server = start_server(mock_data).await;
+        let request = |method: &str, params: Value| {
+            Message::Text(
+                json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params}).to_string(),
+            )
+        };
+        let get_volume = || async {
+            let response = request_response_with_timeout(
+                server.clone(),
+                request("org.rdk.DisplaySettings.1.getVolumeLevel", json!({})),
+            )
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+            serde_json::from_str::<Value>(&response.to_string()).unwrap()["result"]["volumeLevel"]
+                .clone()
+        };
+
+        assert_eq!(get_volume().await, json!(50));
+        let _ = request_response_with_timeout(
+            server.clone(),
+            request(
+                "org.rdk.DisplaySettings.1.setVolumeLevel",
+                json!({"audioPort": "HDMI0", "volumeLevel": 20}),
+            ),
+        )
+        .await
+        .unwrap();
+        assert_eq!(get_volume().await, json!(20));
+
+        server.reset_state();
+        assert_eq!(get_volume().await, json!(50));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_record_and_replay() {
+        let upstream = start_server(get_mock_data(json!({
+            "org.rdk.System.1.getSerialNumber": [{"result": {"serialNumber": "1234", "success": true}}]
+        })))
+        .await;
+        let record_file =
+            std::env::temp_dir().join(format!("mock-record-{}.json", std::process::id()));
+        let config = MockConfig {
+            mode: MockMode::Record,
+            upstream_url: Some(format!("ws://127.0.0.1:{}", upstream.port())),
+            record_file: record_file.display().to_string(),
+            ..Default::default()
+        };
+        let recording =
+            MockWebSocketServer::new(MockData::new(), WsServerParameters::new(), config)
+                .await
+                .unwrap()
+                .into_arc();
+        tokio::spawn(recording.clone().start_server());
+
+        let request = Message::Text(
+            json!({"jsonrpc": "2.0", "id": 7, "method": "org.rdk.System.1.getSerialNumber"})
+                .to_string(),
+        );
+        let response = request_response_with_timeout(recording.clone(), request)
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        let response: Value = serde_json::from_str(&response.to_string()).unwrap();
+        assert_eq!(response["id"], json!(7));
+        assert_eq!(response["result"]["serialNumber"], json!("1234"));
+
+        let request = Message::Text(
+            json!({"jsonrpc": "2.0", "id": 8, "method": "org.rdk.System.1.getSerialNumber"})
+                .to_string(),
+        );
+        request_response_with_timeout(recording.clone(), request)
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+
+        let recorder = recording.recorder.as_ref().unwrap();
+        let recorded = recorder.get_data();
+        assert_eq!(
+            recorded["org.rdk.System.1.getSerialNumber"][0].result,
+            Some(json!({"serialNumber": "1234", "success": true}))
+        );
+
+        // replay the recording from the file, the responses were recorded without params
+        recorder.flush().await;
+        let recorded: MockData =
+            serde_json::from_str(&std::fs::read_to_string(&record_file).unwrap()).unwrap();
+        let responses = &recorded["org.rdk.System.1.getSerialNumber"];
+        assert_eq!(responses.len(), 2);
+        assert!(responses.iter().all(|r| r.params.is_none()));
+        let config = MockConfig {
+            mode: MockMode::Replay,
+            ..Default::default()
+        };
+        let replay = MockWebSocketServer::new(recorded, WsServerParameters::new(), config)
+            .await
+            .unwrap()
+            .into_arc();
+        tokio::spawn(replay.clone().start_server());
+        for id in 1..4 {
+            let request = Message::Text(
+                json!({"jsonrpc": "2.0", "id": id, "method": "org.rdk.System.1.getSerialNumber"})
+                    .to_string(),
+            );
+            let response = request_response_with_timeout(replay.clone(), request)
+                .await
+                .unwrap()
+                .unwrap()
+                .unwrap();
+            let response: Value = serde_json::from_str(&response.to_string()).unwrap();
+            assert_eq!(response["id"], json!(id));
+            assert_eq!(response["result"]["serialNumber"], json!("1234"));
+        }
+        let _ = std::fs::remove_file(record_file);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_replay_in_recorded_order() {
+        let mock_data = get_mock_data(json!({
+            "org.rdk.DisplaySettings.1.getVolumeLevel": [
+                {"params": {"audioPort": "HDMI0"}, "result": {"volumeLevel": 50}},
+                {"params": {"audioPort": "HDMI0"}, "result": {"volumeLevel": 20}},
+                {"params": {"audioPort": "SPDIF0"}, "result": {"volumeLevel": 0}}
+            ]
+        }));
+        let config = MockConfig {
+            mode: MockMode::Replay,
+            ..Default::default()
+        };
+        let server = MockWebSocketServer::new(mock_data, WsServerParameters::new(), config)
+            .await
+            .unwrap()
+            .into_arc();
+        tokio::spawn(server.clone().start_server());
+
+        let mut volumes = Vec::new();
+        for id in 1..4 {
+            let request = Message::Text(
+                json!({"jsonrpc": "2.0", "id": id, "method": "org.rdk.DisplaySettings.1.getVolumeLevel", "params": {"audioPort": "HDMI0"}})
+                    .to_string(),
+            );
+            let response = request_response_with_timeout(server.clone(), request)
+                .await
+                .unwrap()
+                .unwrap()
+                .unwrap();
+            let response: Value = serde_json::from_str(&response.to_string()).unwrap();
+            assert_eq!(response["id"], json!(id));
+            volumes.push(response["result"]["volumeLevel"].clone());
+        }
+        assert_eq!(volumes, vec![json!(50), json!(20), json!(20)]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_fault_profiles() {
+        let server = start_server(get_mock_data(json!({
+            "org.rdk.System.1.getSerialNumber": [{"result": {"serialNumber": "1234", "success": true}}]
+        })))
+        .await;
+        let request = Message::Text(
+            json!({"jsonrpc": "2.0", "id": 1, "method": "org.rdk.System.1.getSerialNumber"})
+                .to_string(),
+        );
+
+        server.set_fault_profiles(HashMap::from([(
+            "org.rdk.System.1.getSerialNumber".to_owned(),
+            serde_json::from_value(json!({"error_rate": 1.0, "latency": 10})).unwrap(),
+        )]));
+        let response = request_response_with_timeout(server.clone(), request.clone())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        let response: Value = serde_json::from_str(&response.to_string()).unwrap();
+        assert_eq!(response["error"]["message"], json!("ERROR_GENERAL"));
+
+        server.set_fault_profiles(HashMap::from([(
+            "org.rdk.System.1.getSerialNumber".to_owned(),
+            serde_json::from_value(json!({"malformed": true})).unwrap(),
+        )]));
+        let response = request_response_with_timeout(server.clone(), request.clone())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert!(serde_json::from_str::<Value>(&response.to_string()).is_err());
+
+        server.set_fault_profiles(HashMap::from([(
+            "org.rdk.System.1.getSerialNumber".to_owned(),
+            serde_json::from_value(json!({"drop_after": 0})).unwrap(),
+        )]));
+        let response = request_response_with_timeout(server.clone(), request.clone())
+            .await
+            .expect("connection not dropped");
+        assert!(!matches!(response, Some(Ok(Message::Text(_)))));
+
+        server.clear_fault_profiles(None);
+        let response = request_response_with_timeout(server, request)
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        let response: Value = serde_json::from_str(&response.to_string()).unwrap();
+        assert_eq!(response["result"]["serialNumber"], json!("1234"));
+    }
+
+    #[test]
+    fn test_cut_in_half() {
+        let mut response = "aéé".to_owned();
+        MockWebSocketServer::cut_in_half(&mut response);
+        assert_eq!(response, "a");
+        let mut response = json!({"result": {"name": "ポータブルテレビ"}}).to_string();
+        MockWebSocketServer::cut_in_half(&mut response);
+        assert!(!response.is_empty());
+        assert!(serde_json::from_str::<Value>(&response).is_err());
+        let mut response = String::new();
+        MockWebSocketServer::cut_in_half(&mut response);
+        assert!(response.is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_delayed_response_does_not_block_others() {
+        let server = start_server(get_mock_data(json!({
+            "org.rdk.System.1.getSerialNumber": [{"result": {"serialNumber": "1234", "success": true}}],
+            "org.rdk.System.1.getDeviceInfo": [{"result": {"make": "Ripple", "success": true}}]
+        })))
+        .await;
+        server.set_fault_profiles(HashMap::from([(
+            "org.rdk.System.1.getSerialNumber".to_owned(),
+            serde_json::from_value(json!({"latency": 300})).unwrap(),
+        )]));
+        let (mut send, mut receive) = WebSocketUtils::get_ws_stream(
+            format!("ws://127.0.0.1:{}", server.port()).as_str(),
+            None,
+        )
+        .await
+        .unwrap();
+
+        for (id, method) in [
+            (1, "org.rdk.System.1.getSerialNumber"),
+            (2, "org.rdk.System.1.getDeviceInfo"),
+        ] {
+            send.send(Message::Text(
+                json!({"jsonrpc": "2.0", "id": id, "method": method}).to_string(),
+            ))
+            .await
+            .unwrap();
+            // the delayed call reaches the server first
+            time::sleep(Duration::from_millis(20)).await;
+        }
+        let mut ids = Vec::new();
+        for _ in 0..2 {
+            let response = time::timeout(Duration::from_secs(1), receive.next())
+                .await
+                .unwrap()
+                .unwrap()
+                .unwrap();
+            let response: Value = serde_json::from_str(&response.to_string()).unwrap();
+            ids.push(response["id"].clone());
+        }
+        assert_eq!(ids, vec![json!(2), json!(1)]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_set_plugin_state() {
+        let server = start_server(MockData::new()).await;
+        let (mut send, mut receive) = WebSocketUtils::get_ws_stream(
+            format!("ws://127.0.0.1:{}", server.port()).as_str(),
+            None,
+        )
+        .await
+        .unwrap();
+        send.send(Message::Text(
+            json!({"jsonrpc": "2.0", "id": 1, "method": "Controller.1.register", "params": {"event": "statechange", "id": "thunder.Broker.Controller.events"}})
+                .to_string(),
+        ))
+        .await
+        .unwrap();
+        let _ = time::timeout(Duration::from_secs(1), receive.next()).await;
+
+        server
+            .set_plugin_state("org.rdk.System", "deactivated")
+            .await;
+        let event = time::timeout(Duration::from_secs(1), receive.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        let event: Value = serde_json::from_str(&event.to_string()).unwrap();
+        assert_eq!(
+            event,
+            json!({"jsonrpc": "2.0", "method": "thunder.Broker.Controller.events.statechange", "params": {"callsign": "org.rdk.System", "state": "Deactivated"}})
+        );
+
+        let response = request_response_with_timeout(
+            server,
+            Message::Text(
+                json!({"jsonrpc": "2.0", "id": 2, "method": "Controller.1.status@org.rdk.System"})
+                    .to_string(),
+            ),
+        )
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+        let response: Value = serde_json::from_str(&response.to_string()).unwrap();
+        assert_eq!(response["result"][0]["state"], json!("deactivated"));
+    }
 }
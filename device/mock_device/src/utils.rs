@@ -29,7 +29,7 @@ use url::{Host, Url};
 
 use crate::{
     errors::{BootFailedError, LoadMockDataError, MockDeviceError},
-    mock_config::MockConfig,
+    mock_config::{MockConfig, MockMode},
     mock_data::MockData,
     mock_web_socket_server::{MockWebSocketServer, WsServerParameters},
 };
@@ -48,10 +48,21 @@ pub async fn boot_ws_server(
         return Err(BootFailedError::BadHostname)?;
     }
 
-    let config = load_config(&client);
+    let mut config = load_config(&client);
 
     let mut server_config = WsServerParameters::new();
-    let mock_data_v2 = load_mock_data_v2(client.clone()).await?;
+    let mock_data_v2 = if config.mode == MockMode::Record {
+        if config.upstream_url.is_none() {
+            return Err(BootFailedError::MissingUpstreamUrl)?;
+        }
+        config.record_file = find_file(client.clone(), config.record_file.clone())
+            .await?
+            .display()
+            .to_string();
+        MockData::new()
+    } else {
+        load_mock_data_v2(client.clone()).await?
+    };
     server_config
         .port(gateway.port().unwrap_or(0))
         .path(gateway.path());
@@ -94,10 +105,14 @@ fn is_valid_host(host: Option<Host<&str>>) -> bool {
     }
 }
 
-async fn find_mock_device_data_file(mut client: ExtnClient) -> Result<PathBuf, MockDeviceError> {
+async fn find_mock_device_data_file(client: ExtnClient) -> Result<PathBuf, MockDeviceError> {
     let file = client
         .get_config("mock_data_file")
         .unwrap_or("mock-device.json".to_owned());
+    find_file(client, file).await
+}
+
+async fn find_file(mut client: ExtnClient, file: String) -> Result<PathBuf, MockDeviceError> {
     let path = PathBuf::from(file);
 
     debug!(
@@ -142,6 +157,16 @@ pub fn load_config(client: &ExtnClient) -> MockConfig {
     if let Some(c) = client.get_config("activate_all_plugins") {
         config.activate_all_plugins = c.parse::<bool>().unwrap_or(false);
     }
+    if let Some(mode) = client.get_config("mode") {
+        config.mode = serde_json::from_value(Value::String(mode)).unwrap_or_else(|_| {
+            error!("Unknown mock device mode, using mock");
+            MockMode::Mock
+        });
+    }
+    config.upstream_url = client.get_config("upstream_url");
+    if let Some(record_file) = client.get_config("record_file") {
+        config.record_file = record_file;
+    }
     config
 }
 
@@ -0,0 +1,168 @@
+// Copyright 2025 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+use ripple_sdk::utils::error::RippleError;
+use serde::{Deserialize, Serialize};
+
+use crate::client::{
+    device_operator::DeviceOperator,
+    thunder_client::ThunderClient,
+    thunder_plugin::ThunderPlugin,
+    thunder_plugin_client::{ThunderEventStream, ThunderPluginClient},
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VideoDisplayRequest {
+    pub video_display: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CurrentResolution {
+    pub resolution: String,
+    pub w: Option<u32>,
+    pub h: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioFormat {
+    #[serde(default)]
+    pub supported_audio_format: Vec<String>,
+    pub current_audio_format: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolutionChangedEvent {
+    pub width: u32,
+    pub height: u32,
+    pub video_display_type: String,
+    pub resolution: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ConnectedVideoDisplaysResponse {
+    connected_video_displays: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HdrCapabilitiesResponse {
+    capabilities: u32,
+}
+
+/// Client of the `org.rdk.DisplaySettings` plugin.
+#[derive(Debug, Clone)]
+pub struct DisplaySettingsClient<O = ThunderClient> {
+    client: ThunderPluginClient<O>,
+}
+
+impl<O: DeviceOperator + Send + Sync> DisplaySettingsClient<O> {
+    pub fn new(operator: O) -> Self {
+        Self {
+            client: ThunderPluginClient::new(ThunderPlugin::DisplaySettings, operator),
+        }
+    }
+
+    pub fn get_plugin_client(&self) -> &ThunderPluginClient<O> {
+        &self.client
+    }
+
+    pub async fn get_current_resolution(
+        &self,
+        video_display: &str,
+    ) -> Result<CurrentResolution, RippleError> {
+        self.client
+            .call(
+                "getCurrentResolution",
+                &VideoDisplayRequest {
+                    video_display: video_display.to_owned(),
+                },
+            )
+            .await
+    }
+
+    pub async fn get_connected_video_displays(&self) -> Result<Vec<String>, RippleError> {
+        let response: ConnectedVideoDisplaysResponse = self
+            .client
+            .call_without_params("getConnectedVideoDisplays")
+            .await?;
+        Ok(response.connected_video_displays)
+    }
+
+    /// HDR formats supported by the TV as a bit mask.
+    pub async fn get_tv_hdr_capabilities(&self) -> Result<u32, RippleError> {
+        let response: HdrCapabilitiesResponse = self
+            .client
+            .call_without_params("getTVHDRCapabilities")
+            .await?;
+        Ok(response.capabilities)
+    }
+
+    pub async fn get_audio_format(&self) -> Result<AudioFormat, RippleError> {
+        self.client.call_without_params("getAudioFormat").await
+    }
+
+    pub async fn on_resolution_changed(
+        &self,
+    ) -> Result<ThunderEventStream<ResolutionChangedEvent>, RippleError> {
+        self.client.subscribe("resolutionChanged").await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::mock_device_operator::MockDeviceOperator;
+    use ripple_sdk::tokio;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_display_settings_client() {
+        let operator = MockDeviceOperator::default().with_response(
+            "org.rdk.DisplaySettings.1.getCurrentResolution",
+            json!({"resolution": "2160p", "success": true}),
+        );
+        let client = DisplaySettingsClient::new(operator.clone());
+        assert_eq!(
+            client.get_current_resolution("HDMI0").await.unwrap(),
+            CurrentResolution {
+                resolution: "2160p".to_owned(),
+                w: None,
+                h: None
+            }
+        );
+        assert_eq!(
+            operator.get_calls()[0].1,
+            Some(json!({"videoDisplay": "HDMI0"}))
+        );
+        assert_eq!(
+            client.get_tv_hdr_capabilities().await,
+            Err(RippleError::ParseError)
+        );
+
+        let mut events = client.on_resolution_changed().await.unwrap();
+        operator
+            .emit(
+                "resolutionChanged",
+                json!({"width": 3840, "height": 2160, "videoDisplayType": "HDMI0", "resolution": "2160p"}),
+            )
+            .await;
+        assert_eq!(events.next().await.unwrap().width, 3840);
+    }
+}
@@ -0,0 +1,144 @@
+// Copyright 2025 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+use ripple_sdk::utils::error::RippleError;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::client::{
+    device_operator::DeviceOperator,
+    thunder_client::{DefaultThunderResult, ThunderClient},
+    thunder_plugin::ThunderPlugin,
+    thunder_plugin_client::{ThunderEventStream, ThunderPluginClient},
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StoreKey {
+    pub namespace: String,
+    pub key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StoreValue {
+    pub namespace: String,
+    pub key: String,
+    pub value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ValueChangedEvent {
+    pub namespace: String,
+    pub key: String,
+    pub value: String,
+    pub scope: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GetValueResponse {
+    value: String,
+}
+
+/// Client of the `org.rdk.PersistentStore` plugin.
+#[derive(Debug, Clone)]
+pub struct PersistentStoreClient<O = ThunderClient> {
+    client: ThunderPluginClient<O>,
+}
+
+impl<O: DeviceOperator + Send + Sync> PersistentStoreClient<O> {
+    pub fn new(operator: O) -> Self {
+        Self {
+            client: ThunderPluginClient::new(ThunderPlugin::PersistentStorage, operator),
+        }
+    }
+
+    pub fn get_plugin_client(&self) -> &ThunderPluginClient<O> {
+        &self.client
+    }
+
+    pub async fn get_value(&self, key: &StoreKey) -> Result<String, RippleError> {
+        let response: GetValueResponse = self.client.call("getValue", key).await?;
+        Ok(response.value)
+    }
+
+    pub async fn set_value(&self, value: &StoreValue) -> Result<(), RippleError> {
+        let _: DefaultThunderResult = self.client.call("setValue", value).await?;
+        Ok(())
+    }
+
+    pub async fn delete_key(&self, key: &StoreKey) -> Result<(), RippleError> {
+        let _: DefaultThunderResult = self.client.call("deleteKey", key).await?;
+        Ok(())
+    }
+
+    pub async fn delete_namespace(&self, namespace: &str) -> Result<(), RippleError> {
+        let _: DefaultThunderResult = self
+            .client
+            .call("deleteNamespace", &json!({ "namespace": namespace }))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn flush_cache(&self) -> Result<(), RippleError> {
+        let _: DefaultThunderResult = self.client.call_without_params("flushCache").await?;
+        Ok(())
+    }
+
+    pub async fn on_value_changed(
+        &self,
+    ) -> Result<ThunderEventStream<ValueChangedEvent>, RippleError> {
+        self.client.subscribe("onValueChanged").await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::mock_device_operator::MockDeviceOperator;
+    use ripple_sdk::tokio;
+
+    #[tokio::test]
+    async fn test_persistent_store_client() {
+        let operator = MockDeviceOperator::default()
+            .with_response(
+                "org.rdk.PersistentStore.1.getValue",
+                json!({"value": "{\"enabled\":true}", "success": true}),
+            )
+            .with_response(
+                "org.rdk.PersistentStore.1.deleteKey",
+                json!({"code": 22, "message": "ERROR_UNKNOWN_KEY"}),
+            );
+        let client = PersistentStoreClient::new(operator.clone());
+        let key = StoreKey {
+            namespace: "Settings".to_owned(),
+            key: "captions".to_owned(),
+            scope: None,
+        };
+        assert_eq!(client.get_value(&key).await.unwrap(), "{\"enabled\":true}");
+        assert_eq!(
+            operator.get_calls()[0].1,
+            Some(json!({"namespace": "Settings", "key": "captions"}))
+        );
+        assert_eq!(
+            client.delete_key(&key).await,
+            Err(RippleError::BrokerError("22: ERROR_UNKNOWN_KEY".to_owned()))
+        );
+    }
+}
@@ -0,0 +1,204 @@
+// Copyright 2025 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+use ripple_sdk::utils::error::RippleError;
+use serde::{Deserialize, Serialize};
+
+use crate::client::{
+    device_operator::DeviceOperator,
+    thunder_client::{DefaultThunderResult, ThunderClient},
+    thunder_plugin::ThunderPlugin,
+    thunder_plugin_client::ThunderPluginClient,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LaunchRequest {
+    pub callsign: String,
+    #[serde(rename = "type")]
+    pub _type: String,
+    pub uri: String,
+    pub suspend: bool,
+    pub visible: bool,
+    pub focused: bool,
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CallsignRequest {
+    pub callsign: String,
+}
+
+/// Identifies a window, `client` is the callsign of the app in it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ClientRequest {
+    pub callsign: String,
+    pub client: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VisibilityRequest {
+    pub callsign: String,
+    pub client: String,
+    pub visible: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BoundsRequest {
+    pub callsign: String,
+    pub client: String,
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemMemory {
+    pub free_ram: u64,
+    pub swap_ram: u64,
+    pub total_ram: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LaunchResponse {
+    launch_type: Option<String>,
+}
+
+/// Client of the `org.rdk.RDKShell` plugin.
+#[derive(Debug, Clone)]
+pub struct RdkShellClient<O = ThunderClient> {
+    client: ThunderPluginClient<O>,
+}
+
+impl<O: DeviceOperator + Send + Sync> RdkShellClient<O> {
+    pub fn new(operator: O) -> Self {
+        Self {
+            client: ThunderPluginClient::new(ThunderPlugin::RDKShell, operator),
+        }
+    }
+
+    pub fn get_plugin_client(&self) -> &ThunderPluginClient<O> {
+        &self.client
+    }
+
+    /// Launches the app, returns how it was launched e.g. `create` or `resume`.
+    pub async fn launch(&self, request: &LaunchRequest) -> Result<Option<String>, RippleError> {
+        let response: LaunchResponse = self.client.call("launch", request).await?;
+        Ok(response.launch_type)
+    }
+
+    pub async fn destroy(&self, callsign: &str) -> Result<(), RippleError> {
+        self.call_success(
+            "destroy",
+            &CallsignRequest {
+                callsign: callsign.to_owned(),
+            },
+        )
+        .await
+    }
+
+    pub async fn move_to_front(&self, request: &ClientRequest) -> Result<(), RippleError> {
+        self.call_success("moveToFront", request).await
+    }
+
+    pub async fn move_to_back(&self, request: &ClientRequest) -> Result<(), RippleError> {
+        self.call_success("moveToBack", request).await
+    }
+
+    pub async fn set_focus(&self, request: &ClientRequest) -> Result<(), RippleError> {
+        self.call_success("setFocus", request).await
+    }
+
+    pub async fn set_visibility(&self, request: &VisibilityRequest) -> Result<(), RippleError> {
+        self.call_success("setVisibility", request).await
+    }
+
+    pub async fn set_bounds(&self, request: &BoundsRequest) -> Result<(), RippleError> {
+        self.call_success("setBounds", request).await
+    }
+
+    pub async fn get_system_memory(&self) -> Result<SystemMemory, RippleError> {
+        self.client.call_without_params("getSystemMemory").await
+    }
+
+    async fn call_success<P: Serialize + Sync>(
+        &self,
+        method: &str,
+        params: &P,
+    ) -> Result<(), RippleError> {
+        let _: DefaultThunderResult = self.client.call(method, params).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::mock_device_operator::MockDeviceOperator;
+    use ripple_sdk::tokio;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_rdk_shell_client() {
+        let operator = MockDeviceOperator::default()
+            .with_response(
+                "org.rdk.RDKShell.1.launch",
+                json!({"launchType": "create", "success": true}),
+            )
+            .with_response("org.rdk.RDKShell.1.setVisibility", json!({"success": true}))
+            .with_response(
+                "org.rdk.RDKShell.1.getSystemMemory",
+                json!({"freeRam": 1024, "swapRam": 0, "totalRam": 2048, "success": true}),
+            );
+        let client = RdkShellClient::new(operator.clone());
+        let launched = client
+            .launch(&LaunchRequest {
+                callsign: "Cobalt".to_owned(),
+                _type: "Cobalt".to_owned(),
+                uri: "https://www.youtube.com/tv".to_owned(),
+                suspend: false,
+                visible: true,
+                focused: true,
+                x: 0,
+                y: 0,
+                w: 1920,
+                h: 1080,
+            })
+            .await
+            .unwrap();
+        assert_eq!(launched, Some("create".to_owned()));
+        assert_eq!(
+            operator.get_calls()[0].1.as_ref().unwrap()["type"],
+            "Cobalt"
+        );
+
+        client
+            .set_visibility(&VisibilityRequest {
+                callsign: "Cobalt".to_owned(),
+                client: "Cobalt".to_owned(),
+                visible: false,
+            })
+            .await
+            .unwrap();
+        assert_eq!(client.get_system_memory().await.unwrap().free_ram, 1024);
+    }
+}
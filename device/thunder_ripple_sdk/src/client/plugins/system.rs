@@ -0,0 +1,223 @@
+// Copyright 2025 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+use std::collections::HashMap;
+
+use ripple_sdk::utils::error::RippleError;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::client::{
+    device_operator::DeviceOperator,
+    thunder_client::{DefaultThunderResult, ThunderClient},
+    thunder_plugin::ThunderPlugin,
+    thunder_plugin_client::{ThunderEventStream, ThunderPluginClient},
+};
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemVersions {
+    pub stb_version: String,
+    pub receiver_version: String,
+    pub stb_timestamp: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FriendlyName {
+    pub friendly_name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeZoneDst {
+    pub time_zone: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeZoneChangedEvent {
+    pub old_time_zone: String,
+    pub new_time_zone: String,
+    pub old_accuracy: Option<String>,
+    pub new_accuracy: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PowerStateChangedEvent {
+    pub power_state: String,
+    pub current_power_state: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SerialNumberResponse {
+    serial_number: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PowerStateResponse {
+    power_state: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TimeZonesResponse {
+    zoneinfo: HashMap<String, Value>,
+}
+
+/// Client of the `org.rdk.System` plugin.
+#[derive(Debug, Clone)]
+pub struct SystemClient<O = ThunderClient> {
+    client: ThunderPluginClient<O>,
+}
+
+impl<O: DeviceOperator + Send + Sync> SystemClient<O> {
+    pub fn new(operator: O) -> Self {
+        Self {
+            client: ThunderPluginClient::new(ThunderPlugin::System, operator),
+        }
+    }
+
+    pub fn get_plugin_client(&self) -> &ThunderPluginClient<O> {
+        &self.client
+    }
+
+    pub async fn get_serial_number(&self) -> Result<String, RippleError> {
+        let response: SerialNumberResponse =
+            self.client.call_without_params("getSerialNumber").await?;
+        Ok(response.serial_number)
+    }
+
+    pub async fn get_system_versions(&self) -> Result<SystemVersions, RippleError> {
+        self.client.call_without_params("getSystemVersions").await
+    }
+
+    pub async fn get_friendly_name(&self) -> Result<String, RippleError> {
+        let response: FriendlyName = self.client.call_without_params("getFriendlyName").await?;
+        Ok(response.friendly_name)
+    }
+
+    pub async fn set_friendly_name(&self, friendly_name: &str) -> Result<(), RippleError> {
+        let _: DefaultThunderResult = self
+            .client
+            .call(
+                "setFriendlyName",
+                &FriendlyName {
+                    friendly_name: friendly_name.to_owned(),
+                },
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_time_zone_dst(&self) -> Result<String, RippleError> {
+        let response: TimeZoneDst = self.client.call_without_params("getTimeZoneDST").await?;
+        Ok(response.time_zone)
+    }
+
+    pub async fn set_time_zone_dst(&self, time_zone: &str) -> Result<(), RippleError> {
+        let _: DefaultThunderResult = self
+            .client
+            .call(
+                "setTimeZoneDST",
+                &TimeZoneDst {
+                    time_zone: time_zone.to_owned(),
+                },
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Time zones by area, the values are the offsets or the time zones of the sub areas.
+    pub async fn get_time_zones(&self) -> Result<HashMap<String, Value>, RippleError> {
+        let response: TimeZonesResponse = self.client.call_without_params("getTimeZones").await?;
+        Ok(response.zoneinfo)
+    }
+
+    pub async fn get_power_state(&self) -> Result<String, RippleError> {
+        let response: PowerStateResponse = self.client.call_without_params("getPowerState").await?;
+        Ok(response.power_state)
+    }
+
+    pub async fn on_friendly_name_changed(
+        &self,
+    ) -> Result<ThunderEventStream<FriendlyName>, RippleError> {
+        self.client.subscribe("onFriendlyNameChanged").await
+    }
+
+    pub async fn on_time_zone_dst_changed(
+        &self,
+    ) -> Result<ThunderEventStream<TimeZoneChangedEvent>, RippleError> {
+        self.client.subscribe("onTimeZoneDSTChanged").await
+    }
+
+    pub async fn on_power_state_changed(
+        &self,
+    ) -> Result<ThunderEventStream<PowerStateChangedEvent>, RippleError> {
+        self.client.subscribe("onSystemPowerStateChanged").await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::mock_device_operator::MockDeviceOperator;
+    use ripple_sdk::tokio;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_system_client() {
+        let operator = MockDeviceOperator::default()
+            .with_response(
+                "org.rdk.System.1.getSystemVersions",
+                json!({
+                    "receiverVersion": "6.9.0.0",
+                    "stbTimestamp": "Tue 07 Nov 2023 00:03:20 AP UTC",
+                    "stbVersion": "SCXI11BEI_VBN_23Q4",
+                    "success": true
+                }),
+            )
+            .with_response("org.rdk.System.1.setTimeZoneDST", json!({"success": false}));
+        let client = SystemClient::new(operator.clone());
+        assert_eq!(
+            client.get_system_versions().await.unwrap().receiver_version,
+            "6.9.0.0"
+        );
+        assert_eq!(
+            client.set_time_zone_dst("Europe/London").await,
+            Err(RippleError::ProcessorError)
+        );
+        assert_eq!(
+            operator.get_calls()[1],
+            (
+                "org.rdk.System.1.setTimeZoneDST".to_owned(),
+                Some(json!({"timeZone": "Europe/London"}))
+            )
+        );
+
+        let mut events = client.on_power_state_changed().await.unwrap();
+        operator
+            .emit(
+                "onSystemPowerStateChanged",
+                json!({"powerState": "STANDBY", "currentPowerState": "ON"}),
+            )
+            .await;
+        assert_eq!(events.next().await.unwrap().power_state, "STANDBY");
+    }
+}
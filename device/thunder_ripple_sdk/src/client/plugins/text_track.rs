@@ -0,0 +1,211 @@
+// Copyright 2025 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+use ripple_sdk::utils::error::RippleError;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::client::{
+    device_operator::DeviceOperator, thunder_client::ThunderClient, thunder_plugin::ThunderPlugin,
+    thunder_plugin_client::ThunderPluginClient,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FontEdge {
+    None,
+    Raised,
+    Depressed,
+    Uniform,
+    DropShadowLeft,
+    DropShadowRight,
+    ContentDefault,
+}
+
+/*
+The TextTrack getters return the style as a plain value and the setters take it in an object
+named after the style, e.g. `{"fontColor": "#FF0000"}`, with a null result. Sizes and opacities
+are set as numbers and -1 sets the content default, the getters may return them as strings.
+*/
+
+/// Client of the `org.rdk.TextTrack` plugin.
+#[derive(Debug, Clone)]
+pub struct TextTrackClient<O = ThunderClient> {
+    client: ThunderPluginClient<O>,
+}
+
+impl<O: DeviceOperator + Send + Sync> TextTrackClient<O> {
+    pub fn new(operator: O) -> Self {
+        Self {
+            client: ThunderPluginClient::new(ThunderPlugin::TextTrack, operator),
+        }
+    }
+
+    pub fn get_plugin_client(&self) -> &ThunderPluginClient<O> {
+        &self.client
+    }
+
+    pub async fn get_font_family(&self) -> Result<String, RippleError> {
+        self.get("FontFamily").await
+    }
+
+    pub async fn set_font_family(&self, font_family: &str) -> Result<(), RippleError> {
+        self.set("FontFamily", "fontFamily", font_family).await
+    }
+
+    /// Font size as reported by the plugin e.g. `16px`.
+    pub async fn get_font_size(&self) -> Result<String, RippleError> {
+        self.get("FontSize").await
+    }
+
+    pub async fn set_font_size(&self, font_size: i32) -> Result<(), RippleError> {
+        self.set("FontSize", "fontSize", font_size).await
+    }
+
+    pub async fn get_font_color(&self) -> Result<String, RippleError> {
+        self.get("FontColor").await
+    }
+
+    pub async fn set_font_color(&self, font_color: &str) -> Result<(), RippleError> {
+        self.set("FontColor", "fontColor", font_color).await
+    }
+
+    pub async fn get_font_edge(&self) -> Result<FontEdge, RippleError> {
+        self.get("FontEdge").await
+    }
+
+    pub async fn set_font_edge(&self, font_edge: FontEdge) -> Result<(), RippleError> {
+        self.set("FontEdge", "fontEdge", font_edge).await
+    }
+
+    pub async fn get_font_edge_color(&self) -> Result<String, RippleError> {
+        self.get("FontEdgeColor").await
+    }
+
+    pub async fn set_font_edge_color(&self, font_edge_color: &str) -> Result<(), RippleError> {
+        self.set("FontEdgeColor", "fontEdgeColor", font_edge_color)
+            .await
+    }
+
+    pub async fn get_font_opacity(&self) -> Result<i32, RippleError> {
+        self.get_number("FontOpacity").await
+    }
+
+    pub async fn set_font_opacity(&self, font_opacity: i32) -> Result<(), RippleError> {
+        self.set("FontOpacity", "fontOpacity", font_opacity).await
+    }
+
+    pub async fn get_background_color(&self) -> Result<String, RippleError> {
+        self.get("BackgroundColor").await
+    }
+
+    pub async fn set_background_color(&self, background_color: &str) -> Result<(), RippleError> {
+        self.set("BackgroundColor", "backgroundColor", background_color)
+            .await
+    }
+
+    pub async fn get_background_opacity(&self) -> Result<i32, RippleError> {
+        self.get_number("BackgroundOpacity").await
+    }
+
+    pub async fn set_background_opacity(&self, background_opacity: i32) -> Result<(), RippleError> {
+        self.set("BackgroundOpacity", "backgroundOpacity", background_opacity)
+            .await
+    }
+
+    pub async fn get_window_color(&self) -> Result<String, RippleError> {
+        self.get("WindowColor").await
+    }
+
+    pub async fn set_window_color(&self, window_color: &str) -> Result<(), RippleError> {
+        self.set("WindowColor", "windowColor", window_color).await
+    }
+
+    pub async fn get_window_opacity(&self) -> Result<i32, RippleError> {
+        self.get_number("WindowOpacity").await
+    }
+
+    pub async fn set_window_opacity(&self, window_opacity: i32) -> Result<(), RippleError> {
+        self.set("WindowOpacity", "windowOpacity", window_opacity)
+            .await
+    }
+
+    async fn get<T: DeserializeOwned>(&self, style: &str) -> Result<T, RippleError> {
+        self.client
+            .call_without_params(&format!("get{}", style))
+            .await
+    }
+
+    async fn get_number(&self, style: &str) -> Result<i32, RippleError> {
+        let value: Value = self.get(style).await?;
+        match &value {
+            Value::Number(n) => n.as_i64().map(|n| n as i32),
+            Value::String(s) => s.parse().ok(),
+            _ => None,
+        }
+        .ok_or(RippleError::ParseError)
+    }
+
+    async fn set<T: Serialize + Send>(
+        &self,
+        style: &str,
+        key: &str,
+        value: T,
+    ) -> Result<(), RippleError> {
+        let _: Value = self
+            .client
+            .call(&format!("set{}", style), &json!({ key: value }))
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::mock_device_operator::MockDeviceOperator;
+    use ripple_sdk::tokio;
+
+    #[tokio::test]
+    async fn test_text_track_client() {
+        let operator = MockDeviceOperator::default()
+            .with_response("org.rdk.TextTrack.1.getFontSize", json!("16px"))
+            .with_response("org.rdk.TextTrack.1.getFontOpacity", json!("90"))
+            .with_response("org.rdk.TextTrack.1.getWindowOpacity", json!(100))
+            .with_response("org.rdk.TextTrack.1.getFontEdge", json!("drop_shadow_left"));
+        let client = TextTrackClient::new(operator.clone());
+        assert_eq!(client.get_font_size().await.unwrap(), "16px");
+        assert_eq!(client.get_font_opacity().await.unwrap(), 90);
+        assert_eq!(client.get_window_opacity().await.unwrap(), 100);
+        assert_eq!(
+            client.get_font_edge().await.unwrap(),
+            FontEdge::DropShadowLeft
+        );
+
+        client.set_font_size(1).await.unwrap();
+        client.set_font_edge(FontEdge::Raised).await.unwrap();
+        let calls = operator.get_calls();
+        assert_eq!(
+            calls[4],
+            (
+                "org.rdk.TextTrack.1.setFontSize".to_owned(),
+                Some(json!({"fontSize": 1}))
+            )
+        );
+        assert_eq!(calls[5].1, Some(json!({"fontEdge": "raised"})));
+    }
+}
@@ -0,0 +1,251 @@
+// Copyright 2025 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+use ripple_sdk::utils::error::RippleError;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::client::{
+    device_operator::DeviceOperator,
+    thunder_client::ThunderClient,
+    thunder_plugin::ThunderPlugin,
+    thunder_plugin_client::{ThunderEventStream, ThunderPluginClient},
+};
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct EnabledChangedEvent {
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PresentationLanguageChangedEvent {
+    pub presentation_language: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreferredLanguagesChangedEvent {
+    pub preferred_languages: String,
+}
+
+/*
+Like TextTrack, the UserSettings getters return plain values and the setters take an object
+with a null result. Preferred languages are ISO 639-2 codes joined with commas on the wire.
+*/
+
+/// Client of the `org.rdk.UserSettings` plugin.
+#[derive(Debug, Clone)]
+pub struct UserSettingsClient<O = ThunderClient> {
+    client: ThunderPluginClient<O>,
+}
+
+impl<O: DeviceOperator + Send + Sync> UserSettingsClient<O> {
+    pub fn new(operator: O) -> Self {
+        Self {
+            client: ThunderPluginClient::new(ThunderPlugin::UserSettings, operator),
+        }
+    }
+
+    pub fn get_plugin_client(&self) -> &ThunderPluginClient<O> {
+        &self.client
+    }
+
+    pub async fn get_presentation_language(&self) -> Result<String, RippleError> {
+        self.get("getPresentationLanguage").await
+    }
+
+    pub async fn set_presentation_language(&self, language: &str) -> Result<(), RippleError> {
+        self.set("setPresentationLanguage", "presentationLanguage", language)
+            .await
+    }
+
+    pub async fn get_preferred_audio_languages(&self) -> Result<Vec<String>, RippleError> {
+        let languages: String = self.get("getPreferredAudioLanguages").await?;
+        Ok(split_languages(&languages))
+    }
+
+    pub async fn set_preferred_audio_languages(
+        &self,
+        languages: &[String],
+    ) -> Result<(), RippleError> {
+        self.set(
+            "setPreferredAudioLanguages",
+            "preferredLanguages",
+            languages.join(","),
+        )
+        .await
+    }
+
+    pub async fn get_preferred_captions_languages(&self) -> Result<Vec<String>, RippleError> {
+        let languages: String = self.get("getPreferredCaptionsLanguages").await?;
+        Ok(split_languages(&languages))
+    }
+
+    pub async fn set_preferred_captions_languages(
+        &self,
+        languages: &[String],
+    ) -> Result<(), RippleError> {
+        self.set(
+            "setPreferredCaptionsLanguages",
+            "preferredLanguages",
+            languages.join(","),
+        )
+        .await
+    }
+
+    pub async fn get_audio_description(&self) -> Result<bool, RippleError> {
+        self.get("getAudioDescription").await
+    }
+
+    pub async fn set_audio_description(&self, enabled: bool) -> Result<(), RippleError> {
+        self.set("setAudioDescription", "enabled", enabled).await
+    }
+
+    pub async fn get_captions(&self) -> Result<bool, RippleError> {
+        self.get("getCaptions").await
+    }
+
+    pub async fn set_captions(&self, enabled: bool) -> Result<(), RippleError> {
+        self.set("setCaptions", "enabled", enabled).await
+    }
+
+    pub async fn get_voice_guidance(&self) -> Result<bool, RippleError> {
+        self.get("getVoiceGuidance").await
+    }
+
+    pub async fn set_voice_guidance(&self, enabled: bool) -> Result<(), RippleError> {
+        self.set("setVoiceGuidance", "enabled", enabled).await
+    }
+
+    pub async fn get_voice_guidance_rate(&self) -> Result<f64, RippleError> {
+        self.get("getVoiceGuidanceRate").await
+    }
+
+    pub async fn set_voice_guidance_rate(&self, rate: f64) -> Result<(), RippleError> {
+        self.set("setVoiceGuidanceRate", "rate", rate).await
+    }
+
+    pub async fn get_voice_guidance_hints(&self) -> Result<bool, RippleError> {
+        self.get("getVoiceGuidanceHints").await
+    }
+
+    pub async fn set_voice_guidance_hints(&self, hints: bool) -> Result<(), RippleError> {
+        self.set("setVoiceGuidanceHints", "hints", hints).await
+    }
+
+    pub async fn get_high_contrast(&self) -> Result<bool, RippleError> {
+        self.get("getHighContrast").await
+    }
+
+    pub async fn on_audio_description_changed(
+        &self,
+    ) -> Result<ThunderEventStream<EnabledChangedEvent>, RippleError> {
+        self.client.subscribe("onAudioDescriptionChanged").await
+    }
+
+    pub async fn on_captions_changed(
+        &self,
+    ) -> Result<ThunderEventStream<EnabledChangedEvent>, RippleError> {
+        self.client.subscribe("onCaptionsChanged").await
+    }
+
+    pub async fn on_voice_guidance_changed(
+        &self,
+    ) -> Result<ThunderEventStream<EnabledChangedEvent>, RippleError> {
+        self.client.subscribe("onVoiceGuidanceChanged").await
+    }
+
+    pub async fn on_presentation_language_changed(
+        &self,
+    ) -> Result<ThunderEventStream<PresentationLanguageChangedEvent>, RippleError> {
+        self.client.subscribe("onPresentationLanguageChanged").await
+    }
+
+    pub async fn on_preferred_audio_languages_changed(
+        &self,
+    ) -> Result<ThunderEventStream<PreferredLanguagesChangedEvent>, RippleError> {
+        self.client
+            .subscribe("onPreferredAudioLanguagesChanged")
+            .await
+    }
+
+    async fn get<T: DeserializeOwned>(&self, method: &str) -> Result<T, RippleError> {
+        self.client.call_without_params(method).await
+    }
+
+    async fn set<T: Serialize + Send>(
+        &self,
+        method: &str,
+        key: &str,
+        value: T,
+    ) -> Result<(), RippleError> {
+        let _: Value = self.client.call(method, &json!({ key: value })).await?;
+        Ok(())
+    }
+}
+
+fn split_languages(languages: &str) -> Vec<String> {
+    languages
+        .split(',')
+        .filter(|l| !l.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::mock_device_operator::MockDeviceOperator;
+    use ripple_sdk::tokio;
+
+    #[tokio::test]
+    async fn test_user_settings_client() {
+        let operator = MockDeviceOperator::default()
+            .with_response(
+                "org.rdk.UserSettings.1.getPreferredAudioLanguages",
+                json!("eng,spa"),
+            )
+            .with_response("org.rdk.UserSettings.1.getVoiceGuidanceRate", json!(0.1))
+            .with_response("org.rdk.UserSettings.1.getCaptions", json!(true));
+        let client = UserSettingsClient::new(operator.clone());
+        assert_eq!(
+            client.get_preferred_audio_languages().await.unwrap(),
+            vec!["eng".to_owned(), "spa".to_owned()]
+        );
+        assert_eq!(client.get_voice_guidance_rate().await.unwrap(), 0.1);
+        assert!(client.get_captions().await.unwrap());
+
+        client
+            .set_preferred_captions_languages(&["fre".to_owned(), "eng".to_owned()])
+            .await
+            .unwrap();
+        assert_eq!(
+            operator.get_calls()[3],
+            (
+                "org.rdk.UserSettings.1.setPreferredCaptionsLanguages".to_owned(),
+                Some(json!({"preferredLanguages": "fre,eng"}))
+            )
+        );
+
+        let mut events = client.on_captions_changed().await.unwrap();
+        operator
+            .emit("onCaptionsChanged", json!({"enabled": false}))
+            .await;
+        assert!(!events.next().await.unwrap().enabled);
+    }
+}
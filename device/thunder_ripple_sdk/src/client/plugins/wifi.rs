@@ -0,0 +1,194 @@
+// Copyright 2025 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+use ripple_sdk::utils::error::RippleError;
+use serde::{Deserialize, Serialize};
+
+use crate::client::{
+    device_operator::DeviceOperator,
+    thunder_client::{DefaultThunderResult, ThunderClient},
+    thunder_plugin::ThunderPlugin,
+    thunder_plugin_client::{ThunderEventStream, ThunderPluginClient},
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WifiConnectRequest {
+    pub ssid: String,
+    pub passphrase: String,
+    pub security_mode: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WifiScanRequest {
+    pub incremental: bool,
+    pub ssid: String,
+    pub frequency: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectedSsid {
+    pub ssid: String,
+    pub bssid: String,
+    pub rate: String,
+    pub noise: String,
+    pub security: String,
+    pub signal_strength: String,
+    pub frequency: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SsidInfo {
+    pub ssid: String,
+    pub security: u32,
+    pub signal_strength: String,
+    pub frequency: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AvailableSsidsEvent {
+    pub ssids: Vec<SsidInfo>,
+    pub more_data: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct WifiStateChangedEvent {
+    pub state: u32,
+    #[serde(rename = "isLNF")]
+    pub is_lnf: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct WifiErrorEvent {
+    pub code: u32,
+}
+
+/// Client of the `org.rdk.Wifi` plugin.
+#[derive(Debug, Clone)]
+pub struct WifiClient<O = ThunderClient> {
+    client: ThunderPluginClient<O>,
+}
+
+impl<O: DeviceOperator + Send + Sync> WifiClient<O> {
+    pub fn new(operator: O) -> Self {
+        Self {
+            client: ThunderPluginClient::new(ThunderPlugin::Wifi, operator),
+        }
+    }
+
+    pub fn get_plugin_client(&self) -> &ThunderPluginClient<O> {
+        &self.client
+    }
+
+    /// Starts connecting, the outcome comes with `onWIFIStateChanged` or `onError`.
+    pub async fn connect(&self, request: &WifiConnectRequest) -> Result<(), RippleError> {
+        let _: DefaultThunderResult = self.client.call("connect", request).await?;
+        Ok(())
+    }
+
+    pub async fn disconnect(&self) -> Result<(), RippleError> {
+        let _: DefaultThunderResult = self.client.call_without_params("disconnect").await?;
+        Ok(())
+    }
+
+    pub async fn get_connected_ssid(&self) -> Result<ConnectedSsid, RippleError> {
+        self.client.call_without_params("getConnectedSSID").await
+    }
+
+    /// Starts a scan, the access points found come with `onAvailableSSIDs`.
+    pub async fn start_scan(&self, request: &WifiScanRequest) -> Result<(), RippleError> {
+        let _: DefaultThunderResult = self.client.call("startScan", request).await?;
+        Ok(())
+    }
+
+    pub async fn stop_scan(&self) -> Result<(), RippleError> {
+        let _: DefaultThunderResult = self.client.call_without_params("stopScan").await?;
+        Ok(())
+    }
+
+    pub async fn on_available_ssids(
+        &self,
+    ) -> Result<ThunderEventStream<AvailableSsidsEvent>, RippleError> {
+        self.client.subscribe("onAvailableSSIDs").await
+    }
+
+    pub async fn on_state_changed(
+        &self,
+    ) -> Result<ThunderEventStream<WifiStateChangedEvent>, RippleError> {
+        self.client.subscribe("onWIFIStateChanged").await
+    }
+
+    pub async fn on_error(&self) -> Result<ThunderEventStream<WifiErrorEvent>, RippleError> {
+        self.client.subscribe("onError").await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::mock_device_operator::MockDeviceOperator;
+    use ripple_sdk::tokio;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_wifi_client() {
+        let operator = MockDeviceOperator::default()
+            .with_response("org.rdk.Wifi.1.startScan", json!({"success": true}))
+            .with_response("org.rdk.Wifi.1.connect", json!({"success": true}));
+        let client = WifiClient::new(operator.clone());
+        let mut ssids = client.on_available_ssids().await.unwrap();
+        let mut states = client.on_state_changed().await.unwrap();
+
+        client
+            .start_scan(&WifiScanRequest::default())
+            .await
+            .unwrap();
+        operator
+            .emit(
+                "onAvailableSSIDs",
+                json!({
+                    "ssids": [{"ssid": "Home", "security": 6, "signalStrength": "-27", "frequency": "5.180"}],
+                    "moreData": false
+                }),
+            )
+            .await;
+        assert_eq!(ssids.next().await.unwrap().ssids[0].ssid, "Home");
+
+        client
+            .connect(&WifiConnectRequest {
+                ssid: "Home".to_owned(),
+                passphrase: "secret".to_owned(),
+                security_mode: 6,
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            operator.get_calls()[1].1,
+            Some(json!({"ssid": "Home", "passphrase": "secret", "securityMode": 6}))
+        );
+        operator
+            .emit("onWIFIStateChanged", json!({"state": 5, "isLNF": false}))
+            .await;
+        assert_eq!(states.next().await.unwrap().state, 5);
+        // a lost response reads as null and fails where a result is expected
+        assert_eq!(client.disconnect().await, Err(RippleError::ParseError));
+    }
+}
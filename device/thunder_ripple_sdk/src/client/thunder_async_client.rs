@@ -17,7 +17,9 @@
 
 use super::{
     device_operator::{DeviceChannelParams, DeviceChannelRequest, DeviceResponseMessage},
-    thunder_async_client_plugins_status_mgr::{AsyncCallback, AsyncSender, StatusManager},
+    thunder_async_client_plugins_status_mgr::{
+        new_status_manager, AsyncCallback, AsyncSender, StatusManager,
+    },
 };
 use crate::utils::get_next_id;
 use futures::{stream::SplitSink, SinkExt, StreamExt};
@@ -144,7 +146,7 @@ impl ThunderAsyncClient {
             None => {
                 // If the plugin status is not available, add the request to the pending list
                 self.status_manager
-                    .add_async_client_request_to_pending_list(callsign.clone(), request.clone());
+                    .add_request_to_pending_list(callsign.clone(), request.clone());
                 // Generate a request to check the plugin status and add it to the requests list
                 let request = self
                     .status_manager
@@ -164,13 +166,13 @@ impl ThunderAsyncClient {
                 callsign
             );
             self.status_manager
-                .add_async_client_request_to_pending_list(callsign.clone(), request.clone());
+                .add_request_to_pending_list(callsign.clone(), request.clone());
             return Err(RippleError::ServiceNotReady);
         }
         // If the plugin is not activated, add the request to the pending list and generate an activation request
         if !status.state.is_activated() {
             self.status_manager
-                .add_async_client_request_to_pending_list(callsign.clone(), request.clone());
+                .add_request_to_pending_list(callsign.clone(), request.clone());
             let request = self
                 .status_manager
                 .generate_plugin_activation_request(callsign.clone());
@@ -220,7 +222,7 @@ impl ThunderAsyncClient {
 
     pub fn new(callback: AsyncCallback, sender: AsyncSender) -> Self {
         Self {
-            status_manager: StatusManager::new(),
+            status_manager: new_status_manager(),
             sender,
             callback,
             subscriptions: HashMap::new(),
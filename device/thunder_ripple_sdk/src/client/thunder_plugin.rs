@@ -20,7 +20,7 @@ use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 use ThunderPluginConfig as Cfg;
 
-#[derive(Debug, EnumIter)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter)]
 pub enum ThunderPlugin {
     Controller,
     DeviceInfo,
@@ -34,6 +34,7 @@ pub enum ThunderPlugin {
     System,
     Wifi,
     TextToSpeech,
+    TextTrack,
     Hdcp,
     Telemetry,
     Analytics,
@@ -52,6 +53,7 @@ const SYSTEM_CFG: Cfg = Cfg::new("org.rdk.System", true, false);
 const WIFI_CFG: Cfg = Cfg::new("org.rdk.Wifi", false, false);
 const LOCATION_SYNC: Cfg = Cfg::new("LocationSync", false, false);
 const TTS_CFG: Cfg = Cfg::new("org.rdk.TextToSpeech", false, true);
+const TEXT_TRACK_CFG: Cfg = Cfg::new("org.rdk.TextTrack", false, false);
 const TELEMETRY_CFG: Cfg = Cfg::new("org.rdk.Telemetry", false, false);
 const ANALYTICS_CFG: Cfg = Cfg::new("org.rdk.Analytics", false, false);
 const USER_SETTINGS_CFG: Cfg = Cfg::new("org.rdk.UserSettings", false, false);
@@ -70,6 +72,7 @@ impl fmt::Display for ThunderPlugin {
             ThunderPlugin::System => write!(f, "org.rdk.System"),
             ThunderPlugin::Wifi => write!(f, "org.rdk.Wifi"),
             ThunderPlugin::TextToSpeech => write!(f, "org.rdk.TextToSpeech"),
+            ThunderPlugin::TextTrack => write!(f, "org.rdk.TextTrack"),
             ThunderPlugin::Hdcp => write!(f, "org.rdk.HdcpProfile"),
             ThunderPlugin::Telemetry => write!(f, "org.rdk.Telemetry"),
             ThunderPlugin::Analytics => write!(f, "org.rdk.Analytics"),
@@ -96,6 +99,7 @@ impl ThunderPlugin {
             Wifi => WIFI_CFG,
             LocationSync => LOCATION_SYNC,
             TextToSpeech => TTS_CFG,
+            TextTrack => TEXT_TRACK_CFG,
             Telemetry => TELEMETRY_CFG,
             Analytics => ANALYTICS_CFG,
             UserSettings => USER_SETTINGS_CFG,
@@ -0,0 +1,250 @@
+// Copyright 2025 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+use std::marker::PhantomData;
+
+use ripple_sdk::{log::error, tokio::sync::mpsc, utils::error::RippleError};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use super::{
+    device_operator::{
+        DeviceCallRequest, DeviceChannelParams, DeviceOperator, DeviceResponseMessage,
+        DeviceSubscribeRequest, DeviceUnsubscribeRequest,
+    },
+    thunder_client::ThunderClient,
+    thunder_plugin::ThunderPlugin,
+};
+
+/*
+Calls the methods of one Thunder plugin with serializable params and deserializes the results.
+The clients in `client::plugins` wrap it with the request and response structs of their plugin,
+it can be used directly for the methods they do not cover. Thunder answers a setter with a null
+result, which can not be told apart from a call without a response, so null is passed on to the
+caller and only fails when the result type needs a value.
+*/
+#[derive(Debug, Clone)]
+pub struct ThunderPluginClient<O = ThunderClient> {
+    plugin: ThunderPlugin,
+    operator: O,
+}
+
+impl<O: DeviceOperator + Send + Sync> ThunderPluginClient<O> {
+    pub fn new(plugin: ThunderPlugin, operator: O) -> Self {
+        Self { plugin, operator }
+    }
+
+    pub fn get_plugin(&self) -> ThunderPlugin {
+        self.plugin
+    }
+
+    pub async fn call<P, R>(&self, method: &str, params: &P) -> Result<R, RippleError>
+    where
+        P: Serialize + Sync,
+        R: DeserializeOwned,
+    {
+        let params = serde_json::to_string(params).map_err(|_| RippleError::InvalidInput)?;
+        self.send(method, Some(DeviceChannelParams::Json(params)))
+            .await
+    }
+
+    pub async fn call_without_params<R: DeserializeOwned>(
+        &self,
+        method: &str,
+    ) -> Result<R, RippleError> {
+        self.send(method, None).await
+    }
+
+    /// Registers for the event, the stream ends when the subscription is dropped.
+    pub async fn subscribe<E: DeserializeOwned>(
+        &self,
+        event: &str,
+    ) -> Result<ThunderEventStream<E>, RippleError> {
+        let (tx, rx) = mpsc::channel(32);
+        let response = self
+            .operator
+            .subscribe(
+                DeviceSubscribeRequest {
+                    module: self.plugin.callsign_and_version(),
+                    event_name: event.to_owned(),
+                    params: None,
+                    sub_id: None,
+                },
+                tx,
+            )
+            .await
+            .map_err(|_| RippleError::NoResponse)?;
+        check_response(response.message)?;
+        Ok(ThunderEventStream {
+            receiver: rx,
+            _event: PhantomData,
+        })
+    }
+
+    pub async fn unsubscribe(&self, event: &str) {
+        self.operator
+            .unsubscribe(DeviceUnsubscribeRequest {
+                module: self.plugin.callsign_and_version(),
+                event_name: event.to_owned(),
+            })
+            .await
+    }
+
+    async fn send<R: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: Option<DeviceChannelParams>,
+    ) -> Result<R, RippleError> {
+        let response = self
+            .operator
+            .call(DeviceCallRequest {
+                method: self.plugin.method(method),
+                params,
+            })
+            .await;
+        let message = check_response(response.message)?;
+        serde_json::from_value(message).map_err(|e| {
+            error!("{}: unexpected result {}", self.plugin.method(method), e);
+            RippleError::ParseError
+        })
+    }
+}
+
+/// Maps a JSON-RPC error and a result with `"success": false` to an error.
+pub fn check_response(message: Value) -> Result<Value, RippleError> {
+    if let (Some(code), Some(text)) = (
+        message.get("code").and_then(Value::as_i64),
+        message.get("message").and_then(Value::as_str),
+    ) {
+        return Err(RippleError::BrokerError(format!("{}: {}", code, text)));
+    }
+    if message.get("success").and_then(Value::as_bool) == Some(false) {
+        return Err(RippleError::ProcessorError);
+    }
+    Ok(message)
+}
+
+/// Events of a Thunder subscription, deserialized to `E`.
+#[derive(Debug)]
+pub struct ThunderEventStream<E> {
+    receiver: mpsc::Receiver<DeviceResponseMessage>,
+    _event: PhantomData<E>,
+}
+
+impl<E: DeserializeOwned> ThunderEventStream<E> {
+    /// Waits for the next event, None once the subscription is gone. Events that do not
+    /// deserialize are logged and skipped.
+    pub async fn next(&mut self) -> Option<E> {
+        while let Some(response) = self.receiver.recv().await {
+            match serde_json::from_value(response.message) {
+                Ok(event) => return Some(event),
+                Err(e) => error!("unexpected thunder event {}", e),
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::mock_device_operator::MockDeviceOperator;
+    use ripple_sdk::tokio;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct FriendlyName {
+        #[serde(rename = "friendlyName")]
+        friendly_name: String,
+    }
+
+    #[test]
+    fn test_check_response() {
+        assert_eq!(
+            check_response(json!({"code": -32601, "message": "Unknown method."})),
+            Err(RippleError::BrokerError(
+                "-32601: Unknown method.".to_owned()
+            ))
+        );
+        assert_eq!(
+            check_response(json!({"success": false})),
+            Err(RippleError::ProcessorError)
+        );
+        assert_eq!(check_response(Value::Null), Ok(Value::Null));
+        assert_eq!(
+            check_response(json!({"message": "hello", "success": true})),
+            Ok(json!({"message": "hello", "success": true}))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_call() {
+        let operator = MockDeviceOperator::default().with_response(
+            "org.rdk.System.1.getFriendlyName",
+            json!({"friendlyName": "Kitchen", "success": true}),
+        );
+        let client = ThunderPluginClient::new(ThunderPlugin::System, operator.clone());
+        let name: FriendlyName = client.call_without_params("getFriendlyName").await.unwrap();
+        assert_eq!(name.friendly_name, "Kitchen");
+        let result: Result<(), RippleError> = client
+            .call("setFriendlyName", &json!({"friendlyName": "Den"}))
+            .await;
+        assert!(result.is_ok());
+        assert_eq!(
+            operator.get_calls(),
+            vec![
+                ("org.rdk.System.1.getFriendlyName".to_owned(), None),
+                (
+                    "org.rdk.System.1.setFriendlyName".to_owned(),
+                    Some(json!({"friendlyName": "Den"}))
+                )
+            ]
+        );
+        let result: Result<FriendlyName, RippleError> =
+            client.call_without_params("getFriendlyName2").await;
+        assert_eq!(result, Err(RippleError::ParseError));
+    }
+
+    #[tokio::test]
+    async fn test_subscribe() {
+        let operator = MockDeviceOperator::default();
+        let client = ThunderPluginClient::new(ThunderPlugin::System, operator.clone());
+        let mut events = client
+            .subscribe::<FriendlyName>("onFriendlyNameChanged")
+            .await
+            .unwrap();
+        assert!(
+            operator
+                .emit("onFriendlyNameChanged", json!({"unexpected": true}))
+                .await
+        );
+        assert!(
+            operator
+                .emit("onFriendlyNameChanged", json!({"friendlyName": "Den"}))
+                .await
+        );
+        assert_eq!(
+            events.next().await,
+            Some(FriendlyName {
+                friendly_name: "Den".to_owned()
+            })
+        );
+        client.unsubscribe("onFriendlyNameChanged").await;
+        assert_eq!(events.next().await, None);
+    }
+}
@@ -22,6 +22,16 @@ pub mod client {
     pub mod thunder_async_client_plugins_status_mgr;
     pub mod thunder_client;
     pub mod thunder_plugin;
+    pub mod thunder_plugin_client;
+    pub mod plugins {
+        pub mod display_settings;
+        pub mod persistent_store;
+        pub mod rdk_shell;
+        pub mod system;
+        pub mod text_track;
+        pub mod user_settings;
+        pub mod wifi;
+    }
 }
 
 pub mod bootstrap {
@@ -61,6 +71,7 @@ pub mod tests {
         pub mod thunder_text_track_pacts;
         pub mod thunder_user_settings_pacts;
     }
+    pub mod mock_device_operator;
     pub mod mock_thunder_controller;
     pub mod thunder_client_pool_test_utility;
 }
@@ -97,6 +97,7 @@ fn get_event_name(event: &TelemetryPayload) -> &'static str {
         TelemetryPayload::InternalInitialize(_) => "app_internal_initialize_split",
         TelemetryPayload::FireboltInteraction(_) => "app_firebolt_split",
         TelemetryPayload::FireboltEvent(_) => "app_firebolt_event_split",
+        TelemetryPayload::AppEviction(_) => "app_eviction_split",
     }
 }
 
@@ -0,0 +1,114 @@
+// Copyright 2025 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use ripple_sdk::{
+    async_trait::async_trait,
+    tokio::sync::{mpsc, oneshot::error::RecvError},
+};
+use serde_json::Value;
+
+use crate::client::device_operator::{
+    DeviceCallRequest, DeviceOperator, DeviceResponseMessage, DeviceSubscribeRequest,
+    DeviceUnsubscribeRequest,
+};
+
+/*
+Device operator answering from canned responses by method, for testing code written against
+`DeviceOperator` without a Thunder connection. Methods without a response get a null result like
+a Thunder setter, and `emit` sends an event to the handler subscribed to it.
+*/
+#[derive(Debug, Clone, Default)]
+pub struct MockDeviceOperator {
+    responses: Arc<Mutex<HashMap<String, Value>>>,
+    calls: Arc<Mutex<Vec<DeviceCallRequest>>>,
+    handlers: Arc<Mutex<HashMap<String, mpsc::Sender<DeviceResponseMessage>>>>,
+}
+
+impl MockDeviceOperator {
+    pub fn with_response(self, method: &str, response: Value) -> Self {
+        self.responses
+            .lock()
+            .unwrap()
+            .insert(method.to_owned(), response);
+        self
+    }
+
+    /// Methods called so far with their params.
+    pub fn get_calls(&self) -> Vec<(String, Option<Value>)> {
+        self.calls
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|c| {
+                (
+                    c.method.clone(),
+                    c.params
+                        .as_ref()
+                        .and_then(|p| serde_json::from_str(&p.as_params()).ok()),
+                )
+            })
+            .collect()
+    }
+
+    /// Sends the event params to the subscriber of the event, false if there is none.
+    pub async fn emit(&self, event: &str, params: Value) -> bool {
+        let handler = self.handlers.lock().unwrap().get(event).cloned();
+        match handler {
+            Some(handler) => handler
+                .send(DeviceResponseMessage::new(params, None))
+                .await
+                .is_ok(),
+            None => false,
+        }
+    }
+}
+
+#[async_trait]
+impl DeviceOperator for MockDeviceOperator {
+    async fn call(&self, request: DeviceCallRequest) -> DeviceResponseMessage {
+        let response = self
+            .responses
+            .lock()
+            .unwrap()
+            .get(&request.method)
+            .cloned()
+            .unwrap_or(Value::Null);
+        self.calls.lock().unwrap().push(request);
+        DeviceResponseMessage::call(response)
+    }
+
+    async fn subscribe(
+        &self,
+        request: DeviceSubscribeRequest,
+        handler: mpsc::Sender<DeviceResponseMessage>,
+    ) -> Result<DeviceResponseMessage, RecvError> {
+        self.handlers
+            .lock()
+            .unwrap()
+            .insert(request.event_name, handler);
+        Ok(DeviceResponseMessage::call(Value::from(0)))
+    }
+
+    async fn unsubscribe(&self, request: DeviceUnsubscribeRequest) {
+        self.handlers.lock().unwrap().remove(&request.event_name);
+    }
+}